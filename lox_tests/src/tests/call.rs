@@ -18,23 +18,23 @@ use rlox_core::error::LoxError;
 test_fail!(
     bool,
     "../test-scripts/call/bool.lox",
-    LoxError::RuntimeError(String::from("true"), 1, String::from(""))
+    LoxError::runtime_error("true", 1)
 );
 
 test_fail!(
     nil,
     "../test-scripts/call/nil.lox",
-    LoxError::RuntimeError(String::from("Nil"), 1, String::from(""))
+    LoxError::runtime_error("Nil", 1)
 );
 
 test_fail!(
     string,
     "../test-scripts/call/string.lox",
-    LoxError::RuntimeError(String::from("str"), 1, String::from(""))
+    LoxError::runtime_error("str", 1)
 );
 
 test_fail!(
     object,
     "../test-scripts/call/object.lox",
-    LoxError::RuntimeError(String::from("Instance<Foo>"), 4, String::from(""))
+    LoxError::runtime_error("Instance<Foo>", 4)
 );
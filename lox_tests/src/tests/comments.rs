@@ -32,3 +32,25 @@ test_succeed!(
 );
 
 test_succeed!(unicode, ".././test-scripts/comments/unicode.lox", "ok");
+
+test_succeed!(
+    block,
+    ".././test-scripts/comments/block.lox",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e"
+);
+
+test_fail!(
+    block_line,
+    ".././test-scripts/comments/block_line.lox",
+    LoxError::parser_error(";", 3)
+);
+
+test_fail!(
+    unterminated_block,
+    ".././test-scripts/comments/unterminated_block.lox",
+    LoxError::scanner_error("/*", 2)
+);
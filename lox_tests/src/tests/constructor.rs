@@ -50,7 +50,7 @@ test_succeed!(
 test_fail!(
     default_arguments,
     ".././test-scripts/constructor/default_arguments.lox",
-    LoxError::RuntimeError(String::from("Foo"), 3, String::from(""))
+    LoxError::runtime_error("Foo", 3)
 );
 
 test_succeed!(
@@ -63,7 +63,7 @@ test_succeed!(
 test_fail!(
     extra_arguments,
     ".././test-scripts/constructor/extra_arguments.lox",
-    LoxError::RuntimeError(String::from("Foo"), 8, String::from(""))
+    LoxError::runtime_error("Foo", 8)
 );
 
 test_succeed!(
@@ -75,7 +75,7 @@ test_succeed!(
 test_fail!(
     missing_arguments,
     ".././test-scripts/constructor/missing_arguments.lox",
-    LoxError::RuntimeError(String::from("Foo"), 5, String::from(""))
+    LoxError::runtime_error("Foo", 5)
 );
 
 test_succeed!(
@@ -88,5 +88,5 @@ test_succeed!(
 test_fail!(
     return_value,
     ".././test-scripts/constructor/return_value.lox",
-    LoxError::SemanticError(String::from("return"), 3, String::from(""))
+    LoxError::semantic_error("return", 3)
 );
@@ -18,25 +18,32 @@ use rlox_core::error::LoxError;
 test_fail!(
     class,
     ".././test-scripts/continue/class.lox",
-    LoxError::ParserError(String::from("continue"), 2, String::from(""))
+    LoxError::parser_error("continue", 2)
 );
 
 test_fail!(
     function,
     ".././test-scripts/continue/function.lox",
-    LoxError::Continue(2)
+    LoxError::semantic_error("continue", 2)
 );
 
 test_fail!(
     global_scope,
     ".././test-scripts/continue/global_scope.lox",
-    LoxError::Continue(1)
+    LoxError::semantic_error("continue", 1)
 );
 
 test_fail!(
     local_scope,
     ".././test-scripts/continue/local_scope.lox",
-    LoxError::Continue(2)
+    LoxError::semantic_error("continue", 2)
 );
 
 test_succeed!(loops, ".././test-scripts/continue/loop.lox", 5, 5);
+
+test_succeed!(
+    last_iteration,
+    ".././test-scripts/continue/last_iteration.lox",
+    1,
+    2
+);
@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+use rlox_core::diagnostics::{Diagnostic, Format};
+use rlox_vm::compiler::{run_file, Session};
+use rlox_vm::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::rc::Rc;
+
+fn diagnose(path: &str) -> Diagnostic {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let err = run_file(
+        path,
+        SystemInterfaceMock {
+            print_cache: Rc::clone(&print_cache),
+        },
+    )
+    .unwrap_err()
+    .remove(0);
+    Diagnostic::from_error(&err, &read_to_string(path).unwrap()).file("script.lox")
+}
+
+#[test]
+fn runtime_error() {
+    let diagnostic = diagnose(".././test-scripts/diagnostics/operands.lox");
+    assert_eq!(diagnostic.code, "E0301");
+    assert_eq!(
+        diagnostic.span.map(|span| (span.line, span.column)),
+        Some((2, 12))
+    );
+    let notes: Vec<_> = diagnostic.notes.iter().map(|note| &note.message).collect();
+    assert_eq!(notes, vec!["[line 2] in half()", "[line 5] in script"]);
+}
+
+#[test]
+fn redeclared() {
+    let diagnostic = diagnose(".././test-scripts/diagnostics/redeclared.lox");
+    assert_eq!(diagnostic.kind, "SemanticError");
+    assert_eq!(diagnostic.code, "E0201");
+    assert_eq!(diagnostic.notes[0].message, "`count` first declared here");
+    assert_eq!(diagnostic.notes[0].span.map(|span| span.line), Some(2));
+}
+
+#[test]
+fn code_from_where_raised() {
+    let diagnostic = diagnose(".././test-scripts/match/not_a_class.lox");
+    assert_eq!(diagnostic.code, "E0320");
+    assert_eq!(diagnostic.help, None);
+}
+
+#[test]
+fn inner_scope_redeclared() {
+    let source = "fun g() {\n  var a = 1;\n  { var a = 2; }\n  var a = 3;\n}";
+    let mut session = Session::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    });
+    let err = session.run_line(source).unwrap_err().remove(0);
+    let diagnostic = Diagnostic::from_error(&err, source);
+    assert_eq!(diagnostic.span.map(|span| span.line), Some(4));
+    assert_eq!(diagnostic.notes[0].span.map(|span| span.line), Some(2));
+}
+
+#[test]
+fn operator_span() {
+    let mut session = Session::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    });
+    let err = session.run_line("print 2**64 & 1;").unwrap_err().remove(0);
+    let diagnostic = Diagnostic::from_error(&err, "print 2**64 & 1;");
+    assert_eq!(diagnostic.code, "E0315");
+    assert_eq!(
+        diagnostic.span.map(|span| (span.column, span.end_column)),
+        Some((13, 14))
+    );
+}
+
+#[test]
+fn module() {
+    let path = ".././test-scripts/diagnostics/module.lox";
+    let mut session = Session::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    });
+    let err = session.run_file(path).unwrap_err().remove(0);
+    let diagnostic = session.diagnostic(&err, Some("script.lox"), &read_to_string(path).unwrap());
+    assert!(diagnostic.file.unwrap().ends_with("lib/broken.lox"));
+    assert_eq!(
+        diagnostic.span.map(|span| (span.line, span.column)),
+        Some((2, 13))
+    );
+    let notes: Vec<_> = diagnostic.notes.iter().map(|note| &note.message).collect();
+    assert_eq!(
+        notes,
+        vec!["[line 2] in module lib/broken.lox", "[line 2] in script"]
+    );
+}
+
+#[test]
+fn earlier_input() {
+    let mut session = Session::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    });
+    session
+        .run_line("fun f() {\n\n\n  return 1 + nil; }")
+        .unwrap();
+    let err = session.run_line("f();").unwrap_err().remove(0);
+    let rendered = session.diagnostic(&err, None, "f();").render(Format::Plain);
+    assert!(rendered.contains("4 |   return 1 + nil; }\n  |            ^\n"));
+}
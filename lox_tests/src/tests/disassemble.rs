@@ -0,0 +1,56 @@
+#![cfg(test)]
+
+use rlox_vm::compiler::disassemble_file;
+
+fn listing(path: &str) -> Vec<String> {
+    disassemble_file(path)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn closure() {
+    let lines = listing(".././test-scripts/disassemble/closure.lox");
+    assert_eq!(lines[0], "== <script> ==");
+    assert!(lines.contains(&"0000    1 Closure            31 <fn outer>".to_string()));
+    assert!(lines.contains(&"0005    | Call                1 args".to_string()));
+    assert!(lines.contains(&"== outer ==".to_string()));
+    assert!(lines.contains(&"0000    2 Constant           29 2".to_string()));
+    assert!(lines
+        .contains(&"0001    3 Closure            30 <fn inner> [local 1] [local 2]".to_string()));
+    assert!(lines.contains(&"== inner ==".to_string()));
+    assert!(lines.contains(&"0000    4 GetUpvalue          0 upvalue".to_string()));
+}
+
+#[test]
+fn jumps() {
+    let lines = listing(".././test-scripts/disassemble/jumps.lox");
+    assert!(lines.contains(&"0008    | JumpIfFalse        -> 0016".to_string()));
+    assert!(lines.contains(&"0015    2 Jump               -> 0005".to_string()));
+    assert!(lines.contains(&"0022    | Constant           38 'done'".to_string()));
+}
+
+#[test]
+fn handler() {
+    let lines = listing(".././test-scripts/disassemble/handler.lox");
+    assert!(lines.contains(&"0003    | Throw".to_string()));
+    assert_eq!(
+        lines.last().unwrap(),
+        "handler [0000, 0004) -> 0005 depth 0"
+    );
+}
+
+#[test]
+fn method() {
+    let lines = listing(".././test-scripts/disassemble/method.lox");
+    assert!(lines.contains(&"0000    1 ClassDef           29 'Greeter'".to_string()));
+    assert!(lines.contains(&"0005    | MethodDef          32 'greet'".to_string()));
+    assert!(lines.contains(&"== greet ==".to_string()));
+}
+
+#[test]
+fn missing_file() {
+    assert!(disassemble_file(".././test-scripts/disassemble/missing.lox").is_err());
+}
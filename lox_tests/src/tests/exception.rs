@@ -0,0 +1,106 @@
+use rlox_core::frontend::lexer::*;
+use rlox_core::frontend::parser::Parser;
+use rlox_core::frontend::resolver::Resolver;
+use rlox_core::runtime::interpreter::Interpreter;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+// use rlox_core::runtime::system_calls::SystemInterfaceMock;
+// use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+// use rlox_core::runtime::definitions::object::Object;
+use super::*;
+use rlox_core::error::LoxError;
+
+test_succeed!(
+    throw_catch,
+    ".././test-scripts/exception/throw_catch.lox",
+    "before",
+    "oops"
+);
+
+test_succeed!(
+    finally,
+    ".././test-scripts/exception/finally.lox",
+    "body",
+    "finally",
+    "caught",
+    "finally"
+);
+
+test_succeed!(
+    error_class,
+    ".././test-scripts/exception/error_class.lox",
+    "custom"
+);
+
+test_succeed!(
+    shadowed_class,
+    ".././test-scripts/exception/shadowed_class.lox",
+    "prelude's TypeError"
+);
+
+test_succeed!(
+    builtin_error,
+    ".././test-scripts/exception/builtin_error.lox",
+    "TypeError",
+    "Operands not Num or String"
+);
+
+test_succeed!(
+    index_error,
+    ".././test-scripts/exception/index_error.lox",
+    "IndexError"
+);
+
+test_succeed!(
+    across_calls,
+    ".././test-scripts/exception/across_calls.lox",
+    "deep",
+    "after"
+);
+
+test_succeed!(
+    nested,
+    ".././test-scripts/exception/nested.lox",
+    "inner finally",
+    "inner",
+    "second"
+);
+
+test_succeed!(
+    closure,
+    ".././test-scripts/exception/closure.lox",
+    2,
+    "captured"
+);
+
+test_succeed!(
+    finally_return,
+    ".././test-scripts/exception/finally_return.lox",
+    "cleanup",
+    "value",
+    1,
+    2
+);
+
+test_fail!(
+    uncaught,
+    ".././test-scripts/exception/uncaught.lox",
+    LoxError::runtime_error("Error", 2)
+);
+
+test_fail!(
+    rethrow,
+    ".././test-scripts/exception/rethrow.lox",
+    LoxError::runtime_error("+", 8)
+);
+
+test_fail!(
+    missing_catch,
+    ".././test-scripts/exception/missing_catch.lox",
+    LoxError::parser_error("print", 4)
+);
@@ -18,7 +18,7 @@ use rlox_core::error::LoxError;
 test_fail!(
     class_in_body,
     "../test-scripts/for/class_in_body.lox",
-    LoxError::ParserError(String::from("class"), 2, String::from(""))
+    LoxError::parser_error("class", 2)
 );
 
 test_succeed!(
@@ -35,7 +35,63 @@ test_succeed!(
 test_fail!(
     fun_in_body,
     "../test-scripts/for/fun_in_body.lox",
-    LoxError::ParserError(String::from("foo"), 2, String::from(""))
+    LoxError::parser_error("foo", 2)
+);
+
+test_succeed!(
+    in_break_continue,
+    "../test-scripts/for/in_break_continue.lox",
+    "found b after skipping 2",
+    "after",
+    "next"
+);
+
+test_succeed!(in_closure, "../test-scripts/for/in_closure.lox", "a", "b");
+
+test_succeed!(
+    in_iterator,
+    "../test-scripts/for/in_iterator.lox",
+    10,
+    "00",
+    "01",
+    "10",
+    "11"
+);
+
+test_succeed!(
+    in_list,
+    "../test-scripts/for/in_list.lox",
+    6,
+    "a",
+    "b",
+    "changed"
+);
+
+test_succeed!(
+    in_map,
+    "../test-scripts/for/in_map.lox",
+    "ann is 30",
+    "bob is 25"
+);
+
+test_fail!(
+    in_missing_paren,
+    "../test-scripts/for/in_missing_paren.lox",
+    LoxError::parser_error("print", 1)
+);
+
+test_fail!(
+    in_not_iterable,
+    "../test-scripts/for/in_not_iterable.lox",
+    LoxError::runtime_error("for", 1)
+);
+
+test_succeed!(
+    in_string,
+    "../test-scripts/for/in_string.lox",
+    "a",
+    "b",
+    "c"
 );
 
 test_succeed!(
@@ -51,19 +107,19 @@ test_succeed!(scope, "../test-scripts/for/scope.lox", 0, -1, "after", 0);
 test_fail!(
     statement_condition,
     "../test-scripts/for/statement_condition.lox",
-    LoxError::ParserError(String::from("{"), 3, String::from(""))
+    LoxError::parser_error("print", 3)
 );
 
 test_fail!(
     statement_increment,
     "../test-scripts/for/statement_increment.lox",
-    LoxError::ParserError(String::from("{"), 2, String::from(""))
+    LoxError::parser_error("print", 2)
 );
 
 test_fail!(
     statement_initializer,
     "../test-scripts/for/statement_initializer.lox",
-    LoxError::ParserError(String::from("{"), 3, String::from(""))
+    LoxError::parser_error("print", 3)
 );
 
 test_succeed!(
@@ -88,5 +144,5 @@ test_succeed!(
 test_fail!(
     var_in_body,
     "../test-scripts/for/var_in_body.lox",
-    LoxError::ParserError(String::from("var"), 2, String::from(""))
+    LoxError::parser_error("var", 2)
 );
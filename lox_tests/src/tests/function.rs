@@ -18,7 +18,7 @@ use rlox_core::error::LoxError;
 test_fail!(
     body_must_be_block,
     "../test-scripts/function/body_must_be_block.lox",
-    LoxError::ParserError(String::from("123"), 3, String::from(""))
+    LoxError::parser_error("123", 3)
 );
 
 test_succeed!(
@@ -30,13 +30,13 @@ test_succeed!(
 test_fail!(
     extra_arguments,
     "../test-scripts/function/extra_arguments.lox",
-    LoxError::RuntimeError(String::from("f"), 6, String::from(""))
+    LoxError::runtime_error("f", 6)
 );
 
 test_fail!(
     local_mutual_recursion,
     "../test-scripts/function/local_mutual_recursion.lox",
-    LoxError::RuntimeError(String::from("isOdd"), 4, String::from(""))
+    LoxError::runtime_error("isOdd", 4)
 );
 
 test_succeed!(
@@ -48,13 +48,13 @@ test_succeed!(
 test_fail!(
     missing_arguments,
     "../test-scripts/function/missing_arguments.lox",
-    LoxError::RuntimeError(String::from("f"), 3, String::from(""))
+    LoxError::runtime_error("f", 3)
 );
 
 test_fail!(
     missing_comma_in_parameters,
     "../test-scripts/function/missing_comma_in_parameters.lox",
-    LoxError::ParserError(String::from("c"), 3, String::from(""))
+    LoxError::parser_error("c", 3)
 );
 
 test_succeed!(
@@ -18,13 +18,13 @@ use rlox_core::error::LoxError;
 test_fail!(
     class_in_else,
     ".././test-scripts/if/class_in_else.lox",
-    LoxError::ParserError(String::from("class"), 2, String::from(""))
+    LoxError::parser_error("class", 2)
 );
 
 test_fail!(
     class_in_then,
     ".././test-scripts/if/class_in_then.lox",
-    LoxError::ParserError(String::from("class"), 2, String::from(""))
+    LoxError::parser_error("class", 2)
 );
 
 test_succeed!(
@@ -44,13 +44,13 @@ test_succeed!(
 test_fail!(
     fun_in_else,
     ".././test-scripts/if/fun_in_else.lox",
-    LoxError::ParserError(String::from("foo"), 2, String::from(""))
+    LoxError::parser_error("foo", 2)
 );
 
 test_fail!(
     fun_in_then,
     ".././test-scripts/if/fun_in_then.lox",
-    LoxError::ParserError(String::from("foo"), 2, String::from(""))
+    LoxError::parser_error("foo", 2)
 );
 
 test_succeed!(if_st, ".././test-scripts/if/if.lox", "good", "block", true);
@@ -60,11 +60,11 @@ test_succeed!(truth, ".././test-scripts/if/truth.lox", "false", true);
 test_fail!(
     var_in_else,
     ".././test-scripts/if/var_in_else.lox",
-    LoxError::ParserError(String::from("var"), 2, String::from(""))
+    LoxError::parser_error("var", 2)
 );
 
 test_fail!(
     var_in_then,
     ".././test-scripts/if/var_in_then.lox",
-    LoxError::ParserError(String::from("var"), 2, String::from(""))
+    LoxError::parser_error("var", 2)
 );
@@ -18,7 +18,7 @@ use rlox_core::error::LoxError;
 test_fail!(
     body_must_be_block,
     "../test-scripts/lambda/body_must_be_block.lox",
-    LoxError::ParserError(String::from("123"), 3, String::from(""))
+    LoxError::parser_error("123", 3)
 );
 
 test_succeed!(
@@ -30,13 +30,13 @@ test_succeed!(
 test_fail!(
     extra_arguments,
     "../test-scripts/lambda/extra_arguments.lox",
-    LoxError::RuntimeError(String::from("f"), 6, String::from(""))
+    LoxError::runtime_error("f", 6)
 );
 
 test_fail!(
     local_recursion,
     "../test-scripts/lambda/local_recursion.lox",
-    LoxError::RuntimeError(String::from("f"), 6, String::from(""))
+    LoxError::runtime_error("f", 6)
 );
 
 test_succeed!(
@@ -38,23 +38,23 @@ test_succeed!(
 test_fail!(
     extra_arguments,
     ".././test-scripts/method/extra_arguments.lox",
-    LoxError::RuntimeError(String::from("method"), 8, String::from(""))
+    LoxError::runtime_error("method", 8)
 );
 
 test_fail!(
     missing_arguments,
     ".././test-scripts/method/missing_arguments.lox",
-    LoxError::RuntimeError(String::from("method"), 5, String::from(""))
+    LoxError::runtime_error("method", 5)
 );
 
 test_fail!(
     not_found,
     ".././test-scripts/method/not_found.lox",
-    LoxError::RuntimeError(String::from("unknown"), 3, String::from(""))
+    LoxError::runtime_error("unknown", 3)
 );
 
 test_fail!(
     refer_to_name,
     ".././test-scripts/method/refer_to_name.lox",
-    LoxError::SemanticError(String::from("method"), 3, String::from(""))
+    LoxError::semantic_error("method", 3)
 );
@@ -13,14 +13,16 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 // // use rlox_core::runtime::definitions::object::Object;
-use rlox_core::error::LoxError;
+use rlox_core::error::{Error, LoxError};
 use rlox_vm::{chunk::VM, compiler::run_file};
 
 fn run_script(path: &str, interpreter: &mut Interpreter) -> Result<(), LoxError> {
     let path = Path::new(path);
     let script = read_to_string(path).unwrap();
     let mut ast = Parser::new(Lexer::new().parse(&script)?).parse()?;
-    Resolver::new().resolve(&mut ast)?;
+    Resolver::new()
+        .resolve_all(&mut ast)
+        .map_err(|mut errors| errors.remove(0))?;
     interpreter.interpret(&mut ast)?;
     Ok(())
 }
@@ -106,24 +108,15 @@ macro_rules! test_fail {
             use rlox_vm::system_calls::SystemInterfaceMock;
             let print_cache = Rc::new(RefCell::new(vec![]));
             // let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock{print_cache: Rc::clone(&print_cache)})));
-            if let Err(err) = run_file(
+            if let Err(mut errors) = run_file(
                 $file_path,
                 SystemInterfaceMock {
                     print_cache: Rc::clone(&print_cache),
                 },
             ) {
-                use LoxError::*;
-                match (err.clone(), $err_val) {
-                    (ScannerError(_, _, _), ScannerError(_, _, _)) => {}
-                    (ParserError(_, _, _), ParserError(_, _, _)) => {}
-                    (RuntimeError(_, _, _), RuntimeError(_, _, _)) => {}
-                    (SemanticError(_, _, _), SemanticError(_, _, _)) => {}
-                    (Break(line1), Break(line2)) if line1 == line2 => {}
-                    (Continue(line1), Continue(line2)) if line1 == line2 => {}
-                    (ReturnVal(_, line1), ReturnVal(_, line2)) if line1 == line2 => {}
-                    _ => {
-                        panic!("unhandled error {:?}", err)
-                    }
+                let err = errors.remove(0);
+                if err.kind != Error::from($err_val).kind {
+                    panic!("unhandled error {:?}", err)
                 }
                 return;
             }
@@ -144,6 +137,7 @@ macro_rules! test_fail {
 // }
 
 mod assignment;
+mod bigint;
 mod block;
 mod bool;
 mod break_stmt;
@@ -153,23 +147,36 @@ mod closure;
 mod comments;
 mod constructor;
 mod continue_stmt;
+mod diagnostics;
+mod disassemble;
+mod exception;
 mod field;
 mod for_stmt;
 mod function;
+mod generator;
 mod if_stmt;
 mod inheritance;
+mod int;
 mod lambda;
+mod list;
 mod logical_operator;
+mod map;
+mod match_stmt;
 mod method;
 mod miscellaneous;
+mod module;
 mod nil;
 mod number;
 mod operator;
 mod print;
+mod recovery;
 mod regression;
+mod repl;
 mod return_stmt;
+mod stacktrace;
 mod string;
 mod super_stmt;
 mod this;
+mod trace;
 mod variable;
 mod while_stmt;
@@ -0,0 +1,141 @@
+use rlox_core::frontend::lexer::*;
+use rlox_core::frontend::parser::Parser;
+use rlox_core::frontend::resolver::Resolver;
+use rlox_core::runtime::interpreter::Interpreter;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+// use rlox_core::runtime::system_calls::SystemInterfaceMock;
+// use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+// use rlox_core::runtime::definitions::object::Object;
+use super::*;
+use rlox_core::error::LoxError;
+use rlox_vm::compiler::run_file;
+use rlox_vm::system_calls::SystemInterfaceMock;
+
+test_succeed!(
+    import_as,
+    ".././test-scripts/module/import_as.lox",
+    "math loaded",
+    3,
+    16,
+    3
+);
+
+test_succeed!(
+    shadowed_prelude,
+    ".././test-scripts/module/shadowed_prelude.lox",
+    1,
+    "math loaded",
+    3,
+    "Index out of bounds"
+);
+
+test_succeed!(
+    from_import,
+    ".././test-scripts/module/from_import.lox",
+    "math loaded",
+    9
+);
+
+test_succeed!(
+    once,
+    ".././test-scripts/module/once.lox",
+    "math loaded",
+    true
+);
+
+test_succeed!(
+    isolation,
+    ".././test-scripts/module/isolation.lox",
+    1,
+    2,
+    100
+);
+
+test_succeed!(
+    relative,
+    ".././test-scripts/module/relative.lox",
+    "hello inner",
+    "inner"
+);
+
+test_succeed!(
+    prelude,
+    ".././test-scripts/module/prelude.lox",
+    1,
+    "empty list"
+);
+
+test_succeed!(live, ".././test-scripts/module/live.lox", 0, 1, 11);
+
+test_fail!(
+    cycle,
+    ".././test-scripts/module/cycle.lox",
+    LoxError::runtime_error("cycle_a.lox", 1)
+);
+
+test_fail!(
+    self_import,
+    ".././test-scripts/module/self_import.lox",
+    LoxError::runtime_error("self_import.lox", 1)
+);
+
+test_fail!(
+    missing,
+    ".././test-scripts/module/missing.lox",
+    LoxError::runtime_error("lib/nope.lox", 1)
+);
+
+test_fail!(
+    missing_member,
+    ".././test-scripts/module/missing_member.lox",
+    LoxError::runtime_error("cube", 1)
+);
+
+test_fail!(
+    in_block,
+    ".././test-scripts/module/in_block.lox",
+    LoxError::semantic_error("import", 2)
+);
+
+#[test]
+fn cycle_chain() {
+    let err = run_file(
+        ".././test-scripts/module/cycle.lox",
+        SystemInterfaceMock {
+            print_cache: Rc::new(RefCell::new(vec![])),
+        },
+    )
+    .unwrap_err()
+    .remove(0);
+    assert_eq!(
+        err.message,
+        "Import cycle detected: cycle_a.lox -> cycle_b.lox -> cycle_a.lox"
+    );
+    let span = err.span.unwrap();
+    assert_eq!((span.file_id, span.line, span.column), (2, 1, 1));
+}
+
+#[test]
+fn missing_after_print() {
+    use rlox_vm::chunk::Object;
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let err = run_file(
+        ".././test-scripts/module/missing_after_print.lox",
+        SystemInterfaceMock {
+            print_cache: Rc::clone(&print_cache),
+        },
+    )
+    .unwrap_err()
+    .remove(0);
+    assert_eq!(err.message, "Module not found");
+    assert_eq!(
+        *print_cache.borrow(),
+        vec![Object::Str("before".to_string())]
+    );
+}
@@ -20,37 +20,37 @@ test_succeed!(add, ".././test-scripts/operator/add.lox", 579, "string");
 test_fail!(
     add_bool_nil,
     ".././test-scripts/operator/add_bool_nil.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_fail!(
     add_bool_num,
     ".././test-scripts/operator/add_bool_num.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_fail!(
     add_bool_string,
     ".././test-scripts/operator/add_bool_string.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_fail!(
     add_nil_nil,
     ".././test-scripts/operator/add_nil_nil.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_fail!(
     add_num_nil,
     ".././test-scripts/operator/add_num_nil.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_fail!(
     add_string_nil,
     ".././test-scripts/operator/add_string_nil.lox",
-    LoxError::RuntimeError(String::from("+"), 1, String::from(""))
+    LoxError::runtime_error("+", 1)
 );
 
 test_succeed!(
@@ -83,13 +83,13 @@ test_succeed!(divide, ".././test-scripts/operator/divide.lox", 4, 1);
 test_fail!(
     divide_nonnum_num,
     ".././test-scripts/operator/divide_nonnum_num.lox",
-    LoxError::RuntimeError(String::from("/"), 1, String::from(""))
+    LoxError::runtime_error("/", 1)
 );
 
 test_fail!(
     divide_num_nonnum,
     ".././test-scripts/operator/divide_num_nonnum.lox",
-    LoxError::RuntimeError(String::from("/"), 1, String::from(""))
+    LoxError::runtime_error("/", 1)
 );
 
 test_succeed!(
@@ -123,49 +123,49 @@ test_succeed!(
 test_fail!(
     greater_nonnum_num,
     ".././test-scripts/operator/greater_nonnum_num.lox",
-    LoxError::RuntimeError(String::from(">"), 1, String::from(""))
+    LoxError::runtime_error(">", 1)
 );
 
 test_fail!(
     greater_num_nonnum,
     ".././test-scripts/operator/greater_num_nonnum.lox",
-    LoxError::RuntimeError(String::from(">"), 1, String::from(""))
+    LoxError::runtime_error(">", 1)
 );
 
 test_fail!(
     greater_or_equal_nonnum_num,
     ".././test-scripts/operator/greater_or_equal_nonnum_num.lox",
-    LoxError::RuntimeError(String::from(">="), 1, String::from(""))
+    LoxError::runtime_error(">=", 1)
 );
 
 test_fail!(
     greater_or_equal_num_nonnum,
     ".././test-scripts/operator/greater_or_equal_num_nonnum.lox",
-    LoxError::RuntimeError(String::from(">="), 1, String::from(""))
+    LoxError::runtime_error(">=", 1)
 );
 
 test_fail!(
     less_nonnum_num,
     ".././test-scripts/operator/less_nonnum_num.lox",
-    LoxError::RuntimeError(String::from("<"), 1, String::from(""))
+    LoxError::runtime_error("<", 1)
 );
 
 test_fail!(
     less_num_nonnum,
     ".././test-scripts/operator/less_num_nonnum.lox",
-    LoxError::RuntimeError(String::from("<"), 1, String::from(""))
+    LoxError::runtime_error("<", 1)
 );
 
 test_fail!(
     less_or_equal_nonnum_num,
     ".././test-scripts/operator/less_or_equal_nonnum_num.lox",
-    LoxError::RuntimeError(String::from("<="), 1, String::from(""))
+    LoxError::runtime_error("<=", 1)
 );
 
 test_fail!(
     less_or_equal_num_nonnum,
     ".././test-scripts/operator/less_or_equal_num_nonnum.lox",
-    LoxError::RuntimeError(String::from("<="), 1, String::from(""))
+    LoxError::runtime_error("<=", 1)
 );
 
 test_succeed!(
@@ -178,13 +178,104 @@ test_succeed!(
 test_fail!(
     multiply_nonnum_num,
     ".././test-scripts/operator/multiply_nonnum_num.lox",
-    LoxError::RuntimeError(String::from("*"), 1, String::from(""))
+    LoxError::runtime_error("*", 1)
 );
 
 test_fail!(
     multiply_num_nonnum,
     ".././test-scripts/operator/multiply_num_nonnum.lox",
-    LoxError::RuntimeError(String::from("*"), 1, String::from(""))
+    LoxError::runtime_error("*", 1)
+);
+
+test_succeed!(
+    power,
+    ".././test-scripts/operator/power.lox",
+    1024,
+    512,
+    -4,
+    -8,
+    0.5,
+    6.25,
+    2,
+    12,
+    "18446744073709551616"
+);
+
+test_fail!(
+    power_too_large,
+    ".././test-scripts/operator/power_too_large.lox",
+    LoxError::runtime_error("**", 2)
+);
+
+test_succeed!(
+    modulo,
+    ".././test-scripts/operator/modulo.lox",
+    1,
+    2,
+    -2,
+    -1,
+    1.5,
+    0.5,
+    3
+);
+
+test_fail!(
+    modulo_by_zero,
+    ".././test-scripts/operator/modulo_by_zero.lox",
+    LoxError::runtime_error("%", 2)
+);
+
+test_succeed!(
+    conditional,
+    ".././test-scripts/operator/conditional.lox",
+    "yes",
+    "no",
+    2,
+    "positive",
+    "else",
+    "else",
+    "or"
+);
+
+test_fail!(
+    conditional_missing_colon,
+    ".././test-scripts/operator/conditional_missing_colon.lox",
+    LoxError::parser_error(";", 1)
+);
+
+test_succeed!(
+    coalesce,
+    ".././test-scripts/operator/coalesce.lox",
+    "default",
+    "value",
+    false,
+    0,
+    "last",
+    1,
+    3,
+    3,
+    true
+);
+
+test_succeed!(
+    optional_chain,
+    ".././test-scripts/operator/optional_chain.lox",
+    1,
+    2,
+    Object::Nil,
+    "node 1",
+    Object::Nil,
+    Object::Nil,
+    "fallback",
+    Object::Nil,
+    2,
+    "none"
+);
+
+test_fail!(
+    optional_chain_assign,
+    ".././test-scripts/operator/optional_chain_assign.lox",
+    LoxError::runtime_error("=", 2)
 );
 
 test_succeed!(negate, ".././test-scripts/operator/negate.lox", -3, 3, -3);
@@ -192,7 +283,7 @@ test_succeed!(negate, ".././test-scripts/operator/negate.lox", -3, 3, -3);
 test_fail!(
     negate_nonnum,
     ".././test-scripts/operator/negate_nonnum.lox",
-    LoxError::RuntimeError(String::from("-"), 1, String::from(""))
+    LoxError::runtime_error("-", 1)
 );
 
 test_succeed!(not, ".././test-scripts/operator/not.lox", false, true, true);
@@ -200,7 +291,7 @@ test_succeed!(not, ".././test-scripts/operator/not.lox", false, true, true);
 test_fail!(
     not_class,
     ".././test-scripts/operator/not_class.lox",
-    LoxError::RuntimeError(String::from("*"), 2, String::from(""))
+    LoxError::runtime_error("*", 2)
 );
 
 test_succeed!(
@@ -223,11 +314,11 @@ test_succeed!(subtract, ".././test-scripts/operator/subtract.lox", 1, 0);
 test_fail!(
     subtract_nonnum_num,
     ".././test-scripts/operator/subtract_nonnum_num.lox",
-    LoxError::RuntimeError(String::from("-"), 1, String::from(""))
+    LoxError::runtime_error("-", 1)
 );
 
 test_fail!(
     subtract_num_nonnum,
     ".././test-scripts/operator/subtract_num_nonnum.lox",
-    LoxError::RuntimeError(String::from("-"), 1, String::from(""))
+    LoxError::runtime_error("-", 1)
 );
@@ -0,0 +1,55 @@
+#![cfg(test)]
+
+use rlox_core::error::{Error, ErrorKind};
+use rlox_vm::compiler::run_file;
+use rlox_vm::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::*;
+
+fn errors(path: &str) -> Vec<Error> {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    run_file(
+        path,
+        SystemInterfaceMock {
+            print_cache: Rc::clone(&print_cache),
+        },
+    )
+    .unwrap_err()
+}
+
+fn lines(errors: &[Error]) -> Vec<u32> {
+    errors
+        .iter()
+        .map(|err| match err.kind {
+            ErrorKind::Runtime => panic!("unexpected error {:?}", err),
+            _ => err.line_no,
+        })
+        .collect()
+}
+
+#[test]
+fn syntax_errors() {
+    let errors = errors(".././test-scripts/recovery/syntax.lox");
+    assert_eq!(lines(&errors), vec![1, 4, 7]);
+}
+
+#[test]
+fn scanner_errors() {
+    let errors = errors(".././test-scripts/recovery/scanner.lox");
+    assert!(errors.iter().all(|err| err.kind == ErrorKind::Scanner));
+    assert_eq!(lines(&errors), vec![1, 2, 4, 5]);
+}
+
+#[test]
+fn semantic_errors() {
+    let errors = errors(".././test-scripts/recovery/semantic.lox");
+    assert_eq!(lines(&errors), vec![1, 4, 6, 7, 10]);
+}
+
+test_succeed!(
+    after_continue,
+    ".././test-scripts/recovery/after_continue.lox",
+    1.0
+);
@@ -0,0 +1,77 @@
+#![cfg(test)]
+
+use rlox_core::error::Error;
+use rlox_vm::chunk::Object;
+use rlox_vm::compiler::Session;
+use rlox_vm::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn session(lines: &[&str]) -> (Vec<Object>, Vec<Error>) {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut session = Session::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    });
+    let mut errors = vec![];
+    for line in lines {
+        if let Err(errs) = session.run_line(line) {
+            errors.extend(errs);
+        }
+    }
+    let printed = print_cache.borrow_mut().drain(..).collect();
+    (printed, errors)
+}
+
+#[test]
+fn keeps_globals() {
+    let (printed, errors) = session(&[
+        "var a = 1;",
+        "fun add(b) { return a + b; }",
+        "print add(2);",
+    ]);
+    assert_eq!(printed, vec![Object::Num(3.0)]);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn keeps_heap_objects() {
+    let (printed, errors) = session(&[
+        "fun counter() { var n = 0; fun inc() { n = n + 1; return n; } return inc; }",
+        "var inc = counter();",
+        "inc();",
+        "var list = [inc(), 5];",
+        "print list[0] + list[1];",
+    ]);
+    assert_eq!(printed, vec![Object::Num(1.0), Object::Num(7.0)]);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn echoes_bare_expression() {
+    let (printed, errors) = session(&["var a = 2;", "a * 3;", "a + 1", "print a;", "nil;"]);
+    assert_eq!(
+        printed,
+        vec![Object::Num(6.0), Object::Num(3.0), Object::Num(2.0)]
+    );
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn recovers_from_errors() {
+    let (printed, errors) = session(&[
+        "var a = 1;",
+        "{ var b = 1; var b = 2; }",
+        "print a + true;",
+        "var c = a + 1;",
+        "c",
+    ]);
+    assert_eq!(printed, vec![Object::Num(2.0)]);
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn multi_line_input() {
+    let (printed, errors) = session(&["fun f(x) {\n  return x + 1;\n}\n", "f(1)\n"]);
+    assert_eq!(printed, vec![Object::Num(2.0)]);
+    assert!(errors.is_empty());
+}
@@ -29,7 +29,7 @@ test_succeed!(
 test_fail!(
     at_top_level,
     ".././test-scripts/return/at_top_level.lox",
-    LoxError::SemanticError(String::from("return"), 1, String::from(""))
+    LoxError::semantic_error("return", 1)
 );
 
 test_succeed!(
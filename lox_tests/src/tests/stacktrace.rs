@@ -0,0 +1,66 @@
+#![cfg(test)]
+
+use rlox_core::frontend::lexer::*;
+use rlox_core::frontend::parser::Parser;
+use rlox_core::frontend::resolver::Resolver;
+use rlox_core::runtime::interpreter::Interpreter;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+// use rlox_core::runtime::system_calls::SystemInterfaceMock;
+// use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+// use rlox_core::runtime::definitions::object::Object;
+use super::*;
+use rlox_core::error::LoxError;
+
+fn uncaught(path: &str) -> String {
+    use rlox_vm::system_calls::SystemInterfaceMock;
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    run_file(
+        path,
+        SystemInterfaceMock {
+            print_cache: Rc::clone(&print_cache),
+        },
+    )
+    .unwrap_err()[0]
+        .to_string()
+}
+
+test_succeed!(
+    statement,
+    ".././test-scripts/stacktrace/statement.lox",
+    "[line 2] in inner()\n[line 6] in outer()\n[line 9] in script",
+    "[line 10] in script"
+);
+
+test_fail!(
+    caught,
+    ".././test-scripts/stacktrace/caught.lox",
+    LoxError::runtime_error("1", 10)
+);
+
+test_fail!(
+    native,
+    ".././test-scripts/stacktrace/native.lox",
+    LoxError::runtime_error("1", 2)
+);
+
+#[test]
+fn method_backtrace() {
+    assert_eq!(
+        uncaught(".././test-scripts/stacktrace/method.lox"),
+        "[RuntimeError:L8:Error] Uncaught Error: negative\n[line 8] in check()\n[line 4] in init()\n[line 12] in Lambda()\n[line 13] in script"
+    );
+}
+
+#[test]
+fn native_backtrace() {
+    assert_eq!(
+        uncaught(".././test-scripts/stacktrace/native.lox"),
+        "[RuntimeError:L2:1] len expects a Str, List or Map\n[line 2] in size()\n[line 4] in script"
+    );
+}
@@ -0,0 +1,94 @@
+#![cfg(test)]
+
+use rlox_vm::chunk::Object;
+use rlox_vm::compiler::trace_file;
+use rlox_vm::debug::Tracer;
+use rlox_vm::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+struct Sink(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn trace(tracer: fn(Tracer) -> Tracer) -> Vec<String> {
+    trace_script(
+        ".././test-scripts/trace/calls.lox",
+        Object::Num(3.0),
+        tracer,
+    )
+}
+
+fn trace_script(path: &str, expected: Object, tracer: fn(Tracer) -> Tracer) -> Vec<String> {
+    let buffer = Rc::new(RefCell::new(vec![]));
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    trace_file(
+        path,
+        SystemInterfaceMock {
+            print_cache: Rc::clone(&print_cache),
+        },
+        tracer(Tracer::new(Box::new(Sink(Rc::clone(&buffer))))),
+    )
+    .unwrap();
+    let printed: Vec<Object> = print_cache.borrow_mut().drain(..).collect();
+    assert_eq!(printed, vec![expected]);
+    let trace = String::from_utf8(buffer.borrow().clone()).unwrap();
+    trace.lines().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn every_instruction() {
+    let lines = trace(|tracer| tracer);
+    assert!(lines[0].starts_with("[1 <script>] 0000    1 Closure"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("[2 add] 0000    2 GetLocal")));
+    assert!(lines.last().unwrap().starts_with("[1 <script>]"));
+    assert!(lines.last().unwrap().contains("Exit"));
+}
+
+#[test]
+fn stack_snapshot() {
+    let lines = trace(|tracer| tracer);
+    let add = lines
+        .iter()
+        .find(|line| line.starts_with("[2 add] 0002    2 Add"))
+        .unwrap();
+    assert!(add.ends_with("[ | Closure<Some(\"add\")> 1 2 1 2 ]"));
+}
+
+#[test]
+fn filter_function() {
+    let lines = trace(|tracer| tracer.function("add"));
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|line| line.starts_with("[2 add]")));
+}
+
+#[test]
+fn filter_lines() {
+    let lines = trace(|tracer| tracer.lines(4..=4));
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|line| line.contains(" 4 ")));
+    assert!(lines.iter().all(|line| line.starts_with("[1 <script>]")));
+}
+
+#[test]
+fn filter_lines_first_instruction() {
+    // `var y = 2;` is a lone constant opening its function
+    let lines = trace_script(
+        ".././test-scripts/disassemble/closure.lox",
+        Object::Int(3),
+        |tracer| tracer.lines(2..=2),
+    );
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("[2 outer] 0000    2 Constant"));
+}
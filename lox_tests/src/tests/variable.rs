@@ -18,19 +18,19 @@ use rlox_core::error::LoxError;
 test_fail!(
     collide_with_parameter,
     ".././test-scripts/variable/collide_with_parameter.lox",
-    LoxError::SemanticError(String::from("a"), 2, String::from(""))
+    LoxError::semantic_error("a", 2)
 );
 
 test_fail!(
     duplicate_local,
     ".././test-scripts/variable/duplicate_local.lox",
-    LoxError::SemanticError(String::from("a"), 3, String::from(""))
+    LoxError::semantic_error("a", 3)
 );
 
 test_fail!(
     duplicate_parameter,
     ".././test-scripts/variable/duplicate_parameter.lox",
-    LoxError::SemanticError(String::from("arg"), 2, String::from(""))
+    LoxError::semantic_error("arg", 2)
 );
 
 test_succeed!(
@@ -92,13 +92,13 @@ test_succeed!(
 test_fail!(
     undefined_global,
     ".././test-scripts/variable/undefined_global.lox",
-    LoxError::SemanticError(String::from("notDefined"), 1, String::from(""))
+    LoxError::semantic_error("notDefined", 1)
 );
 
 test_fail!(
     undefined_local,
     ".././test-scripts/variable/undefined_local.lox",
-    LoxError::SemanticError(String::from("notDefined"), 2, String::from(""))
+    LoxError::semantic_error("notDefined", 2)
 );
 
 test_succeed!(
@@ -110,23 +110,23 @@ test_succeed!(
 test_fail!(
     use_false_as_var,
     ".././test-scripts/variable/use_false_as_var.lox",
-    LoxError::ParserError(String::from("false"), 2, String::from(""))
+    LoxError::parser_error("false", 2)
 );
 
 test_fail!(
     use_local_in_initializer,
     ".././test-scripts/variable/use_local_in_initializer.lox",
-    LoxError::SemanticError(String::from("a"), 3, String::from(""))
+    LoxError::semantic_error("a", 3)
 );
 
 test_fail!(
     use_nil_as_var,
     ".././test-scripts/variable/use_nil_as_var.lox",
-    LoxError::ParserError(String::from("nil"), 2, String::from(""))
+    LoxError::parser_error("nil", 2)
 );
 
 test_fail!(
     use_this_as_var,
     ".././test-scripts/variable/use_this_as_var.lox",
-    LoxError::ParserError(String::from("this"), 2, String::from(""))
+    LoxError::parser_error("this", 2)
 );
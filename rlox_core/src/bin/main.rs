@@ -3,20 +3,29 @@
 // mod tests;
 // mod frontend;
 // mod runtime;
+use rlox_core::diagnostics::Format;
 use rlox_core::runtime::runner::Runner;
 // use runner::Runner;
 use std::env::args;
 
 fn main() {
-    let cli_args: Vec<String> = args().collect();
-    let ln = cli_args.len();
+    let mut cli_args: Vec<String> = args().collect();
     let mut runner = Runner::new();
+    match Format::from_args(&mut cli_args) {
+        Some(format) => runner.set_error_format(format),
+        None => return usage(),
+    }
+    let ln = cli_args.len();
     if ln == 2 {
         runner.run_file(&cli_args[1]);
     } else if ln < 2 {
         runner.run_prompt();
     } else {
-        println!("rlox [script]");
+        usage();
     }
     // ast_printer::test();
 }
+
+fn usage() {
+    println!("rlox [--error-format plain|color|json] [script]");
+}
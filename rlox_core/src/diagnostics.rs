@@ -0,0 +1,336 @@
+use crate::error::{format_backtrace, Error, ErrorCode, LoxError};
+use crate::frontend::definitions::span::Span;
+use crate::runtime::module::Modules;
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+// How a diagnostic is printed: as text, as text with ANSI colors, or as one JSON object a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    Colored,
+    Json,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "plain" => Some(Format::Plain),
+            "color" | "colored" => Some(Format::Colored),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    // Takes `--error-format name` out of a binary's arguments, errors being colored on a
+    // terminal when it's left out. None if the format is unknown.
+    pub fn from_args(cli_args: &mut Vec<String>) -> Option<Format> {
+        match cli_args.iter().position(|arg| arg == "--error-format") {
+            Some(pos) => {
+                let format = Format::parse(cli_args.get(pos + 1)?)?;
+                cli_args.drain(pos..pos + 2);
+                Some(format)
+            }
+            None => Some(Format::for_stderr()),
+        }
+    }
+
+    // Colored when a person is likely to read it, i.e. when stderr is a terminal.
+    pub fn for_stderr() -> Format {
+        if std::io::stderr().is_terminal() {
+            Format::Colored
+        } else {
+            Format::Plain
+        }
+    }
+}
+
+// A hint on how to fix the errors of a code, for the codes there is one for.
+fn help(code: &ErrorCode) -> Option<&'static str> {
+    use ErrorCode::*;
+    Some(match code {
+        InvalidEscape => "the escapes are \\n, \\t, \\r, \\0, \\\\, \\\", \\$ and \\u{...}",
+        UnterminatedString => "strings end with a '\"'",
+        UnexpectedCharacter => "characters outside strings and comments must be part of a token",
+        MalformedNumber => "numbers are written like 12, 1.5, 1e9, 0xFF, 0b1010 or 0o17",
+        DigitSeparator => "write '_' between two digits, as in 1_000_000",
+        UnterminatedComment => "every '/*' needs a matching '*/', including nested ones",
+        ExpectSemicolon => "statements end with a ';'",
+        ExpectParen => "check that every '(' is closed",
+        ExpectBrace => "check that every '{' is closed",
+        ExpectBracket => "check that every '[' is closed",
+        InvalidAssignment => "only variables, fields and indexes can be assigned to",
+        TooManyArguments => "functions take at most 255 arguments",
+        AlreadyExists(_) => "pick another name, or assign to the existing variable",
+        OwnInitializer => "give the new variable another name",
+        ReturnFromInitializer => "init always returns the instance, use a bare `return;`",
+        ImportOutsideTopLevel => "move the import to the top level of the module",
+        OperandType => "convert the operands first, as with int(), float() or \"${x}\"",
+        Undefined => "check the name for typos",
+        NotCallable => "only functions and classes can be called",
+        IndexOutOfBounds => "check the index against len()",
+        ModuleNotFound => "module paths are relative to the importing file",
+        ImportCycle => "move what both modules need into a third one",
+        Uncaught => "wrap the code raising it in a try/catch",
+        BitwiseRange => "&, |, ^ and ~ only take ints that fit in 64 bits",
+        ShiftRange => "shift by 0 to 65536 bits",
+        ExponentTooLarge => "ints can only be raised to powers of up to 65536",
+        _ => return None,
+    })
+}
+
+// A remark about an error, pointing at some other place in the source when it has a span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+// An error ready to be shown to a user: what went wrong and where, with the source lines it
+// points at, a hint at a fix and related notes, such as the frames of a backtrace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: &'static str,
+    pub code: &'static str,
+    pub message: String,
+    pub file: Option<String>,
+    pub line_no: u32,
+    pub span: Option<Span>,
+    pub help: Option<&'static str>,
+    pub notes: Vec<Note>,
+    lines: Vec<(u32, String)>,
+}
+
+impl Diagnostic {
+    pub fn new(err: &LoxError, source: &str) -> Self {
+        Diagnostic::from_error(&Error::from(err.clone()), source)
+    }
+
+    // Errors raised in a module are shown against the module's file, which the file id of their
+    // span picks out, and other errors against the script, named file if it was read from one.
+    pub fn from_program<T>(
+        err: &Error,
+        modules: &Modules<T>,
+        file: Option<&str>,
+        source: &str,
+    ) -> Self {
+        match err.span.and_then(|span| modules.file(span.file_id)) {
+            Some((path, source)) => {
+                Diagnostic::from_error(err, source).file(path.display().to_string())
+            }
+            None => {
+                let diagnostic = Diagnostic::from_error(err, source);
+                match file {
+                    Some(file) => diagnostic.file(file),
+                    None => diagnostic,
+                }
+            }
+        }
+    }
+
+    // The frames of the error's backtrace become notes without a span.
+    pub fn from_error(err: &Error, source: &str) -> Self {
+        let (kind, lexeme, line_no, span) =
+            (err.kind.name(), err.lexeme.as_str(), err.line_no, err.span);
+        let message = err.message.clone();
+        let mut notes: Vec<Note> = err
+            .backtrace
+            .iter()
+            .map(|frame| Note {
+                message: format_backtrace(std::slice::from_ref(frame)),
+                span: None,
+            })
+            .collect();
+        // only shown when it's in the source the error is shown against
+        if let (Some(span), ErrorCode::AlreadyExists(Some(first))) = (span, &err.code) {
+            if first.file_id == span.file_id {
+                notes.insert(
+                    0,
+                    Note {
+                        message: format!("`{}` first declared here", lexeme),
+                        span: Some(**first),
+                    },
+                );
+            }
+        }
+        let lines = source.split('\n').collect::<Vec<_>>();
+        let mut wanted: Vec<u32> = span
+            .iter()
+            .chain(notes.iter().filter_map(|note| note.span.as_ref()))
+            .map(|span| span.line)
+            .collect();
+        wanted.sort_unstable();
+        wanted.dedup();
+        Diagnostic {
+            kind,
+            code: err.code.as_str(),
+            message,
+            file: None,
+            line_no,
+            span,
+            help: help(&err.code),
+            notes,
+            // a span outside the source has no snippet
+            lines: wanted
+                .into_iter()
+                .filter_map(|line| {
+                    let text = lines.get((line as usize).checked_sub(1)?)?;
+                    Some((line, text.trim_end_matches('\r').to_string()))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn file<S: AsRef<str>>(mut self, file: S) -> Self {
+        self.file = Some(file.as_ref().to_string());
+        self
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Plain => self.text(false),
+            Format::Colored => self.text(true),
+            Format::Json => self.json(),
+        }
+    }
+
+    // Laid out after rustc: the message, where it happened, the lines involved with the error
+    // underlined by carets and the notes' spans by dashes, then the help and remaining notes.
+    fn text(&self, colored: bool) -> String {
+        let paint = |text: &str, style: &str| {
+            if colored {
+                format!("\x1b[{}m{}\x1b[0m", style, text)
+            } else {
+                text.to_string()
+            }
+        };
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}{}",
+            paint(&format!("error[{}]", self.code), "1;31"),
+            paint(&format!(": {}", self.message), "1")
+        )
+        .unwrap();
+        let width = self
+            .lines
+            .last()
+            .map_or(self.line_no, |(line, _)| *line)
+            .to_string()
+            .len();
+        let gutter = paint(&format!("{:width$} |", "", width = width), "1;34");
+        let file = self.file.as_deref().unwrap_or("<input>");
+        let arrow = paint(&format!("{:width$}-->", "", width = width), "1;34");
+        if self.span.is_none() && self.line_no > 0 {
+            writeln!(out, "{} {}:{}", arrow, file, self.line_no).unwrap();
+        }
+        if let Some(span) = self.span {
+            writeln!(out, "{} {}:{}:{}", arrow, file, span.line, span.column).unwrap();
+            writeln!(out, "{}", gutter).unwrap();
+            for (line, text) in &self.lines {
+                let number = paint(&format!("{:>width$} |", line, width = width), "1;34");
+                writeln!(out, "{} {}", number, text).unwrap();
+                let labels = self
+                    .notes
+                    .iter()
+                    .filter_map(|note| Some((note.span?, '-', note.message.as_str(), "1;34")))
+                    .chain(Some((span, '^', "", "1;31")));
+                for (span, mark, label, style) in labels.filter(|label| label.0.line == *line) {
+                    let marks = underline(text, span, mark);
+                    let label = format!("{} {}", marks, label);
+                    writeln!(out, "{} {}", gutter, paint(label.trim_end(), style)).unwrap();
+                }
+            }
+        }
+        let mut footer = self
+            .help
+            .iter()
+            .map(|help| ("= help", *help))
+            .chain(
+                self.notes
+                    .iter()
+                    .filter(|note| note.span.is_none())
+                    .map(|note| ("= note", note.message.as_str())),
+            )
+            .peekable();
+        if self.span.is_some() && footer.peek().is_some() {
+            writeln!(out, "{}", gutter).unwrap();
+        }
+        for (title, text) in footer {
+            writeln!(
+                out,
+                "{:width$} {}: {}",
+                "",
+                paint(title, "1"),
+                text,
+                width = width
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    fn json(&self) -> String {
+        let notes = self
+            .notes
+            .iter()
+            .map(|note| {
+                format!(
+                    "{{\"message\":{},\"span\":{}}}",
+                    json_string(&note.message),
+                    json_span(note.span)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"kind\":{},\"code\":{},\"message\":{},\"file\":{},\"line\":{},\"span\":{},\"help\":{},\"notes\":[{}]}}\n",
+            json_string(self.kind),
+            json_string(self.code),
+            json_string(&self.message),
+            self.file.as_deref().map_or("null".to_string(), json_string),
+            self.line_no,
+            json_span(self.span),
+            self.help.map_or("null".to_string(), json_string),
+            notes
+        )
+    }
+}
+
+// The marks under a span of a line, lined up with it by keeping the tabs before the span.
+fn underline(text: &str, span: Span, mark: char) -> String {
+    let pad: String = text
+        .chars()
+        .take(span.column as usize - 1)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+    let len = span.end_column.saturating_sub(span.column).max(1) as usize;
+    format!("{}{}", pad, mark.to_string().repeat(len))
+}
+
+fn json_span(span: Option<Span>) -> String {
+    match span {
+        Some(span) => format!(
+            "{{\"start\":{},\"end\":{},\"line\":{},\"column\":{},\"end_line\":{},\"end_column\":{}}}",
+            span.start, span.end, span.line, span.column, span.end_line, span.end_column
+        ),
+        None => "null".to_string(),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => write!(out, "\\u{:04x}", ch as u32).unwrap(),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
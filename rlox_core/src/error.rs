@@ -1,16 +1,354 @@
+use crate::frontend::definitions::span::Span;
+use crate::frontend::definitions::token::Token;
 use crate::runtime::definitions::object::Object;
 use std::fmt::Display;
 
-// make return, break into another enum and extend
+// The errors raised while scanning, parsing, resolving and running a script. A thrown value is
+// carried as Throw until a catch takes it, nothing catching it makes it a RuntimeError. Errors
+// carry the span of the code raising them, and runtime errors nothing caught the backtrace of
+// where they were raised.
 #[derive(Debug, Clone)]
 pub enum LoxError {
-    ScannerError(String, u32, String),
-    ParserError(String, u32, String),
-    RuntimeError(String, u32, String),
-    SemanticError(String, u32, String),
-    ReturnVal(Object, u32),
-    Break(u32),
-    Continue(u32),
+    ScannerError {
+        lexeme: String,
+        line_no: u32,
+        message: String,
+        code: ErrorCode,
+        span: Option<Span>,
+    },
+    ParserError {
+        lexeme: String,
+        line_no: u32,
+        message: String,
+        code: ErrorCode,
+        span: Option<Span>,
+    },
+    RuntimeError {
+        lexeme: String,
+        line_no: u32,
+        message: String,
+        code: ErrorCode,
+        // boxed, the backtrace making runtime errors the largest
+        span: Option<Box<Span>>,
+        backtrace: Box<[TraceFrame]>,
+    },
+    SemanticError {
+        lexeme: String,
+        line_no: u32,
+        message: String,
+        code: ErrorCode,
+        span: Option<Span>,
+    },
+    Throw(Object, u32, Option<Span>),
+}
+
+// What went wrong, which stays the same when the wording of a message changes. Codes are
+// grouped by phase: E00xx are raised while scanning, E01xx while parsing, E02xx while resolving
+// and E03xx while running, and the xx00 ones are errors of a phase with no code of their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorCode {
+    Scan,
+    InvalidEscape,
+    UnterminatedString,
+    UnexpectedCharacter,
+    MalformedNumber,
+    DigitSeparator,
+    UnterminatedComment,
+    Parse,
+    ExpectExpression,
+    ExpectSemicolon,
+    ExpectParen,
+    ExpectBrace,
+    ExpectBracket,
+    InvalidAssignment,
+    TooManyArguments,
+    ExpectToken,
+    Resolve,
+    // a name declared twice in a scope, with where it was first declared
+    AlreadyExists(Option<Box<Span>>),
+    OwnInitializer,
+    ReturnOutsideFunction,
+    ReturnFromInitializer,
+    ThisOutsideClass,
+    SuperOutsideSubclass,
+    InheritsItself,
+    ImportOutsideTopLevel,
+    BreakOutsideLoop,
+    ContinueOutsideLoop,
+    DeclarationWithoutDefinition,
+    YieldOutsideFunction,
+    Run,
+    OperandType,
+    Undefined,
+    NotCallable,
+    ArgumentCount,
+    IndexOutOfBounds,
+    IndexType,
+    NotIndexable,
+    MapKey,
+    SuperclassNotClass,
+    DivisionByZero,
+    ModuleNotFound,
+    ImportCycle,
+    Uncaught,
+    NativeArgument,
+    BitwiseRange,
+    ShiftRange,
+    Conversion,
+    ExponentTooLarge,
+    NotInstance,
+    NotMatchable,
+    NotIterable,
+    GeneratorRunning,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        use ErrorCode::*;
+        match self {
+            Scan => "E0000",
+            InvalidEscape => "E0001",
+            UnterminatedString => "E0002",
+            UnexpectedCharacter => "E0003",
+            MalformedNumber => "E0004",
+            DigitSeparator => "E0005",
+            UnterminatedComment => "E0006",
+            Parse => "E0100",
+            ExpectExpression => "E0101",
+            ExpectSemicolon => "E0102",
+            ExpectParen => "E0103",
+            ExpectBrace => "E0104",
+            ExpectBracket => "E0105",
+            InvalidAssignment => "E0106",
+            TooManyArguments => "E0107",
+            ExpectToken => "E0108",
+            Resolve => "E0200",
+            AlreadyExists(_) => "E0201",
+            OwnInitializer => "E0202",
+            ReturnOutsideFunction => "E0203",
+            ReturnFromInitializer => "E0204",
+            ThisOutsideClass => "E0205",
+            SuperOutsideSubclass => "E0206",
+            InheritsItself => "E0207",
+            ImportOutsideTopLevel => "E0208",
+            BreakOutsideLoop => "E0209",
+            ContinueOutsideLoop => "E0210",
+            DeclarationWithoutDefinition => "E0211",
+            YieldOutsideFunction => "E0212",
+            Run => "E0300",
+            OperandType => "E0301",
+            Undefined => "E0302",
+            NotCallable => "E0303",
+            ArgumentCount => "E0304",
+            IndexOutOfBounds => "E0305",
+            IndexType => "E0306",
+            NotIndexable => "E0307",
+            MapKey => "E0308",
+            SuperclassNotClass => "E0309",
+            DivisionByZero => "E0310",
+            ModuleNotFound => "E0311",
+            ImportCycle => "E0312",
+            Uncaught => "E0313",
+            NativeArgument => "E0314",
+            BitwiseRange => "E0315",
+            ShiftRange => "E0316",
+            Conversion => "E0317",
+            ExponentTooLarge => "E0318",
+            NotInstance => "E0319",
+            NotMatchable => "E0320",
+            NotIterable => "E0321",
+            GeneratorRunning => "E0322",
+        }
+    }
+}
+
+// A frame of a Lox-level backtrace: the function being run, and the line it had got to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceFrame {
+    pub name: String,
+    pub line_no: u32,
+}
+
+impl TraceFrame {
+    pub fn new(name: String, line_no: u32) -> Self {
+        TraceFrame { name, line_no }
+    }
+}
+
+// Renders frames innermost first, the way both backends print them. The top-level code of the
+// script and of modules isn't a call, so has no parentheses.
+pub fn format_backtrace(frames: &[TraceFrame]) -> String {
+    frames
+        .iter()
+        .map(|frame| match frame.name.as_str() {
+            name if name == "script" || name.starts_with("module ") => {
+                format!("[line {}] in {}", frame.line_no, name)
+            }
+            name => format!("[line {}] in {}()", frame.line_no, name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl LoxError {
+    // A runtime error raised by the code at a token.
+    pub fn runtime(token: &Token, code: ErrorCode, msg: &str) -> LoxError {
+        LoxError::runtime_on_line(token.lexeme.clone(), token.line_no, code, msg).at(token.span)
+    }
+
+    // A runtime error raised away from the code, such as in an operator or a native, which
+    // `at` places later.
+    pub fn runtime_on_line(lexeme: String, line_no: u32, code: ErrorCode, msg: &str) -> LoxError {
+        LoxError::RuntimeError {
+            lexeme,
+            line_no,
+            message: msg.to_string(),
+            code,
+            span: None,
+            backtrace: Box::new([]),
+        }
+    }
+
+    // A semantic error found at a token.
+    pub fn semantic(token: &Token, code: ErrorCode, msg: &str) -> LoxError {
+        LoxError::SemanticError {
+            lexeme: token.lexeme.clone(),
+            line_no: token.line_no,
+            message: msg.to_string(),
+            code,
+            span: Some(token.span),
+        }
+    }
+
+    // Errors of each kind with only a lexeme and a line, such as the ones tests expect.
+    pub fn scanner_error(lexeme: &str, line_no: u32) -> LoxError {
+        LoxError::ScannerError {
+            lexeme: lexeme.to_string(),
+            line_no,
+            message: String::new(),
+            code: ErrorCode::Scan,
+            span: None,
+        }
+    }
+
+    pub fn parser_error(lexeme: &str, line_no: u32) -> LoxError {
+        LoxError::ParserError {
+            lexeme: lexeme.to_string(),
+            line_no,
+            message: String::new(),
+            code: ErrorCode::Parse,
+            span: None,
+        }
+    }
+
+    pub fn semantic_error(lexeme: &str, line_no: u32) -> LoxError {
+        LoxError::SemanticError {
+            lexeme: lexeme.to_string(),
+            line_no,
+            message: String::new(),
+            code: ErrorCode::Resolve,
+            span: None,
+        }
+    }
+
+    pub fn runtime_error(lexeme: &str, line_no: u32) -> LoxError {
+        LoxError::RuntimeError {
+            lexeme: lexeme.to_string(),
+            line_no,
+            message: String::new(),
+            code: ErrorCode::Run,
+            span: None,
+            backtrace: Box::new([]),
+        }
+    }
+
+    // Runtime errors that nothing caught carry the backtrace of where they were raised.
+    pub fn with_backtrace(mut self, frames: Vec<TraceFrame>) -> LoxError {
+        if let LoxError::RuntimeError { backtrace, .. } = &mut self {
+            *backtrace = frames.into();
+        }
+        self
+    }
+
+    // Places an error raised without knowing where, such as one from an operator or a native,
+    // at the code which ran it.
+    pub fn at(mut self, at: Span) -> LoxError {
+        match &mut self {
+            LoxError::RuntimeError {
+                span: span @ None, ..
+            } => *span = Some(Box::new(at)),
+            LoxError::Throw(_, _, span @ None) => *span = Some(at),
+            _ => {}
+        }
+        self
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            LoxError::ScannerError { span, .. }
+            | LoxError::ParserError { span, .. }
+            | LoxError::SemanticError { span, .. }
+            | LoxError::Throw(_, _, span) => *span,
+            LoxError::RuntimeError { span, .. } => span.as_deref().copied(),
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            LoxError::ScannerError { code, .. }
+            | LoxError::ParserError { code, .. }
+            | LoxError::SemanticError { code, .. }
+            | LoxError::RuntimeError { code, .. } => code.clone(),
+            LoxError::Throw(..) => ErrorCode::Uncaught,
+        }
+    }
+
+    // The prelude class built-in runtime errors surface as in `catch`, picked by their code.
+    pub fn error_class(&self) -> &'static str {
+        use ErrorCode::*;
+        match self.code() {
+            Undefined => "NameError",
+            IndexOutOfBounds => "IndexError",
+            DivisionByZero => "ArithmeticError",
+            OperandType | NotCallable | ArgumentCount | IndexType | NotIndexable | MapKey
+            | SuperclassNotClass | NativeArgument | NotInstance | NotMatchable | NotIterable => {
+                "TypeError"
+            }
+            _ => "Error",
+        }
+    }
+
+    pub fn error_message(&self) -> String {
+        match self {
+            LoxError::ScannerError {
+                lexeme: literal,
+                message: msg,
+                ..
+            }
+            | LoxError::ParserError {
+                lexeme: literal,
+                message: msg,
+                ..
+            }
+            | LoxError::RuntimeError {
+                lexeme: literal,
+                message: msg,
+                ..
+            }
+            | LoxError::SemanticError {
+                lexeme: literal,
+                message: msg,
+                ..
+            } => {
+                if msg.is_empty() {
+                    literal.clone()
+                } else {
+                    msg.clone()
+                }
+            }
+            err => err.to_string(),
+        }
+    }
 }
 
 impl Display for LoxError {
@@ -19,46 +357,177 @@ impl Display for LoxError {
         writer: &mut std::fmt::Formatter<'_>,
     ) -> std::result::Result<(), std::fmt::Error> {
         match self {
-            LoxError::ScannerError(literal, line_no, msg) => writer.write_fmt(format_args!(
+            LoxError::ScannerError {
+                lexeme: literal,
+                line_no,
+                message: msg,
+                ..
+            } => writer.write_fmt(format_args!(
                 "[ScannerError:L{}:{}] {}",
                 line_no, literal, msg
             )),
-            LoxError::ParserError(literal, line_no, msg) => writer.write_fmt(format_args!(
+            LoxError::ParserError {
+                lexeme: literal,
+                line_no,
+                message: msg,
+                ..
+            } => writer.write_fmt(format_args!(
                 "[ParserError:L{}:{}] {}",
                 line_no, literal, msg
             )),
-            LoxError::RuntimeError(literal, line_no, msg) => writer.write_fmt(format_args!(
-                "[RuntimeError:L{}:{}] {}",
-                line_no, literal, msg
-            )),
-            LoxError::SemanticError(literal, line_no, msg) => writer.write_fmt(format_args!(
+            LoxError::RuntimeError {
+                lexeme: literal,
+                line_no,
+                message: msg,
+                backtrace: frames,
+                ..
+            } => {
+                writer.write_fmt(format_args!(
+                    "[RuntimeError:L{}:{}] {}",
+                    line_no, literal, msg
+                ))?;
+                if !frames.is_empty() {
+                    writer.write_fmt(format_args!("\n{}", format_backtrace(frames)))?;
+                }
+                Ok(())
+            }
+            LoxError::SemanticError {
+                lexeme: literal,
+                line_no,
+                message: msg,
+                ..
+            } => writer.write_fmt(format_args!(
                 "[SemanticError:L{}:{}] {}",
                 line_no, literal, msg
             )),
-            LoxError::ReturnVal(_, line_no) => writer.write_fmt(format_args!(
-                "[Improper return:L{}] {}",
-                line_no, "Return statements allowed only inside function/lambdas."
-            )),
-            LoxError::Break(line_no) => writer.write_fmt(format_args!(
-                "[Improper break:L{}] {}",
-                line_no, "break statements allowed only inside loops."
-            )),
-            LoxError::Continue(line_no) => writer.write_fmt(format_args!(
-                "[Improper continue:L{}] {}",
-                line_no, "continue statements allowed only inside loops."
-            )),
+            LoxError::Throw(value, line_no, _) => {
+                writer.write_fmt(format_args!("[Uncaught:L{}] {}", line_no, value))
+            }
         }
     }
 }
 
+impl std::error::Error for LoxError {}
+
 impl std::convert::From<std::time::SystemTimeError> for LoxError {
     fn from(err: std::time::SystemTimeError) -> Self {
-        LoxError::RuntimeError(err.to_string().to_string(), 0, "".to_string())
+        LoxError::runtime_on_line(err.to_string(), 0, ErrorCode::Run, "")
     }
 }
 
 impl std::convert::From<std::num::ParseFloatError> for LoxError {
     fn from(err: std::num::ParseFloatError) -> Self {
-        LoxError::RuntimeError(err.to_string().to_string(), 0, "".to_string())
+        LoxError::runtime_on_line(err.to_string(), 0, ErrorCode::Run, "")
+    }
+}
+
+// The phase an error was raised in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Scanner,
+    Parser,
+    Semantic,
+    Runtime,
+}
+
+impl ErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Scanner => "ScannerError",
+            ErrorKind::Parser => "ParserError",
+            ErrorKind::Semantic => "SemanticError",
+            ErrorKind::Runtime => "RuntimeError",
+        }
+    }
+}
+
+// An error as handed to embedders: what kind it is, where it was raised, its message and, for
+// runtime errors nothing caught, the Lox backtrace, innermost frame first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub code: ErrorCode,
+    pub lexeme: String,
+    pub line_no: u32,
+    pub span: Option<Span>,
+    pub message: String,
+    pub backtrace: Vec<TraceFrame>,
+}
+
+impl From<LoxError> for Error {
+    fn from(err: LoxError) -> Self {
+        let message = match &err {
+            LoxError::Throw(value, ..) => format!("Uncaught exception: {}", value),
+            err => err.error_message(),
+        };
+        let code = err.code();
+        let (kind, lexeme, line_no, span, backtrace) = match err {
+            LoxError::ScannerError {
+                lexeme: literal,
+                line_no,
+                span,
+                ..
+            } => (ErrorKind::Scanner, literal, line_no, span, vec![]),
+            LoxError::ParserError {
+                lexeme: literal,
+                line_no,
+                span,
+                ..
+            } => (ErrorKind::Parser, literal, line_no, span, vec![]),
+            LoxError::SemanticError {
+                lexeme: literal,
+                line_no,
+                span,
+                ..
+            } => (ErrorKind::Semantic, literal, line_no, span, vec![]),
+            LoxError::RuntimeError {
+                lexeme: literal,
+                line_no,
+                span,
+                backtrace: frames,
+                ..
+            } => (
+                ErrorKind::Runtime,
+                literal,
+                line_no,
+                span.map(|span| *span),
+                frames.into_vec(),
+            ),
+            LoxError::Throw(_, line_no, span) => (
+                ErrorKind::Runtime,
+                "throw".to_string(),
+                line_no,
+                span,
+                vec![],
+            ),
+        };
+        Error {
+            kind,
+            code,
+            lexeme,
+            line_no,
+            span,
+            message,
+            backtrace,
+        }
     }
 }
+
+impl Display for Error {
+    fn fmt(&self, writer: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            writer,
+            "[{}:L{}:{}] {}",
+            self.kind.name(),
+            self.line_no,
+            self.lexeme,
+            self.message
+        )?;
+        if !self.backtrace.is_empty() {
+            write!(writer, "\n{}", format_backtrace(&self.backtrace))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
@@ -1,4 +1,6 @@
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::pattern::MatchArm;
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::stmt::*;
 use crate::frontend::definitions::token::Token;
 use crate::frontend::lexer::*;
@@ -18,7 +20,47 @@ pub enum Expr {
     Logical(Box<Logical>),
     Call(Box<Call>),
     Lambda(Box<Lambda>),
-    Literal(Literal),
+    List(Box<List>),
+    Map(Box<Map>),
+    Index(Box<Index>),
+    SetIndex(Box<SetIndex>),
+    Compound(Box<Compound>),
+    Conditional(Box<Conditional>),
+    Coalesce(Box<Coalesce>),
+    SafeGet(Box<SafeGet>),
+    OptionalChain(Box<OptionalChain>),
+    Match(Box<MatchExpr>),
+    Literal(Literal, Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Binary(val) => val.span,
+            Expr::Grouping(val) => val.span,
+            Expr::Unary(val) => val.span,
+            Expr::Variable(val) => val.span,
+            Expr::This(val) => val.span,
+            Expr::Assign(val) => val.span,
+            Expr::Get(val) => val.span,
+            Expr::Set(val) => val.span,
+            Expr::Super(val) => val.span,
+            Expr::Logical(val) => val.span,
+            Expr::Call(val) => val.span,
+            Expr::Lambda(val) => val.span,
+            Expr::List(val) => val.span,
+            Expr::Map(val) => val.span,
+            Expr::Index(val) => val.span,
+            Expr::SetIndex(val) => val.span,
+            Expr::Compound(val) => val.span,
+            Expr::Conditional(val) => val.span,
+            Expr::Coalesce(val) => val.span,
+            Expr::SafeGet(val) => val.span,
+            Expr::OptionalChain(val) => val.span,
+            Expr::Match(val) => val.span,
+            Expr::Literal(_, span) => *span,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -26,14 +68,16 @@ pub struct Binary {
     pub left: Expr,
     pub operator: Token,
     pub right: Expr,
+    pub span: Span,
 }
 
 impl Binary {
-    pub fn new(left: Expr, operator: Token, right: Expr) -> Self {
+    pub fn new(left: Expr, operator: Token, right: Expr, span: Span) -> Self {
         Self {
             left,
             operator,
             right,
+            span,
         }
     }
 }
@@ -41,11 +85,12 @@ impl Binary {
 #[derive(Debug, Clone)]
 pub struct Grouping {
     pub expression: Expr,
+    pub span: Span,
 }
 
 impl Grouping {
-    pub fn new(expression: Expr) -> Self {
-        Self { expression }
+    pub fn new(expression: Expr, span: Span) -> Self {
+        Self { expression, span }
     }
 }
 
@@ -53,33 +98,40 @@ impl Grouping {
 pub struct Unary {
     pub operator: Token,
     pub right: Expr,
+    pub span: Span,
 }
 
 impl Unary {
-    pub fn new(operator: Token, right: Expr) -> Self {
-        Self { operator, right }
+    pub fn new(operator: Token, right: Expr, span: Span) -> Self {
+        Self {
+            operator,
+            right,
+            span,
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Variable {
     pub name: Token,
+    pub span: Span,
 }
 
 impl Variable {
-    pub fn new(name: Token) -> Self {
-        Self { name }
+    pub fn new(name: Token, span: Span) -> Self {
+        Self { name, span }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct This {
     pub keyword: Token,
+    pub span: Span,
 }
 
 impl This {
-    pub fn new(keyword: Token) -> Self {
-        Self { keyword }
+    pub fn new(keyword: Token, span: Span) -> Self {
+        Self { keyword, span }
     }
 }
 
@@ -87,11 +139,12 @@ impl This {
 pub struct Assign {
     pub name: Token,
     pub value: Expr,
+    pub span: Span,
 }
 
 impl Assign {
-    pub fn new(name: Token, value: Expr) -> Self {
-        Self { name, value }
+    pub fn new(name: Token, value: Expr, span: Span) -> Self {
+        Self { name, value, span }
     }
 }
 
@@ -99,11 +152,12 @@ impl Assign {
 pub struct Get {
     pub object: Expr,
     pub name: Token,
+    pub span: Span,
 }
 
 impl Get {
-    pub fn new(object: Expr, name: Token) -> Self {
-        Self { object, name }
+    pub fn new(object: Expr, name: Token, span: Span) -> Self {
+        Self { object, name, span }
     }
 }
 
@@ -112,14 +166,16 @@ pub struct Set {
     pub object: Expr,
     pub name: Token,
     pub value: Expr,
+    pub span: Span,
 }
 
 impl Set {
-    pub fn new(object: Expr, name: Token, value: Expr) -> Self {
+    pub fn new(object: Expr, name: Token, value: Expr, span: Span) -> Self {
         Self {
             object,
             name,
             value,
+            span,
         }
     }
 }
@@ -128,11 +184,16 @@ impl Set {
 pub struct Super {
     pub method: Token,
     pub keyword: Token,
+    pub span: Span,
 }
 
 impl Super {
-    pub fn new(method: Token, keyword: Token) -> Self {
-        Self { method, keyword }
+    pub fn new(method: Token, keyword: Token, span: Span) -> Self {
+        Self {
+            method,
+            keyword,
+            span,
+        }
     }
 }
 
@@ -141,14 +202,16 @@ pub struct Logical {
     pub left: Expr,
     pub operator: Token,
     pub right: Expr,
+    pub span: Span,
 }
 
 impl Logical {
-    pub fn new(left: Expr, operator: Token, right: Expr) -> Self {
+    pub fn new(left: Expr, operator: Token, right: Expr, span: Span) -> Self {
         Self {
             left,
             operator,
             right,
+            span,
         }
     }
 }
@@ -158,14 +221,16 @@ pub struct Call {
     pub callee: Expr,
     pub paren: Token,
     pub arguments: Vec<Expr>,
+    pub span: Span,
 }
 
 impl Call {
-    pub fn new(callee: Expr, paren: Token, arguments: Vec<Expr>) -> Self {
+    pub fn new(callee: Expr, paren: Token, arguments: Vec<Expr>, span: Span) -> Self {
         Self {
             callee,
             paren,
             arguments,
+            span,
         }
     }
 }
@@ -175,14 +240,212 @@ pub struct Lambda {
     pub paren: Token,
     pub params: Vec<Token>,
     pub body: Vec<Stmt>,
+    pub is_generator: bool,
+    pub span: Span,
 }
 
 impl Lambda {
-    pub fn new(paren: Token, params: Vec<Token>, body: Vec<Stmt>) -> Self {
+    pub fn new(paren: Token, params: Vec<Token>, body: Vec<Stmt>, span: Span) -> Self {
         Self {
             paren,
             params,
+            is_generator: body.iter().any(Stmt::yields),
             body,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct List {
+    pub bracket: Token,
+    pub elements: Vec<Expr>,
+    pub span: Span,
+}
+
+impl List {
+    pub fn new(bracket: Token, elements: Vec<Expr>, span: Span) -> Self {
+        Self {
+            bracket,
+            elements,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    pub brace: Token,
+    pub entries: Vec<(Expr, Expr)>,
+    pub span: Span,
+}
+
+impl Map {
+    pub fn new(brace: Token, entries: Vec<(Expr, Expr)>, span: Span) -> Self {
+        Self {
+            brace,
+            entries,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub object: Expr,
+    pub bracket: Token,
+    pub index: Expr,
+    pub span: Span,
+}
+
+impl Index {
+    pub fn new(object: Expr, bracket: Token, index: Expr, span: Span) -> Self {
+        Self {
+            object,
+            bracket,
+            index,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetIndex {
+    pub object: Expr,
+    pub bracket: Token,
+    pub index: Expr,
+    pub value: Expr,
+    pub span: Span,
+}
+
+impl SetIndex {
+    pub fn new(object: Expr, bracket: Token, index: Expr, value: Expr, span: Span) -> Self {
+        Self {
+            object,
+            bracket,
+            index,
+            value,
+            span,
+        }
+    }
+}
+
+// `+=` and the like, and `++`/`--` which add or subtract 1. The target is a Variable, Get or
+// Index, whose object and index are evaluated once. The operator is the binary one applied,
+// keeping the lexeme it was written with, and postfix ones evaluate to the old value.
+#[derive(Debug, Clone)]
+pub struct Compound {
+    pub target: Expr,
+    pub operator: Token,
+    pub value: Expr,
+    pub postfix: bool,
+    pub span: Span,
+}
+
+impl Compound {
+    pub fn new(target: Expr, operator: Token, value: Expr, postfix: bool, span: Span) -> Self {
+        Self {
+            target,
+            operator,
+            value,
+            postfix,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Conditional {
+    pub condition: Expr,
+    pub question: Token,
+    pub then_branch: Expr,
+    pub else_branch: Expr,
+    pub span: Span,
+}
+
+impl Conditional {
+    pub fn new(
+        condition: Expr,
+        question: Token,
+        then_branch: Expr,
+        else_branch: Expr,
+        span: Span,
+    ) -> Self {
+        Self {
+            condition,
+            question,
+            then_branch,
+            else_branch,
+            span,
+        }
+    }
+}
+
+// `left ?? right`, which only evaluates right when left is nil.
+#[derive(Debug, Clone)]
+pub struct Coalesce {
+    pub left: Expr,
+    pub operator: Token,
+    pub right: Expr,
+    pub span: Span,
+}
+
+impl Coalesce {
+    pub fn new(left: Expr, operator: Token, right: Expr, span: Span) -> Self {
+        Self {
+            left,
+            operator,
+            right,
+            span,
+        }
+    }
+}
+
+// `object?.name`, which skips the rest of the OptionalChain it's in when object is nil.
+#[derive(Debug, Clone)]
+pub struct SafeGet {
+    pub object: Expr,
+    pub name: Token,
+    pub span: Span,
+}
+
+impl SafeGet {
+    pub fn new(object: Expr, name: Token, span: Span) -> Self {
+        Self { object, name, span }
+    }
+}
+
+// A chain of calls, gets and indexes with a SafeGet in it, which is nil when one of those
+// short-circuits.
+#[derive(Debug, Clone)]
+pub struct OptionalChain {
+    pub expr: Expr,
+    pub span: Span,
+}
+
+impl OptionalChain {
+    pub fn new(expr: Expr, span: Span) -> Self {
+        Self { expr, span }
+    }
+}
+
+// The value of the first arm whose pattern matches the subject and whose guard holds, nil when
+// there is none.
+#[derive(Debug, Clone)]
+pub struct MatchExpr {
+    pub keyword: Token,
+    pub subject: Expr,
+    pub arms: Vec<MatchArm<Expr>>,
+    pub span: Span,
+}
+
+impl MatchExpr {
+    pub fn new(keyword: Token, subject: Expr, arms: Vec<MatchArm<Expr>>, span: Span) -> Self {
+        Self {
+            keyword,
+            subject,
+            arms,
+            span,
         }
     }
 }
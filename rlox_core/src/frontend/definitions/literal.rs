@@ -1,9 +1,12 @@
+use crate::runtime::definitions::big_int::BigInt;
 use crate::runtime::definitions::object::Object;
 use std::fmt::Display;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     NUM(f64),
+    INT(i64),
+    BIG(BigInt),
     STRING(String),
     BOOL(bool),
     NIL,
@@ -16,6 +19,8 @@ impl Display for Literal {
     ) -> std::result::Result<(), std::fmt::Error> {
         match self {
             Literal::NUM(val) => writer.write_str(&val.to_string()),
+            Literal::INT(val) => writer.write_str(&val.to_string()),
+            Literal::BIG(val) => writer.write_str(&val.to_string()),
             Literal::STRING(val) => writer.write_str(&val.to_string()),
             Literal::BOOL(val) => writer.write_str(&val.to_string()),
             Literal::NIL => writer.write_str("Nil"),
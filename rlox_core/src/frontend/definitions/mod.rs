@@ -2,6 +2,8 @@ pub mod class_type;
 pub mod expr;
 pub mod function_type;
 pub mod literal;
+pub mod pattern;
+pub mod span;
 pub mod stmt;
 pub mod token;
 pub mod token_type;
@@ -0,0 +1,67 @@
+use crate::frontend::definitions::expr::{Expr, Variable};
+use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::token::Token;
+
+// What a match arm tests its subject against.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // `_`, which matches anything.
+    Wildcard(Token),
+    // A name, which matches anything and binds it.
+    Binding(Token),
+    // A number, string, bool or nil, which matches values equal to it.
+    Literal(Literal, Token),
+    // `Class name`, which matches instances of the class or its subclasses, binding the instance
+    // to name unless it's `_`.
+    Class(Variable, Option<Token>),
+    // `[p, ...]`, which matches lists of that many elements, each matching its pattern.
+    List(Token, Vec<Pattern>),
+}
+
+impl Pattern {
+    // The token errors about the pattern are reported at.
+    pub fn token(&self) -> &Token {
+        match self {
+            Pattern::Wildcard(token)
+            | Pattern::Binding(token)
+            | Pattern::Literal(_, token)
+            | Pattern::List(token, _) => token,
+            Pattern::Class(class, _) => &class.name,
+        }
+    }
+
+    // Whether the pattern matches every value.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, Pattern::Wildcard(_) | Pattern::Binding(_))
+    }
+
+    // The names the pattern binds, in the order they appear.
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Binding(name) | Pattern::Class(_, Some(name)) => vec![name],
+            Pattern::List(_, elements) => elements.iter().flat_map(Pattern::bindings).collect(),
+            _ => vec![],
+        }
+    }
+}
+
+// `case pattern if guard => body`, the body being a statement in a match statement and an
+// expression in a match expression.
+#[derive(Debug, Clone)]
+pub struct MatchArm<T> {
+    pub keyword: Token,
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: T,
+}
+
+impl<T> MatchArm<T> {
+    pub fn new(keyword: Token, pattern: Pattern, guard: Option<Expr>, body: T) -> Self {
+        Self {
+            keyword,
+            pattern,
+            guard,
+            body,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// A stretch of source text: the file it's in, its byte offsets, and the line and column (1-based,
+// counted in chars) it starts and ends at, the end being just past its last char.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub file_id: usize,
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    pub fn new(file_id: usize, start: usize, end: usize) -> Self {
+        Span {
+            file_id,
+            start,
+            end,
+            ..Span::default()
+        }
+    }
+
+    // The span from the start of this one to the end of other.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end,
+            end_line: other.end_line,
+            end_column: other.end_column,
+            ..self
+        }
+    }
+}
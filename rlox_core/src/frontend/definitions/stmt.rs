@@ -1,4 +1,6 @@
 use crate::frontend::definitions::expr::*;
+use crate::frontend::definitions::pattern::MatchArm;
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::token::Token;
 use crate::frontend::lexer::*;
 
@@ -11,32 +13,92 @@ pub enum Stmt {
     Print(Box<Print>),
     Var(Box<Var>),
     While(Box<While>),
+    ForIn(Box<ForIn>),
     Break(Box<Break>),
     Continue(Box<Continue>),
     If(Box<If>),
     Return(Box<Return>),
-    StackTrace,
+    Yield(Box<Yield>),
+    Try(Box<Try>),
+    Throw(Box<Throw>),
+    Import(Box<Import>),
+    StackTrace(Box<StackTrace>),
+    Match(Box<Match>),
+}
+
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Expression(val) => val.span,
+            Stmt::Block(val) => val.span,
+            Stmt::Class(val) => val.span,
+            Stmt::Function(val) => val.span,
+            Stmt::Print(val) => val.span,
+            Stmt::Var(val) => val.span,
+            Stmt::While(val) => val.span,
+            Stmt::ForIn(val) => val.span,
+            Stmt::Break(val) => val.span,
+            Stmt::Continue(val) => val.span,
+            Stmt::If(val) => val.span,
+            Stmt::Return(val) => val.span,
+            Stmt::Yield(val) => val.span,
+            Stmt::Try(val) => val.span,
+            Stmt::Throw(val) => val.span,
+            Stmt::Import(val) => val.span,
+            Stmt::StackTrace(val) => val.span,
+            Stmt::Match(val) => val.span,
+        }
+    }
+
+    // Whether running the statement may yield. Functions declared in it are left out, as
+    // their yields are their own.
+    pub fn yields(&self) -> bool {
+        match self {
+            Stmt::Yield(_) => true,
+            Stmt::Block(val) => val.statements.iter().any(Stmt::yields),
+            Stmt::If(val) => {
+                val.then_branch.yields() || val.else_branch.as_ref().is_some_and(Stmt::yields)
+            }
+            Stmt::While(val) => val.body.yields(),
+            Stmt::ForIn(val) => val.body.yields(),
+            Stmt::Match(val) => val.arms.iter().any(|arm| arm.body.yields()),
+            Stmt::Try(val) => {
+                val.body.iter().any(Stmt::yields)
+                    || val
+                        .catch
+                        .as_ref()
+                        .is_some_and(|catch| catch.body.iter().any(Stmt::yields))
+                    || val
+                        .finally
+                        .as_ref()
+                        .is_some_and(|finally| finally.iter().any(Stmt::yields))
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Expression {
     pub expr: Expr,
+    pub span: Span,
 }
 
 impl Expression {
-    pub fn new(expr: Expr) -> Self {
-        Self { expr }
+    pub fn new(expr: Expr, span: Span) -> Self {
+        Self { expr, span }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Block {
     pub statements: Vec<Stmt>,
+    pub span: Span,
 }
 
 impl Block {
-    pub fn new(statements: Vec<Stmt>) -> Self {
-        Self { statements }
+    pub fn new(statements: Vec<Stmt>, span: Span) -> Self {
+        Self { statements, span }
     }
 }
 
@@ -45,14 +107,21 @@ pub struct Class {
     pub name: Token,
     pub methods: Vec<Function>,
     pub superclass: Option<Variable>,
+    pub span: Span,
 }
 
 impl Class {
-    pub fn new(name: Token, methods: Vec<Function>, superclass: Option<Variable>) -> Self {
+    pub fn new(
+        name: Token,
+        methods: Vec<Function>,
+        superclass: Option<Variable>,
+        span: Span,
+    ) -> Self {
         Self {
             name,
             methods,
             superclass,
+            span,
         }
     }
 }
@@ -62,11 +131,20 @@ pub struct Function {
     pub name: Token,
     pub params: Vec<Token>,
     pub body: Vec<Stmt>,
+    // calling a function with a yield in it returns a generator instead of running it
+    pub is_generator: bool,
+    pub span: Span,
 }
 
 impl Function {
-    pub fn new(name: Token, params: Vec<Token>, body: Vec<Stmt>) -> Self {
-        Self { name, params, body }
+    pub fn new(name: Token, params: Vec<Token>, body: Vec<Stmt>, span: Span) -> Self {
+        Self {
+            name,
+            params,
+            is_generator: body.iter().any(Stmt::yields),
+            body,
+            span,
+        }
     }
 }
 
@@ -74,11 +152,12 @@ impl Function {
 pub struct Print {
     pub expr: Expr,
     pub token: Token,
+    pub span: Span,
 }
 
 impl Print {
-    pub fn new(expr: Expr, token: Token) -> Self {
-        Self { expr, token }
+    pub fn new(expr: Expr, token: Token, span: Span) -> Self {
+        Self { expr, token, span }
     }
 }
 
@@ -86,11 +165,16 @@ impl Print {
 pub struct Var {
     pub name: Token,
     pub initializer: Option<Expr>,
+    pub span: Span,
 }
 
 impl Var {
-    pub fn new(name: Token, initializer: Option<Expr>) -> Self {
-        Self { name, initializer }
+    pub fn new(name: Token, initializer: Option<Expr>, span: Span) -> Self {
+        Self {
+            name,
+            initializer,
+            span,
+        }
     }
 }
 
@@ -99,37 +183,75 @@ pub struct While {
     pub token: Token,
     pub condition: Expr,
     pub body: Stmt,
+    pub span: Span,
 }
 
 impl While {
-    pub fn new(condition: Expr, body: Stmt, token: Token) -> Self {
+    pub fn new(condition: Expr, body: Stmt, token: Token, span: Span) -> Self {
         Self {
             token,
             condition,
             body,
+            span,
+        }
+    }
+}
+
+// Runs the body once for each item the iterable yields, with name bound to the item.
+#[derive(Debug, Clone)]
+pub struct ForIn {
+    pub keyword: Token,
+    pub name: Token,
+    pub iterable: Expr,
+    pub body: Stmt,
+    pub span: Span,
+}
+
+impl ForIn {
+    pub fn new(keyword: Token, name: Token, iterable: Expr, body: Stmt, span: Span) -> Self {
+        Self {
+            keyword,
+            name,
+            iterable,
+            body,
+            span,
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct StackTrace {
+    pub keyword: Token,
+    pub span: Span,
+}
+
+impl StackTrace {
+    pub fn new(keyword: Token, span: Span) -> Self {
+        Self { keyword, span }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Break {
     pub keyword: Token,
+    pub span: Span,
 }
 
 impl Break {
-    pub fn new(keyword: Token) -> Self {
-        Self { keyword }
+    pub fn new(keyword: Token, span: Span) -> Self {
+        Self { keyword, span }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Continue {
     pub keyword: Token,
+    pub span: Span,
 }
 
 impl Continue {
-    pub fn new(keyword: Token) -> Self {
-        Self { keyword }
+    pub fn new(keyword: Token, span: Span) -> Self {
+        Self { keyword, span }
     }
 }
 
@@ -139,6 +261,7 @@ pub struct If {
     pub condition: Expr,
     pub then_branch: Stmt,
     pub else_branch: Option<Stmt>,
+    pub span: Span,
 }
 
 impl If {
@@ -147,12 +270,14 @@ impl If {
         then_branch: Stmt,
         else_branch: Option<Stmt>,
         token: Token,
+        span: Span,
     ) -> Self {
         Self {
             token,
             condition,
             then_branch,
             else_branch,
+            span,
         }
     }
 }
@@ -161,10 +286,136 @@ impl If {
 pub struct Return {
     pub keyword: Token,
     pub value: Option<Expr>,
+    pub span: Span,
 }
 
 impl Return {
-    pub fn new(keyword: Token, value: Option<Expr>) -> Self {
-        Self { keyword, value }
+    pub fn new(keyword: Token, value: Option<Expr>, span: Span) -> Self {
+        Self {
+            keyword,
+            value,
+            span,
+        }
+    }
+}
+
+// Suspends the generator it's in, handing the value to whatever resumed it.
+#[derive(Debug, Clone)]
+pub struct Yield {
+    pub keyword: Token,
+    pub value: Option<Expr>,
+    pub span: Span,
+}
+
+impl Yield {
+    pub fn new(keyword: Token, value: Option<Expr>, span: Span) -> Self {
+        Self {
+            keyword,
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Try {
+    pub keyword: Token,
+    pub body: Vec<Stmt>,
+    pub catch: Option<Catch>,
+    pub finally: Option<Vec<Stmt>>,
+    pub span: Span,
+}
+
+impl Try {
+    pub fn new(
+        keyword: Token,
+        body: Vec<Stmt>,
+        catch: Option<Catch>,
+        finally: Option<Vec<Stmt>>,
+        span: Span,
+    ) -> Self {
+        Self {
+            keyword,
+            body,
+            catch,
+            finally,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Catch {
+    pub name: Token,
+    pub body: Vec<Stmt>,
+}
+
+impl Catch {
+    pub fn new(name: Token, body: Vec<Stmt>) -> Self {
+        Self { name, body }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Throw {
+    pub keyword: Token,
+    pub value: Expr,
+    pub span: Span,
+}
+
+impl Throw {
+    pub fn new(keyword: Token, value: Expr, span: Span) -> Self {
+        Self {
+            keyword,
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub keyword: Token,
+    pub path: Token,
+    pub name: Option<Token>,
+    pub names: Vec<Token>,
+    pub span: Span,
+}
+
+impl Import {
+    pub fn new(
+        keyword: Token,
+        path: Token,
+        name: Option<Token>,
+        names: Vec<Token>,
+        span: Span,
+    ) -> Self {
+        Self {
+            keyword,
+            path,
+            name,
+            names,
+            span,
+        }
+    }
+}
+
+// Runs the body of the first arm whose pattern matches the subject and whose guard holds.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub keyword: Token,
+    pub subject: Expr,
+    pub arms: Vec<MatchArm<Stmt>>,
+    pub span: Span,
+}
+
+impl Match {
+    pub fn new(keyword: Token, subject: Expr, arms: Vec<MatchArm<Stmt>>, span: Span) -> Self {
+        Self {
+            keyword,
+            subject,
+            arms,
+            span,
+        }
     }
 }
@@ -1,4 +1,5 @@
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::token_type::TokenType;
 
 #[derive(Debug, Clone)]
@@ -8,6 +9,9 @@ pub struct Token {
     pub literal: Option<Literal>,
     pub lexeme: String,
     pub scope: Option<usize>,
+    pub span: Span,
+    // the doc comments right before the token, `///` lines or a `/** */` block, for tooling
+    pub doc: Option<String>,
 }
 
 impl Token {
@@ -23,6 +27,13 @@ impl Token {
             literal,
             lexeme,
             scope: None,
+            span: Span::default(),
+            doc: None,
         }
     }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
 }
@@ -14,9 +14,18 @@ pub enum TokenType {
     #[token("}")]
     RightBrace,
 
+    #[token("[")]
+    LeftBracket,
+
+    #[token("]")]
+    RightBracket,
+
     #[token(",")]
     COMMA,
 
+    #[token(":")]
+    COLON,
+
     #[token(".")]
     DOT,
 
@@ -35,6 +44,21 @@ pub enum TokenType {
     #[token("*")]
     STAR,
 
+    #[token("%")]
+    PERCENT,
+
+    #[token("&")]
+    AMPERSAND,
+
+    #[token("|")]
+    PIPE,
+
+    #[token("^")]
+    CARET,
+
+    #[token("~")]
+    TILDE,
+
     // One or two character tokens.
     #[token("!")]
     BANG,
@@ -60,6 +84,53 @@ pub enum TokenType {
     #[token("<=")]
     LessEqual,
 
+    #[token("<<")]
+    LessLess,
+
+    #[token(">>")]
+    GreaterGreater,
+
+    // Integer division, as `//` starts a comment.
+    #[token("~/")]
+    TildeSlash,
+
+    #[token("**")]
+    StarStar,
+
+    #[token("+=")]
+    PlusEqual,
+
+    #[token("-=")]
+    MinusEqual,
+
+    #[token("*=")]
+    StarEqual,
+
+    #[token("/=")]
+    SlashEqual,
+
+    #[token("++")]
+    PlusPlus,
+
+    #[token("--")]
+    MinusMinus,
+
+    #[token("?")]
+    QUESTION,
+
+    #[token("??")]
+    QuestionQuestion,
+
+    #[token("?.")]
+    QuestionDot,
+
+    #[token("=>")]
+    FatArrow,
+
+    // The wildcard pattern, as identifiers start with a letter.
+    #[token("_")]
+    UNDERSCORE,
+
     // Keywords.
     #[token("and")]
     AND,
@@ -79,6 +150,9 @@ pub enum TokenType {
     #[token("for")]
     FOR,
 
+    #[token("in")]
+    IN,
+
     #[token("if")]
     IF,
 
@@ -94,6 +168,9 @@ pub enum TokenType {
     #[token("return")]
     RETURN,
 
+    #[token("yield")]
+    YIELD,
+
     #[token("super")]
     SUPER,
 
@@ -115,22 +192,52 @@ pub enum TokenType {
     #[token("continue")]
     CONTINUE,
 
+    #[token("try")]
+    TRY,
+
+    #[token("catch")]
+    CATCH,
+
+    #[token("finally")]
+    FINALLY,
+
+    #[token("throw")]
+    THROW,
+
+    #[token("import")]
+    IMPORT,
+
+    #[token("from")]
+    FROM,
+
+    #[token("as")]
+    AS,
+
     #[token("stacktrace")]
     STACKTRACE,
 
+    #[token("match")]
+    MATCH,
+
+    #[token("case")]
+    CASE,
+
     // Or regular expressions.
     #[regex("[a-zA-Z]+[a-zA-Z0-9_]*")]
     IDENTIFIER,
 
-    // Or regular expressions.
-    #[regex("[0-9]+|[0-9]+.[0-9]+")]
+    // Numbers are scanned by hand so that a malformed one, like `0x` or `1_`, is a single token
+    // the lexer can report, rather than a number followed by an identifier.
+    #[regex("[0-9]", number)]
     NUMBER,
 
-    // Or regular expressions.
-    #[regex("\"[^\"]*\"")]
+    // Strings are scanned by hand so that `${...}` may contain nested strings and braces.
+    #[token("\"", string)]
     STRING,
 
+    // Block comments nest, so they are scanned by hand too.
     #[regex("//[^\n]*")]
+    #[token("/*", block_comment)]
     COMMENTS,
 
     // Logos requires one token variant to handle errors,
@@ -142,4 +249,111 @@ pub enum TokenType {
     Error,
 
     EOF,
+
+    // Never produced by logos; the lexer emits it in front of every `${...}`
+    // so that the interpolated expression is converted to a string.
+    STRINGIFY,
+}
+
+fn string(lex: &mut logos::Lexer<TokenType>) -> bool {
+    match string_end(lex.remainder()) {
+        Some(end) => {
+            lex.bump(end);
+            true
+        }
+        None => {
+            lex.bump(lex.remainder().len());
+            false
+        }
+    }
+}
+
+// Takes the letters, digits and '_' following the first digit, a '.' only when a digit comes
+// after it, as `1.foo` is a property access, and the sign of a decimal exponent.
+fn number(lex: &mut logos::Lexer<TokenType>) -> bool {
+    let bytes = lex.remainder().as_bytes();
+    let is_hex = lex.slice() == "0" && matches!(bytes.first(), Some(b'x' | b'X'));
+    let is_digit = |i: usize| bytes.get(i).is_some_and(|b| b.is_ascii_digit());
+    let mut i = 0;
+    let mut fraction = false;
+    loop {
+        match bytes.get(i) {
+            Some(b) if b.is_ascii_alphanumeric() || *b == b'_' => i += 1,
+            Some(b'.') if !fraction && !is_hex && is_digit(i + 1) => {
+                fraction = true;
+                i += 1;
+            }
+            Some(b'+' | b'-')
+                if !is_hex
+                    && is_digit(i + 1)
+                    && matches!(i.checked_sub(1).map(|prev| bytes[prev]), Some(b'e' | b'E')) =>
+            {
+                i += 1
+            }
+            _ => break,
+        }
+    }
+    lex.bump(i);
+    true
+}
+
+fn block_comment(lex: &mut logos::Lexer<TokenType>) -> bool {
+    let bytes = lex.remainder().as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match &bytes[i..] {
+            [b'/', b'*', ..] => {
+                depth += 1;
+                i += 2;
+            }
+            [b'*', b'/', ..] if depth == 0 => {
+                lex.bump(i + 2);
+                return true;
+            }
+            [b'*', b'/', ..] => {
+                depth -= 1;
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+    lex.bump(bytes.len());
+    false
+}
+
+// Returns the byte length of a string body including the closing quote.
+fn string_end(src: &str) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => return Some(i + 1),
+            b'\\' => i += 2,
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                i += 2;
+                i += interpolation_end(&src[i..])?;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// Returns the byte length of an interpolated expression including the closing brace.
+fn interpolation_end(src: &str) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(i + 1),
+            b'}' => depth -= 1,
+            b'"' => i += string_end(&src[(i + 1)..])?,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
 }
@@ -1,56 +1,384 @@
 use std::error::Error;
 use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::CharIndices;
 
 use logos::Logos;
 
-use crate::error::LoxError;
+use crate::error::{ErrorCode, LoxError};
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::token::Token;
 use crate::frontend::definitions::token_type::TokenType;
+use crate::runtime::definitions::big_int::BigInt;
 use crate::runtime::definitions::object::Object;
 
-// TODO: some issue with how we are parsing comments
-
 pub struct Lexer {
     curr: u32,
+    // which file the source comes from, 0 being the script run
+    file_id: usize,
+    errors: Vec<LoxError>,
 }
 
 impl Lexer {
     pub fn new() -> Self {
-        Lexer { curr: 1 }
+        Lexer::for_file(0)
+    }
+
+    pub fn for_file(file_id: usize) -> Self {
+        Lexer {
+            curr: 1,
+            file_id,
+            errors: vec![],
+        }
     }
 
     pub fn parse(&mut self, script: &String) -> Result<Vec<Token>, LoxError> {
-        let lines = script.split('\n');
-        let mut tokens = vec![];
-        for line in lines {
-            tokens.append(&mut self.read_line(&line.to_string())?);
-            self.curr += 1;
+        let (tokens, mut errors) = self.parse_all(script);
+        if errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(errors.remove(0))
         }
-        tokens.push(Token::new(TokenType::EOF, self.curr, None, String::new()));
-        Ok(tokens)
     }
 
-    pub fn read_line(&mut self, line: &String) -> Result<Vec<Token>, LoxError> {
+    // Scans the whole script, skipping what couldn't be scanned so that all errors are
+    // reported. The tokens returned are the ones which scanned.
+    pub fn parse_all(&mut self, script: &String) -> (Vec<Token>, Vec<LoxError>) {
+        let mut tokens = self.scan(script, 0);
+        // errors at the end of the script point just past its last token
+        let last = tokens.last().map_or(0, |token| token.span.end);
+        let end = Span::new(self.file_id, last, last);
+        tokens.push(Token::new(TokenType::EOF, self.curr + 1, None, String::new()).with_span(end));
+        let mut errors = std::mem::take(&mut self.errors);
+        let error_spans = errors.iter_mut().filter_map(|err| match err {
+            LoxError::ScannerError {
+                span: Some(span), ..
+            } => Some(span),
+            _ => None,
+        });
+        set_positions(
+            script,
+            tokens
+                .iter_mut()
+                .map(|token| &mut token.span)
+                .chain(error_spans),
+        );
+        (tokens, errors)
+    }
+
+    // Lines are tracked by counting the newlines skipped between tokens, so
+    // tokens spanning several lines (multi-line strings) keep their start line.
+    // Spans only get their byte offsets here, base being where source starts in the file.
+    fn scan(&mut self, source: &str, base: usize) -> Vec<Token> {
         let mut tokens = vec![];
-        let mut lex = TokenType::lexer(&line);
-        //TODO: handle error tokens properly
-        while let Some(tk) = lex.next() {
-            if tk == TokenType::COMMENTS {
-                continue;
-            }
-            let literal;
-            literal = match tk {
+        let mut lex = TokenType::lexer(source).spanned().peekable();
+        let mut offset = 0;
+        let mut doc: Option<String> = None;
+        while let Some((tk, range)) = lex.next() {
+            self.curr += count_lines(&source[offset..range.start]);
+            offset = range.start;
+            let slice = &source[range.clone()];
+            let span = Span::new(self.file_id, base + range.start, base + range.end);
+            let literal = match tk {
+                TokenType::COMMENTS => {
+                    if let Some(text) = doc_comment(slice) {
+                        doc = Some(match doc.take() {
+                            Some(prev) => format!("{}\n{}", prev, text),
+                            None => text.to_string(),
+                        });
+                    }
+                    continue;
+                }
+                TokenType::STRING => {
+                    let first = tokens.len();
+                    if let Err(err) = self.string(slice, span, &mut tokens) {
+                        self.errors.push(err);
+                    }
+                    if let Some(token) = tokens.get_mut(first) {
+                        token.doc = doc.take();
+                    }
+                    continue;
+                }
+                // a string logos couldn't find the end of runs to the end of the source
+                TokenType::Error if slice.starts_with('"') => {
+                    let first_line = slice.split('\n').next().unwrap_or_default();
+                    let span = Span::new(self.file_id, span.start, span.start + first_line.len());
+                    self.error(
+                        first_line,
+                        span,
+                        ErrorCode::UnterminatedString,
+                        "Unterminated string.",
+                    );
+                    continue;
+                }
+                TokenType::Error if slice.starts_with("/*") => {
+                    let span = Span::new(self.file_id, span.start, span.start + 2);
+                    self.error(
+                        "/*",
+                        span,
+                        ErrorCode::UnterminatedComment,
+                        "Unterminated block comment.",
+                    );
+                    continue;
+                }
+                // characters which can't start a token are reported together
+                TokenType::Error => {
+                    let mut end = range.end;
+                    while let Some((TokenType::Error, next)) = lex.peek() {
+                        if next.start != end || source[next.clone()].starts_with(['"', '/']) {
+                            break;
+                        }
+                        end = next.end;
+                        lex.next();
+                    }
+                    let span = Span::new(self.file_id, span.start, base + end);
+                    self.error(
+                        &source[range.start..end],
+                        span,
+                        ErrorCode::UnexpectedCharacter,
+                        "Unexpected character.",
+                    );
+                    continue;
+                }
                 TokenType::TRUE => Some(Literal::BOOL(true)),
                 TokenType::FALSE => Some(Literal::BOOL(false)),
-                TokenType::STRING => Some(Literal::STRING(
-                    lex.slice()[1..(lex.slice().len() - 1)].to_string(),
-                )),
-                TokenType::NUMBER => Some(Literal::NUM(lex.slice().parse::<f64>()?)),
+                TokenType::NUMBER => match number(slice) {
+                    Ok(num) => Some(num),
+                    Err((code, msg)) => {
+                        self.error(slice, span, code, msg);
+                        continue;
+                    }
+                },
                 _ => None,
             };
-            tokens.push(Token::new(tk, self.curr, literal, lex.slice().to_string()))
+            let mut token = Token::new(tk, self.curr, literal, slice.to_string());
+            token.doc = doc.take();
+            tokens.push(token.with_span(span))
+        }
+        self.curr += count_lines(&source[offset..]);
+        tokens
+    }
+
+    fn error(&mut self, lexeme: &str, span: Span, code: ErrorCode, msg: &str) {
+        self.errors.push(LoxError::ScannerError {
+            lexeme: lexeme.to_string(),
+            line_no: self.curr,
+            message: msg.to_string(),
+            code,
+            span: Some(span),
+        });
+    }
+
+    // A string without interpolations becomes a single STRING token, otherwise
+    // `"a${x}b"` is desugared into the tokens of `("a" + STRINGIFY (x) + "b")`, the
+    // tokens added around the interpolated expressions spanning the whole string.
+    fn string(
+        &mut self,
+        lexeme: &str,
+        span: Span,
+        tokens: &mut Vec<Token>,
+    ) -> Result<(), LoxError> {
+        let body = &lexeme[1..(lexeme.len() - 1)];
+        let mut line = self.curr;
+        let mut text = String::new();
+        let mut parts = vec![];
+        let mut chars = body.char_indices().peekable();
+        while let Some((pos, ch)) = chars.next() {
+            match ch {
+                '\\' => text.push(self.escape(&mut chars, body, span.start + 1, pos, line)?),
+                '$' if chars.peek().map(|(_, ch)| *ch) == Some('{') => {
+                    chars.next();
+                    let start = pos + 2;
+                    let len = interpolation_len(&body[start..]);
+                    let mut inner = Lexer {
+                        curr: line,
+                        file_id: self.file_id,
+                        errors: vec![],
+                    };
+                    let inner_tokens =
+                        inner.scan(&body[start..(start + len)], span.start + 1 + start);
+                    line = inner.curr;
+                    self.errors.append(&mut inner.errors);
+                    parts.push((std::mem::take(&mut text), inner_tokens));
+                    while chars.peek().is_some_and(|(pos, _)| *pos <= start + len) {
+                        chars.next();
+                    }
+                }
+                '\n' => {
+                    line += 1;
+                    text.push(ch);
+                }
+                _ => text.push(ch),
+            }
+        }
+
+        if parts.is_empty() {
+            let token = Token::new(
+                TokenType::STRING,
+                self.curr,
+                Some(Literal::STRING(text)),
+                lexeme.to_string(),
+            );
+            tokens.push(token.with_span(span));
+            return Ok(());
+        }
+
+        let synthetic = |token_type, lexeme: &str, literal| {
+            Token::new(token_type, self.curr, literal, lexeme.to_string()).with_span(span)
+        };
+        let string = |text: String| {
+            synthetic(
+                TokenType::STRING,
+                &format!("\"{}\"", text),
+                Some(Literal::STRING(text)),
+            )
+        };
+        tokens.push(synthetic(TokenType::LeftParen, "(", None));
+        for (prefix, mut expr_tokens) in parts {
+            tokens.push(string(prefix));
+            tokens.push(synthetic(TokenType::PLUS, "+", None));
+            tokens.push(synthetic(TokenType::STRINGIFY, "${", None));
+            tokens.push(synthetic(TokenType::LeftParen, "(", None));
+            tokens.append(&mut expr_tokens);
+            tokens.push(synthetic(TokenType::RightParen, ")", None));
+            tokens.push(synthetic(TokenType::PLUS, "+", None));
+        }
+        tokens.push(string(text));
+        tokens.push(synthetic(TokenType::RightParen, ")", None));
+        Ok(())
+    }
+
+    fn escape(
+        &self,
+        chars: &mut Peekable<CharIndices>,
+        body: &str,
+        base: usize,
+        start: usize,
+        line: u32,
+    ) -> Result<char, LoxError> {
+        let invalid = |end: usize| LoxError::ScannerError {
+            lexeme: body[start..end].to_string(),
+            line_no: line,
+            message: "Invalid escape sequence.".to_string(),
+            code: ErrorCode::InvalidEscape,
+            span: Some(Span::new(self.file_id, base + start, base + end)),
+        };
+        let (pos, ch) = match chars.next() {
+            Some(next) => next,
+            None => return Err(invalid(body.len())),
+        };
+        Ok(match ch {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '"' | '$' => ch,
+            'u' => {
+                if chars.next().map(|(_, ch)| ch) != Some('{') {
+                    return Err(invalid(pos + 1));
+                }
+                let mut code = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, ch)) if ch.is_ascii_hexdigit() => code.push(ch),
+                        Some((end, ch)) => return Err(invalid(end + ch.len_utf8())),
+                        None => return Err(invalid(body.len())),
+                    }
+                }
+                let end = pos + code.len() + 3;
+                u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(|| invalid(end))?
+            }
+            _ => return Err(invalid(pos + ch.len_utf8())),
+        })
+    }
+}
+
+// The value of a number literal: an int, written in decimal, hex (0x), binary (0b) or octal
+// (0o), which becomes a BigInt past 64 bits, or a float with a fraction or exponent. Digits may be
+// separated by '_'.
+fn number(lexeme: &str) -> Result<Literal, (ErrorCode, &'static str)> {
+    let prefix = lexeme.get(..2).map(|prefix| prefix.to_ascii_lowercase());
+    let (radix, digits) = match prefix.as_deref() {
+        Some("0x") => (16, &lexeme[2..]),
+        Some("0b") => (2, &lexeme[2..]),
+        Some("0o") => (8, &lexeme[2..]),
+        _ => (10, lexeme),
+    };
+    let bytes = digits.as_bytes();
+    let is_digit = |i: Option<usize>| {
+        i.and_then(|i| bytes.get(i))
+            .is_some_and(|b| (*b as char).is_digit(radix))
+    };
+    for (i, _) in digits.match_indices('_') {
+        if !is_digit(i.checked_sub(1)) || !is_digit(Some(i + 1)) {
+            return Err((
+                ErrorCode::DigitSeparator,
+                "Digit separators are only allowed between digits.",
+            ));
+        }
+    }
+    let digits = digits.replace('_', "");
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+        return match digits.parse::<f64>() {
+            Ok(num) if radix == 10 => Ok(Literal::NUM(num)),
+            _ => Err((ErrorCode::MalformedNumber, "Malformed number.")),
+        };
+    }
+    match i64::from_str_radix(&digits, radix) {
+        Ok(val) => Ok(Literal::INT(val)),
+        Err(_) => BigInt::from_str_radix(&digits, radix)
+            .map(Literal::BIG)
+            .ok_or((ErrorCode::MalformedNumber, "Malformed number.")),
+    }
+}
+
+// The text of a doc comment, `///` or `/** */` but not `////` or `/***`, which are plain
+// comments as in Rust.
+fn doc_comment(comment: &str) -> Option<&str> {
+    if let Some(text) = comment.strip_prefix("///") {
+        return (!text.starts_with('/')).then(|| text.trim());
+    }
+    let text = comment.strip_prefix("/**")?.strip_suffix("*/")?;
+    (!text.is_empty() && !text.starts_with('*')).then(|| text.trim())
+}
+
+fn count_lines(src: &str) -> u32 {
+    src.matches('\n').count() as u32
+}
+
+// Fills in the lines and columns of spans from their byte offsets.
+fn set_positions<'a>(script: &str, spans: impl Iterator<Item = &'a mut Span>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(script.match_indices('\n').map(|(pos, _)| pos + 1))
+        .collect();
+    let position = |offset: usize| {
+        let line = line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = script[line_starts[line]..offset].chars().count();
+        (line as u32 + 1, column as u32 + 1)
+    };
+    for span in spans {
+        (span.line, span.column) = position(span.start);
+        (span.end_line, span.end_column) = position(span.end);
+    }
+}
+
+// Length of the expression inside `${...}`, excluding the closing brace.
+// The STRING token was already validated by logos, so the brace is always found.
+fn interpolation_len(src: &str) -> usize {
+    let mut lex = TokenType::lexer(src);
+    let mut depth = 0;
+    while let Some(tk) = lex.next() {
+        match tk {
+            TokenType::LeftBrace => depth += 1,
+            TokenType::RightBrace if depth == 0 => return lex.span().start,
+            TokenType::RightBrace => depth -= 1,
+            _ => {}
         }
-        return Ok(tokens);
     }
+    src.len()
 }
@@ -1,37 +1,66 @@
-use crate::error::LoxError;
+use crate::error::{ErrorCode, LoxError};
 use crate::frontend::definitions::expr::*;
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::pattern::{MatchArm, Pattern};
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::stmt::*;
 use crate::frontend::definitions::token::Token;
 use crate::frontend::definitions::token_type::TokenType;
 use crate::frontend::lexer::*;
+use crate::runtime::definitions::big_int::BigInt;
 use crate::runtime::definitions::lox_class::LoxClass;
 
 pub struct Parser {
     tokens: Vec<Token>,
     curr: usize,
+    // errors recovered from so far, in the order they were found
+    errors: Vec<LoxError>,
 }
 
 // TODO: Look into how enum matching is happening
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, curr: 0 }
+        Parser {
+            tokens,
+            curr: 0,
+            errors: vec![],
+        }
     }
 
+    // The first syntax error of the script, if any.
     pub fn parse(&mut self) -> Result<Vec<Stmt>, LoxError> {
-        let mut statements: Vec<Stmt> = Vec::new();
-        while !self.is_at_end() {
-            let val = self.declaration();
-            match val {
+        let (statements, mut errors) = self.parse_all();
+        if errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+
+    // Parses the whole script, skipping to the next statement after each syntax error, so that
+    // all errors are reported. The statements returned are the ones which parsed, blocks
+    // keeping whatever statements in them parsed.
+    pub fn parse_all(&mut self) -> (Vec<Stmt>, Vec<LoxError>) {
+        let statements = self.declarations(|parser| parser.is_at_end());
+        (statements, std::mem::take(&mut self.errors))
+    }
+
+    fn declarations(&mut self, is_end: fn(&Self) -> bool) -> Vec<Stmt> {
+        let mut statements = Vec::new();
+        while !is_end(self) {
+            match self.declaration() {
                 Ok(val) => statements.push(val),
                 Err(err) => {
-                    self.synchronize();
-                    return Err(err);
+                    self.errors.push(err);
+                    // a block's '}' may be where the error was found
+                    if !is_end(self) {
+                        self.synchronize();
+                    }
                 }
             }
         }
-        return Ok(statements);
+        statements
     }
 
     fn declaration(&mut self) -> Result<Stmt, LoxError> {
@@ -39,7 +68,7 @@ impl Parser {
             return self.class_declaration();
         }
         if self.validate(TokenType::FUN) {
-            return self.function("function");
+            return self.function("function", self.previous().span);
         }
         if self.validate(TokenType::VAR) {
             return self.val_declaration();
@@ -47,10 +76,17 @@ impl Parser {
         if self.validate(TokenType::STACKTRACE) {
             return self.stack_trace();
         }
+        if self.validate(TokenType::IMPORT) {
+            return self.import_declaration();
+        }
+        if self.validate(TokenType::FROM) {
+            return self.import_from_declaration();
+        }
         return self.statement();
     }
 
     fn class_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let start = self.previous().span;
         let name = self
             .consume(TokenType::IDENTIFIER, format!("Expect Class name"))?
             .clone();
@@ -58,14 +94,15 @@ impl Parser {
 
         if self.validate(TokenType::LESS) {
             self.consume(TokenType::IDENTIFIER, format!("Expect SuperClass name"))?;
-            super_class = Some(Variable::new(self.previous().clone()));
+            let name = self.previous().clone();
+            super_class = Some(Variable::new(name.clone(), name.span));
         }
         self.consume(TokenType::LeftBrace, format!("Expect {{ before class body"))?;
 
         let mut methods = Vec::new();
 
         while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            if let Stmt::Function(method) = self.function("method")? {
+            if let Stmt::Function(method) = self.function("method", self.peek().span)? {
                 methods.push(*method);
             }
         }
@@ -78,10 +115,11 @@ impl Parser {
             name.clone(),
             methods,
             super_class,
+            self.span_from(start),
         ))));
     }
 
-    fn function(&mut self, kind: &str) -> Result<Stmt, LoxError> {
+    fn function(&mut self, kind: &str, start: Span) -> Result<Stmt, LoxError> {
         let name = self
             .consume(TokenType::IDENTIFIER, format!("Expect {} name", kind))?
             .clone();
@@ -93,6 +131,7 @@ impl Parser {
                 if params.len() > 255 {
                     return Err(Self::error(
                         self.peek().clone(),
+                        ErrorCode::TooManyArguments,
                         "m ax no. of args 255".to_string(),
                     ));
                 }
@@ -113,10 +152,12 @@ impl Parser {
             name.clone(),
             params,
             body,
+            self.span_from(start),
         ))));
     }
 
     fn val_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let start = self.previous().span;
         let name = self.consume(TokenType::IDENTIFIER, "Expect Variable Name.".to_string())?;
         let name = name.clone();
         let mut initializer = None;
@@ -124,15 +165,78 @@ impl Parser {
             initializer = Some(self.expression()?);
         }
         self.consume(TokenType::SEMICOLON, "Expect ';' after value.".to_string())?;
-        Ok(Stmt::Var(Box::new(Var::new(name, initializer))))
+        Ok(Stmt::Var(Box::new(Var::new(
+            name,
+            initializer,
+            self.span_from(start),
+        ))))
+    }
+
+    fn import_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let path = self
+            .consume(
+                TokenType::STRING,
+                "Expect module path after 'import'.".to_string(),
+            )?
+            .clone();
+        self.consume(TokenType::AS, "Expect 'as' after module path.".to_string())?;
+        let name = self
+            .consume(TokenType::IDENTIFIER, "Expect module name.".to_string())?
+            .clone();
+        self.consume(TokenType::SEMICOLON, "Expect ';' after import.".to_string())?;
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::Import(Box::new(Import::new(
+            keyword,
+            path,
+            Some(name),
+            vec![],
+            span,
+        ))))
+    }
+
+    fn import_from_declaration(&mut self) -> Result<Stmt, LoxError> {
+        let start = self.previous().span;
+        let path = self
+            .consume(
+                TokenType::STRING,
+                "Expect module path after 'from'.".to_string(),
+            )?
+            .clone();
+        let keyword = self
+            .consume(
+                TokenType::IMPORT,
+                "Expect 'import' after module path.".to_string(),
+            )?
+            .clone();
+        let mut names = vec![];
+        loop {
+            names.push(
+                self.consume(TokenType::IDENTIFIER, "Expect name to import.".to_string())?
+                    .clone(),
+            );
+            if !self.validate(TokenType::COMMA) {
+                break;
+            }
+        }
+        self.consume(TokenType::SEMICOLON, "Expect ';' after import.".to_string())?;
+        Ok(Stmt::Import(Box::new(Import::new(
+            keyword,
+            path,
+            None,
+            names,
+            self.span_from(start),
+        ))))
     }
 
     fn stack_trace(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
         self.consume(
             TokenType::SEMICOLON,
             "Expect ';' after stack trace.".to_string(),
         )?;
-        Ok(Stmt::StackTrace)
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::StackTrace(Box::new(StackTrace::new(keyword, span))))
     }
 
     fn statement(&mut self) -> Result<Stmt, LoxError> {
@@ -140,7 +244,12 @@ impl Parser {
             return self.print_statement();
         }
         if self.validate(TokenType::LeftBrace) {
-            return Ok(Stmt::Block(Box::new(Block::new(self.block()?))));
+            let start = self.previous().span;
+            let statements = self.block()?;
+            return Ok(Stmt::Block(Box::new(Block::new(
+                statements,
+                self.span_from(start),
+            ))));
         }
         if self.validate(TokenType::IF) {
             return self.if_statement();
@@ -160,6 +269,18 @@ impl Parser {
         if self.validate(TokenType::RETURN) {
             return self.return_statement();
         }
+        if self.validate(TokenType::YIELD) {
+            return self.yield_statement();
+        }
+        if self.validate(TokenType::TRY) {
+            return self.try_statement();
+        }
+        if self.validate(TokenType::THROW) {
+            return self.throw_statement();
+        }
+        if self.validate(TokenType::MATCH) {
+            return self.match_statement();
+        }
 
         return self.expressions_statement();
     }
@@ -171,38 +292,134 @@ impl Parser {
             value = Some(self.expression()?);
         }
         self.consume(TokenType::SEMICOLON, "Expect ';' after return.".to_string())?;
-        return Ok(Stmt::Return(Box::new(Return::new(keyword, value))));
+        let span = self.span_from(keyword.span);
+        return Ok(Stmt::Return(Box::new(Return::new(keyword, value, span))));
+    }
+
+    fn yield_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let mut value = None;
+        if !self.check(TokenType::SEMICOLON) {
+            value = Some(self.expression()?);
+        }
+        self.consume(TokenType::SEMICOLON, "Expect ';' after yield.".to_string())?;
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::Yield(Box::new(Yield::new(keyword, value, span))))
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        self.consume(TokenType::LeftBrace, "Expect '{' after 'try'.".to_string())?;
+        let body = self.block()?;
+
+        let mut catch = None;
+        if self.validate(TokenType::CATCH) {
+            self.consume(
+                TokenType::LeftParen,
+                "Expect '(' after 'catch'.".to_string(),
+            )?;
+            let name = self
+                .consume(
+                    TokenType::IDENTIFIER,
+                    "Expect exception variable name.".to_string(),
+                )?
+                .clone();
+            self.consume(
+                TokenType::RightParen,
+                "Expect ')' after exception variable.".to_string(),
+            )?;
+            self.consume(
+                TokenType::LeftBrace,
+                "Expect '{' before catch body.".to_string(),
+            )?;
+            catch = Some(Catch::new(name, self.block()?));
+        }
+
+        let mut finally = None;
+        if self.validate(TokenType::FINALLY) {
+            self.consume(
+                TokenType::LeftBrace,
+                "Expect '{' after 'finally'.".to_string(),
+            )?;
+            finally = Some(self.block()?);
+        }
+
+        if catch.is_none() && finally.is_none() {
+            return Err(Self::error(
+                self.peek().clone(),
+                ErrorCode::ExpectToken,
+                "Expect 'catch' or 'finally' after try block.".to_string(),
+            ));
+        }
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::Try(Box::new(Try::new(
+            keyword, body, catch, finally, span,
+        ))))
+    }
+
+    fn throw_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after throw value.".to_string(),
+        )?;
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::Throw(Box::new(Throw::new(keyword, value, span))))
+    }
+
+    fn match_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
+        let subject = self.match_subject()?;
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let (case, pattern, guard) = self.match_case()?;
+            arms.push(MatchArm::new(case, pattern, guard, self.statement()?));
+        }
+        self.consume(
+            TokenType::RightBrace,
+            "Expect '}' after match arms.".to_string(),
+        )?;
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::Match(Box::new(Match::new(
+            keyword, subject, arms, span,
+        ))))
     }
 
     fn break_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
         self.consume(TokenType::SEMICOLON, "Expect ';' after break.".to_string())?;
-        return Ok(Stmt::Break(Box::new(Break::new(self.previous().clone()))));
+        let span = self.span_from(keyword.span);
+        return Ok(Stmt::Break(Box::new(Break::new(keyword, span))));
     }
 
     fn continue_statement(&mut self) -> Result<Stmt, LoxError> {
+        let keyword = self.previous().clone();
         self.consume(
             TokenType::SEMICOLON,
             "Expect ';' after continue.".to_string(),
         )?;
-        return Ok(Stmt::Continue(Box::new(Continue::new(
-            self.previous().clone(),
-        ))));
+        let span = self.span_from(keyword.span);
+        return Ok(Stmt::Continue(Box::new(Continue::new(keyword, span))));
     }
 
     fn block(&mut self) -> Result<Vec<Stmt>, LoxError> {
-        let mut list = Vec::new();
-        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            list.push(self.declaration()?)
-        }
+        let list =
+            self.declarations(|parser| parser.check(TokenType::RightBrace) || parser.is_at_end());
         self.consume(TokenType::RightBrace, "Expect '}' after block".to_string())?;
         return Ok(list);
     }
 
     fn print_statement(&mut self) -> Result<Stmt, LoxError> {
+        let start = self.previous().span;
         let val = self.expression()?;
         let token = self.previous().clone();
         self.consume(TokenType::SEMICOLON, "Expect ';' after value.".to_string())?;
-        return Ok(Stmt::Print(Box::new(Print::new(val, token))));
+        return Ok(Stmt::Print(Box::new(Print::new(
+            val,
+            token,
+            self.span_from(start),
+        ))));
     }
 
     fn while_statement(&mut self) -> Result<Stmt, LoxError> {
@@ -215,12 +432,18 @@ impl Parser {
         )?;
 
         let body = self.statement()?;
-        return Ok(Stmt::While(Box::new(While::new(condition, body, token))));
+        let span = self.span_from(token.span);
+        return Ok(Stmt::While(Box::new(While::new(
+            condition, body, token, span,
+        ))));
     }
 
     fn for_statement(&mut self) -> Result<Stmt, LoxError> {
         let token = self.previous().clone();
         self.consume(TokenType::LeftParen, "Expect '(' after 'for'.".to_string())?;
+        if self.check(TokenType::IDENTIFIER) && self.check_next(TokenType::IN) {
+            return self.for_in_statement(token);
+        }
 
         // Initialization
         let init;
@@ -253,27 +476,49 @@ impl Parser {
         )?;
 
         let mut body = self.statement()?;
+        // the statements the loop is desugared into all span the whole of it
+        let span = self.span_from(token.span);
 
         if let Some(increment) = increment {
-            body = Stmt::Block(Box::new(Block::new(vec![
-                body,
-                Stmt::Expression(Box::new(Expression::new(increment))),
-            ])))
+            let increment_span = increment.span();
+            body = Stmt::Block(Box::new(Block::new(
+                vec![
+                    body,
+                    Stmt::Expression(Box::new(Expression::new(increment, increment_span))),
+                ],
+                span,
+            )))
         }
 
         body = Stmt::While(Box::new(While::new(
-            condition.unwrap_or(Expr::Literal(Literal::BOOL(true))),
+            condition.unwrap_or(Expr::Literal(Literal::BOOL(true), token.span)),
             body,
             token,
+            span,
         )));
 
         if let Some(init) = init {
-            body = Stmt::Block(Box::new(Block::new(vec![init, body])));
+            body = Stmt::Block(Box::new(Block::new(vec![init, body], span)));
         }
 
         return Ok(body);
     }
 
+    fn for_in_statement(&mut self, keyword: Token) -> Result<Stmt, LoxError> {
+        let name = self.advance().clone();
+        self.advance();
+        let iterable = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            "Expect ')' after for clause.".to_string(),
+        )?;
+        let body = self.statement()?;
+        let span = self.span_from(keyword.span);
+        Ok(Stmt::ForIn(Box::new(ForIn::new(
+            keyword, name, iterable, body, span,
+        ))))
+    }
+
     fn if_statement(&mut self) -> Result<Stmt, LoxError> {
         let token = self.previous().clone();
         self.consume(TokenType::LeftParen, "Expect '(' after 'if'.".to_string())?;
@@ -288,18 +533,24 @@ impl Parser {
         if self.validate(TokenType::ELSE) {
             else_branch = Some(self.statement()?);
         }
+        let span = self.span_from(token.span);
         return Ok(Stmt::If(Box::new(If::new(
             condition,
             then_branch,
             else_branch,
             token,
+            span,
         ))));
     }
 
     fn expressions_statement(&mut self) -> Result<Stmt, LoxError> {
+        let start = self.peek().span;
         let val = self.expression()?;
         self.consume(TokenType::SEMICOLON, "Expect ';' after value.".to_string())?;
-        return Ok(Stmt::Expression(Box::new(Expression::new(val))));
+        return Ok(Stmt::Expression(Box::new(Expression::new(
+            val,
+            self.span_from(start),
+        ))));
     }
 
     fn expression(&mut self) -> Result<Expr, LoxError> {
@@ -310,6 +561,7 @@ impl Parser {
     }
 
     fn lambda_expr(&mut self, kind: &str) -> Result<Expr, LoxError> {
+        let start = self.previous().span;
         let paren = self
             .consume(TokenType::LeftParen, format!("Expect after {} name", kind))?
             .clone();
@@ -320,6 +572,7 @@ impl Parser {
                 if params.len() > 255 {
                     return Err(Self::error(
                         self.peek().clone(),
+                        ErrorCode::TooManyArguments,
                         "m ax no. of args 255".to_string(),
                     ));
                 }
@@ -336,42 +589,131 @@ impl Parser {
         self.consume(TokenType::LeftBrace, "Expect '{' before body".to_string())?;
 
         let body = self.block()?;
-        return Ok(Expr::Lambda(Box::new(Lambda::new(paren, params, body))));
+        return Ok(Expr::Lambda(Box::new(Lambda::new(
+            paren,
+            params,
+            body,
+            self.span_from(start),
+        ))));
     }
 
     fn assignment(&mut self) -> Result<Expr, LoxError> {
-        let expr = self.or()?;
+        let expr = self.conditional()?;
 
         if self.validate(TokenType::EQUAL) {
             let equals = self.previous().clone();
             let value = self.assignment()?;
+            let span = expr.span().to(value.span());
             if let Expr::Variable(name) = expr {
-                Ok(Expr::Assign(Box::new(Assign::new(name.name, value))))
+                Ok(Expr::Assign(Box::new(Assign::new(name.name, value, span))))
             } else if let Expr::Get(get_expr) = expr {
                 Ok(Expr::Set(Box::new(Set::new(
                     get_expr.object,
                     get_expr.name.clone(),
                     value,
+                    span,
+                ))))
+            } else if let Expr::Index(index_expr) = expr {
+                Ok(Expr::SetIndex(Box::new(SetIndex::new(
+                    index_expr.object,
+                    index_expr.bracket,
+                    index_expr.index,
+                    value,
+                    span,
                 ))))
             } else {
-                Err(LoxError::RuntimeError(
-                    equals.lexeme.clone(),
-                    equals.line_no,
-                    String::from("Invalid assignment"),
+                Err(LoxError::runtime(
+                    &equals,
+                    ErrorCode::InvalidAssignment,
+                    "Invalid assignment",
                 ))
             }
+        } else if self.validate(TokenType::PlusEqual)
+            | self.validate(TokenType::MinusEqual)
+            | self.validate(TokenType::StarEqual)
+            | self.validate(TokenType::SlashEqual)
+        {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+            let span = expr.span().to(value.span());
+            self.compound(expr, operator, value, false, span)
         } else {
             Ok(expr)
         }
     }
 
+    // A compound assignment or increment, as long as the target can be assigned to.
+    fn compound(
+        &self,
+        target: Expr,
+        mut operator: Token,
+        value: Expr,
+        postfix: bool,
+        span: Span,
+    ) -> Result<Expr, LoxError> {
+        if !matches!(target, Expr::Variable(_) | Expr::Get(_) | Expr::Index(_)) {
+            return Err(LoxError::runtime(
+                &operator,
+                ErrorCode::InvalidAssignment,
+                "Invalid assignment",
+            ));
+        }
+        operator.token_type = match operator.token_type {
+            TokenType::PlusEqual | TokenType::PlusPlus => TokenType::PLUS,
+            TokenType::MinusEqual | TokenType::MinusMinus => TokenType::MINUS,
+            TokenType::StarEqual => TokenType::STAR,
+            _ => TokenType::SLASH,
+        };
+        Ok(Expr::Compound(Box::new(Compound::new(
+            target, operator, value, postfix, span,
+        ))))
+    }
+
+    fn conditional(&mut self) -> Result<Expr, LoxError> {
+        let expr = self.coalesce()?;
+
+        if self.validate(TokenType::QUESTION) {
+            let question = self.previous().clone();
+            let then_branch = self.expression()?;
+            self.consume(
+                TokenType::COLON,
+                "Expect ':' after then branch of conditional.".to_string(),
+            )?;
+            let else_branch = self.conditional()?;
+            let span = expr.span().to(else_branch.span());
+            return Ok(Expr::Conditional(Box::new(Conditional::new(
+                expr,
+                question,
+                then_branch,
+                else_branch,
+                span,
+            ))));
+        }
+
+        Ok(expr)
+    }
+
+    fn coalesce(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.or()?;
+
+        while self.validate(TokenType::QuestionQuestion) {
+            let operator = self.previous().clone();
+            let right = self.or()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Coalesce(Box::new(Coalesce::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
     fn or(&mut self) -> Result<Expr, LoxError> {
         let mut expr = self.and()?;
 
         while self.validate(TokenType::OR) {
             let operator = self.previous().clone();
             let right = self.and()?;
-            expr = Expr::Logical(Box::new(Logical::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Logical(Box::new(Logical::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
@@ -383,7 +725,8 @@ impl Parser {
         while self.validate(TokenType::AND) {
             let operator = self.previous().clone();
             let right = self.equality()?;
-            expr = Expr::Logical(Box::new(Logical::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Logical(Box::new(Logical::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
@@ -395,23 +738,79 @@ impl Parser {
         while self.validate(TokenType::BangEqual) | self.validate(TokenType::EqualEqual) {
             let operator = self.previous().clone();
             let right = self.comparison()?;
-            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
     }
 
     fn comparison(&mut self) -> Result<Expr, LoxError> {
-        let mut expr = self.addition()?;
+        let mut expr = self.bit_or()?;
 
         while self.validate(TokenType::GREATER)
             | self.validate(TokenType::GreaterEqual)
             | self.validate(TokenType::LESS)
             | self.validate(TokenType::LessEqual)
         {
+            let operator = self.previous().clone();
+            let right = self.bit_or()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
+    // The bitwise operators bind tighter than comparisons, as in Python, so that
+    // `x & mask == 0` tests the masked bits.
+    fn bit_or(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.bit_xor()?;
+
+        while self.validate(TokenType::PIPE) {
+            let operator = self.previous().clone();
+            let right = self.bit_xor()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.bit_and()?;
+
+        while self.validate(TokenType::CARET) {
+            let operator = self.previous().clone();
+            let right = self.bit_and()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.shift()?;
+
+        while self.validate(TokenType::AMPERSAND) {
+            let operator = self.previous().clone();
+            let right = self.shift()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
+    fn shift(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.addition()?;
+
+        while self.validate(TokenType::LessLess) | self.validate(TokenType::GreaterGreater) {
             let operator = self.previous().clone();
             let right = self.addition()?;
-            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
@@ -423,7 +822,8 @@ impl Parser {
         while self.validate(TokenType::MINUS) | self.validate(TokenType::PLUS) {
             let operator = self.previous().clone();
             let right = self.multiplication()?;
-            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
@@ -432,26 +832,72 @@ impl Parser {
     fn multiplication(&mut self) -> Result<Expr, LoxError> {
         let mut expr = self.unary()?;
 
-        while self.validate(TokenType::SLASH) | self.validate(TokenType::STAR) {
+        while self.validate(TokenType::SLASH)
+            | self.validate(TokenType::STAR)
+            | self.validate(TokenType::TildeSlash)
+            | self.validate(TokenType::PERCENT)
+        {
             let operator = self.previous().clone();
             let right = self.unary()?;
-            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right)));
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
         }
 
         return Ok(expr);
     }
 
     fn unary(&mut self) -> Result<Expr, LoxError> {
-        if self.validate(TokenType::BANG) | self.validate(TokenType::MINUS) {
+        if self.validate(TokenType::BANG)
+            | self.validate(TokenType::MINUS)
+            | self.validate(TokenType::TILDE)
+            | self.validate(TokenType::STRINGIFY)
+        {
             let operator = self.previous().clone();
             let right = self.unary()?;
-            return Ok(Expr::Unary(Box::new(Unary::new(operator, right))));
+            let span = operator.span.to(right.span());
+            return Ok(Expr::Unary(Box::new(Unary::new(operator, right, span))));
+        }
+        if self.validate(TokenType::PlusPlus) | self.validate(TokenType::MinusMinus) {
+            let operator = self.previous().clone();
+            let target = self.unary()?;
+            let span = operator.span.to(target.span());
+            let one = Expr::Literal(Literal::INT(1), operator.span);
+            return self.compound(target, operator, one, false, span);
+        }
+        return self.power();
+    }
+
+    // `**` binds tighter than a unary operator on its left, so -2 ** 2 is -4, and is right
+    // associative, its right operand being parsed as a unary.
+    fn power(&mut self) -> Result<Expr, LoxError> {
+        let mut expr = self.postfix()?;
+
+        if self.validate(TokenType::StarStar) {
+            let operator = self.previous().clone();
+            let right = self.unary()?;
+            let span = expr.span().to(right.span());
+            expr = Expr::Binary(Box::new(Binary::new(expr, operator, right, span)));
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, LoxError> {
+        let expr = self.call()?;
+
+        if self.validate(TokenType::PlusPlus) | self.validate(TokenType::MinusMinus) {
+            let operator = self.previous().clone();
+            let span = expr.span().to(operator.span);
+            let one = Expr::Literal(Literal::INT(1), operator.span);
+            return self.compound(expr, operator, one, true, span);
         }
-        return self.call();
+
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<Expr, LoxError> {
         let mut expr = self.primary()?;
+        let mut optional = false;
 
         loop {
             if self.validate(TokenType::LeftParen) {
@@ -461,12 +907,35 @@ impl Parser {
                     TokenType::IDENTIFIER,
                     "Expect property name after '.'.".to_string(),
                 )?;
-                expr = Expr::Get(Box::new(Get::new(expr, name.clone())));
+                let span = expr.span().to(name.span);
+                expr = Expr::Get(Box::new(Get::new(expr, name.clone(), span)));
+            } else if self.validate(TokenType::QuestionDot) {
+                let name = self.consume(
+                    TokenType::IDENTIFIER,
+                    "Expect property name after '?.'.".to_string(),
+                )?;
+                let span = expr.span().to(name.span);
+                expr = Expr::SafeGet(Box::new(SafeGet::new(expr, name.clone(), span)));
+                optional = true;
+            } else if self.validate(TokenType::LeftBracket) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(
+                    TokenType::RightBracket,
+                    "Expect ']' after index.".to_string(),
+                )?;
+                let span = self.span_from(expr.span());
+                expr = Expr::Index(Box::new(Index::new(expr, bracket, index, span)));
             } else {
                 break;
             }
         }
 
+        // the whole chain is nil when a `?.` in it short-circuits
+        if optional {
+            let span = expr.span();
+            expr = Expr::OptionalChain(Box::new(OptionalChain::new(expr, span)));
+        }
         return Ok(expr);
     }
 
@@ -477,6 +946,7 @@ impl Parser {
                 if args.len() > 255 {
                     return Err(Self::error(
                         self.peek().clone(),
+                        ErrorCode::TooManyArguments,
                         "m ax no. of args 255".to_string(),
                     ));
                 }
@@ -487,13 +957,20 @@ impl Parser {
             }
         }
 
-        let paren = self.consume(TokenType::RightParen, "Expect ')' after args.".to_string())?;
-        return Ok(Expr::Call(Box::new(Call::new(callee, paren.clone(), args))));
+        let paren = self
+            .consume(TokenType::RightParen, "Expect ')' after args.".to_string())?
+            .clone();
+        let span = callee.span().to(paren.span);
+        return Ok(Expr::Call(Box::new(Call::new(callee, paren, args, span))));
     }
 
     fn primary(&mut self) -> Result<Expr, LoxError> {
         if self.validate(TokenType::THIS) {
-            return Ok(Expr::This(Box::new(This::new(self.previous().clone()))));
+            let keyword = self.previous().clone();
+            return Ok(Expr::This(Box::new(This::new(
+                keyword.clone(),
+                keyword.span,
+            ))));
         }
         if self.validate(TokenType::SUPER) {
             let keyword = self.previous().clone();
@@ -504,43 +981,237 @@ impl Parser {
                     "Expect super class method name.".to_string(),
                 )?
                 .clone();
-            return Ok(Expr::Super(Box::new(Super::new(method, keyword))));
+            let span = keyword.span.to(method.span);
+            return Ok(Expr::Super(Box::new(Super::new(method, keyword, span))));
         }
         if self.validate(TokenType::FALSE) {
-            return Ok(Expr::Literal(Literal::BOOL(false)));
+            return Ok(Expr::Literal(Literal::BOOL(false), self.previous().span));
         }
         if self.validate(TokenType::TRUE) {
-            return Ok(Expr::Literal(Literal::BOOL(true)));
+            return Ok(Expr::Literal(Literal::BOOL(true), self.previous().span));
         }
         if self.validate(TokenType::NIL) {
-            return Ok(Expr::Literal(Literal::NIL));
+            return Ok(Expr::Literal(Literal::NIL, self.previous().span));
         }
 
         if self.validate(TokenType::NUMBER) || self.validate(TokenType::STRING) {
-            return Ok(Expr::Literal(self.previous().literal.clone().unwrap()));
+            let token = self.previous();
+            return Ok(Expr::Literal(token.literal.clone().unwrap(), token.span));
+        }
+
+        if self.validate(TokenType::LeftBracket) {
+            return self.list_expr();
+        }
+
+        if self.validate(TokenType::LeftBrace) {
+            return self.map_expr();
+        }
+
+        if self.validate(TokenType::MATCH) {
+            return self.match_expr();
         }
 
         if self.validate(TokenType::LeftParen) {
+            let start = self.previous().span;
             let expr = self.expression()?;
             self.consume(
                 TokenType::RightParen,
                 "Expect ')' after expression.".to_string(),
             )?;
-            return Ok(Expr::Grouping(Box::new(Grouping::new(expr))));
+            return Ok(Expr::Grouping(Box::new(Grouping::new(
+                expr,
+                self.span_from(start),
+            ))));
         }
 
         if self.validate(TokenType::IDENTIFIER) {
+            let name = self.previous().clone();
             return Ok(Expr::Variable(Box::new(Variable::new(
-                self.previous().clone(),
+                name.clone(),
+                name.span,
             ))));
         }
 
         return Err(Self::error(
             self.peek().clone(),
+            ErrorCode::ExpectExpression,
             "Expect Expression".to_string(),
         ));
     }
 
+    fn list_expr(&mut self) -> Result<Expr, LoxError> {
+        let bracket = self.previous().clone();
+        let mut elements = Vec::new();
+        if !self.check(TokenType::RightBracket) {
+            loop {
+                elements.push(self.expression()?);
+                if !self.validate(TokenType::COMMA) {
+                    break;
+                };
+            }
+        }
+        self.consume(
+            TokenType::RightBracket,
+            "Expect ']' after list elements.".to_string(),
+        )?;
+        let span = self.span_from(bracket.span);
+        Ok(Expr::List(Box::new(List::new(bracket, elements, span))))
+    }
+
+    fn map_expr(&mut self) -> Result<Expr, LoxError> {
+        let brace = self.previous().clone();
+        let mut entries = Vec::new();
+        if !self.check(TokenType::RightBrace) {
+            loop {
+                let key = self.expression()?;
+                self.consume(TokenType::COLON, "Expect ':' after map key.".to_string())?;
+                let value = self.expression()?;
+                entries.push((key, value));
+                if !self.validate(TokenType::COMMA) {
+                    break;
+                };
+            }
+        }
+        self.consume(
+            TokenType::RightBrace,
+            "Expect '}' after map entries.".to_string(),
+        )?;
+        let span = self.span_from(brace.span);
+        Ok(Expr::Map(Box::new(Map::new(brace, entries, span))))
+    }
+
+    fn match_expr(&mut self) -> Result<Expr, LoxError> {
+        let keyword = self.previous().clone();
+        let subject = self.match_subject()?;
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let (case, pattern, guard) = self.match_case()?;
+            arms.push(MatchArm::new(case, pattern, guard, self.expression()?));
+            if !self.validate(TokenType::COMMA) {
+                break;
+            }
+        }
+        self.consume(
+            TokenType::RightBrace,
+            "Expect '}' after match arms.".to_string(),
+        )?;
+        let span = self.span_from(keyword.span);
+        Ok(Expr::Match(Box::new(MatchExpr::new(
+            keyword, subject, arms, span,
+        ))))
+    }
+
+    // `(subject) {`, after the `match` keyword.
+    fn match_subject(&mut self) -> Result<Expr, LoxError> {
+        self.consume(
+            TokenType::LeftParen,
+            "Expect '(' after 'match'.".to_string(),
+        )?;
+        let subject = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            "Expect ')' after match subject.".to_string(),
+        )?;
+        self.consume(
+            TokenType::LeftBrace,
+            "Expect '{' before match arms.".to_string(),
+        )?;
+        Ok(subject)
+    }
+
+    // `case pattern if guard =>`, up to the arm's body.
+    fn match_case(&mut self) -> Result<(Token, Pattern, Option<Expr>), LoxError> {
+        let case = self
+            .consume(
+                TokenType::CASE,
+                "Expect 'case' before match arm.".to_string(),
+            )?
+            .clone();
+        let pattern = self.pattern()?;
+        let mut guard = None;
+        if self.validate(TokenType::IF) {
+            guard = Some(self.expression()?);
+        }
+        self.consume(
+            TokenType::FatArrow,
+            "Expect '=>' after match pattern.".to_string(),
+        )?;
+        Ok((case, pattern, guard))
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, LoxError> {
+        if self.validate(TokenType::UNDERSCORE) {
+            return Ok(Pattern::Wildcard(self.previous().clone()));
+        }
+        if self.validate(TokenType::IDENTIFIER) {
+            let name = self.previous().clone();
+            // a name after another one makes the first a class
+            let class = Variable::new(name.clone(), name.span);
+            if self.validate(TokenType::IDENTIFIER) {
+                return Ok(Pattern::Class(class, Some(self.previous().clone())));
+            }
+            if self.validate(TokenType::UNDERSCORE) {
+                return Ok(Pattern::Class(class, None));
+            }
+            return Ok(Pattern::Binding(name));
+        }
+        if self.validate(TokenType::LeftBracket) {
+            let bracket = self.previous().clone();
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RightBracket) {
+                loop {
+                    elements.push(self.pattern()?);
+                    if !self.validate(TokenType::COMMA) {
+                        break;
+                    }
+                }
+            }
+            self.consume(
+                TokenType::RightBracket,
+                "Expect ']' after list pattern.".to_string(),
+            )?;
+            return Ok(Pattern::List(bracket, elements));
+        }
+        if self.validate(TokenType::MINUS) {
+            let minus = self.previous().clone();
+            let literal = match self
+                .consume(TokenType::NUMBER, "Expect number after '-'.".to_string())?
+                .literal
+                .clone()
+            {
+                Some(Literal::INT(val)) => match val.checked_neg() {
+                    Some(val) => Literal::INT(val),
+                    None => Literal::BIG(BigInt::from_i64(val).neg()),
+                },
+                Some(Literal::BIG(val)) => match val.neg().to_i64() {
+                    Some(val) => Literal::INT(val),
+                    None => Literal::BIG(val.neg()),
+                },
+                Some(Literal::NUM(val)) => Literal::NUM(-val),
+                literal => literal.unwrap(),
+            };
+            return Ok(Pattern::Literal(literal, minus));
+        }
+        if self.validate(TokenType::NUMBER) || self.validate(TokenType::STRING) {
+            let token = self.previous().clone();
+            return Ok(Pattern::Literal(token.literal.clone().unwrap(), token));
+        }
+        let literal = if self.validate(TokenType::TRUE) {
+            Literal::BOOL(true)
+        } else if self.validate(TokenType::FALSE) {
+            Literal::BOOL(false)
+        } else if self.validate(TokenType::NIL) {
+            Literal::NIL
+        } else {
+            return Err(Self::error(
+                self.peek().clone(),
+                ErrorCode::ExpectExpression,
+                "Expect pattern.".to_string(),
+            ));
+        };
+        Ok(Pattern::Literal(literal, self.previous().clone()))
+    }
+
     fn validate(&mut self, token: TokenType) -> bool {
         if self.check(token) {
             self.advance();
@@ -556,6 +1227,13 @@ impl Parser {
         return self.peek().token_type == token;
     }
 
+    // Whether the token after the current one is of the given type.
+    fn check_next(&self, token: TokenType) -> bool {
+        self.tokens
+            .get(self.curr + 1)
+            .is_some_and(|next| next.token_type == token)
+    }
+
     fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.curr += 1
@@ -573,17 +1251,36 @@ impl Parser {
         return &self.tokens[self.curr - 1];
     }
 
+    // From start to the end of the last token consumed.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous().span)
+    }
+
     fn consume(&mut self, token: TokenType, message: String) -> Result<&Token, LoxError> {
+        // the code is picked by what was expected, the message says where
+        let code = match token {
+            TokenType::SEMICOLON => ErrorCode::ExpectSemicolon,
+            TokenType::RightParen => ErrorCode::ExpectParen,
+            TokenType::RightBrace => ErrorCode::ExpectBrace,
+            TokenType::RightBracket => ErrorCode::ExpectBracket,
+            _ => ErrorCode::ExpectToken,
+        };
         return if self.check(token) {
             Ok(self.advance())
         } else {
             let err_token = self.peek();
-            Err(Self::error(err_token.clone(), message))
+            Err(Self::error(err_token.clone(), code, message))
         };
     }
 
-    fn error(token: Token, message: String) -> LoxError {
-        return LoxError::ParserError(token.lexeme.clone(), token.line_no, message);
+    fn error(token: Token, code: ErrorCode, message: String) -> LoxError {
+        return LoxError::ParserError {
+            lexeme: token.lexeme.clone(),
+            line_no: token.line_no,
+            message,
+            code,
+            span: Some(token.span),
+        };
     }
 
     fn synchronize(&mut self) {
@@ -595,7 +1292,8 @@ impl Parser {
                 return;
             };
             match self.peek().token_type {
-                CLASS | FUN | VAR | FOR | IF | WHILE | PRINT | RETURN => return,
+                CLASS | FUN | VAR | FOR | IF | WHILE | PRINT | RETURN | YIELD | TRY | THROW
+                | MATCH | IMPORT | FROM | RightBrace => return,
                 _ => {}
             };
             self.advance();
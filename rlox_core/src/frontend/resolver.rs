@@ -4,21 +4,28 @@ use crate::frontend::lexer::*;
 
 use crate::runtime::visitor::{VisitorMut, VisitorMutAcceptor};
 
-use crate::error::LoxError;
+use crate::error::{ErrorCode, LoxError};
 use crate::frontend::definitions::class_type::ClassType;
 use crate::frontend::definitions::function_type::FunctionType;
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::pattern::{MatchArm, Pattern};
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::token::Token;
 use std::collections::HashMap;
 
 // handle break/continue at resolve
 // static fields maybe
 
+// bool corresponds to if the value has been initialized, the span to where it was declared
+type Scope = HashMap<String, (bool, Option<Span>)>;
+
 pub struct Resolver {
-    // bool corresponds to if the value has been initialized
-    pub scopes: Vec<HashMap<String, bool>>,
+    pub scopes: Vec<Scope>,
     curr_class: ClassType,
     curr_function: FunctionType,
+    // how many loops the code being resolved is in, within the current function
+    loop_depth: usize,
+    errors: Vec<LoxError>,
 }
 
 impl Resolver {
@@ -27,16 +34,38 @@ impl Resolver {
             scopes: vec![],
             curr_class: ClassType::NONE,
             curr_function: FunctionType::NONE,
+            loop_depth: 0,
+            errors: vec![],
         }
     }
 
-    pub fn resolve<T: VisitorMutAcceptor<()> + Sized>(
-        &mut self,
-        expr: &mut T,
-    ) -> Result<(), LoxError> {
+    // Resolves a whole script, going on after errors so that all of them are reported, in the
+    // order they appear in.
+    pub fn resolve_all(&mut self, ast: &mut Vec<Stmt>) -> Result<(), Vec<LoxError>> {
+        let _ = self.resolve(ast);
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        self.reset();
+        Err(std::mem::take(&mut self.errors))
+    }
+
+    fn resolve<T: VisitorMutAcceptor<()> + Sized>(&mut self, expr: &mut T) -> Result<(), LoxError> {
         expr.accept(self)
     }
 
+    // Drops the scopes a failed resolve left open, so that the resolver can be reused.
+    fn reset(&mut self) {
+        self.scopes.clear();
+        self.curr_class = ClassType::NONE;
+        self.curr_function = FunctionType::NONE;
+        self.loop_depth = 0;
+    }
+
+    fn error(&mut self, err: LoxError) {
+        self.errors.push(err);
+    }
+
     fn resolve_local(&mut self, name: &mut Token) {
         for (i, scope) in self.scopes.iter().enumerate().rev() {
             if scope.contains_key(&name.lexeme) {
@@ -56,11 +85,12 @@ impl Resolver {
 
     fn declare(&mut self, token: &Token) -> Result<(), LoxError> {
         if let Some(scope) = self.scopes.last_mut() {
-            if scope.insert(token.lexeme.clone(), false).is_some() {
-                return Err(LoxError::RuntimeError(
-                    token.lexeme.clone(),
-                    token.line_no,
-                    String::from("Already exists"),
+            if let Some((_, first)) = scope.insert(token.lexeme.clone(), (false, Some(token.span)))
+            {
+                self.error(LoxError::runtime(
+                    token,
+                    ErrorCode::AlreadyExists(first.map(Box::new)),
+                    "Already exists",
                 ));
             }
         }
@@ -69,13 +99,16 @@ impl Resolver {
 
     fn define(&mut self, token: &Token) -> Result<(), LoxError> {
         if let Some(scope) = self.scopes.last_mut() {
-            scope
-                .insert(token.lexeme.clone(), true)
-                .ok_or(LoxError::SemanticError(
-                    token.lexeme.clone(),
-                    token.line_no,
-                    "Declaration without definition".to_string(),
-                ))?;
+            if scope
+                .insert(token.lexeme.clone(), (true, Some(token.span)))
+                .is_none()
+            {
+                self.error(LoxError::semantic(
+                    token,
+                    ErrorCode::DeclarationWithoutDefinition,
+                    "Declaration without definition",
+                ));
+            }
         }
         Ok(())
     }
@@ -84,6 +117,7 @@ impl Resolver {
         self.begin_scope();
         let currfn = self.curr_function;
         self.curr_function = dec;
+        let loop_depth = std::mem::take(&mut self.loop_depth);
 
         for param in &func.params {
             self.declare(&param)?;
@@ -92,6 +126,74 @@ impl Resolver {
         self.resolve(&mut func.body)?;
         self.end_scope();
         self.curr_function = currfn;
+        self.loop_depth = loop_depth;
+        Ok(())
+    }
+
+    // resolves a try/catch/finally body in its own scope, with the catch variable declared in it
+    fn resolve_clause(
+        &mut self,
+        body: &mut Vec<Stmt>,
+        name: Option<&Token>,
+    ) -> Result<(), LoxError> {
+        self.begin_scope();
+        if let Some(name) = name {
+            self.declare(name)?;
+            self.define(name)?;
+        }
+        let res = self.resolve(body);
+        self.end_scope();
+        res
+    }
+
+    // Resolves each arm in its own scope, holding the names its pattern binds. An arm after one
+    // that matches anything could never run.
+    fn resolve_arms<T: VisitorMutAcceptor<()>>(
+        &mut self,
+        arms: &mut [MatchArm<T>],
+    ) -> Result<(), LoxError> {
+        let count = arms.len();
+        for (i, arm) in arms.iter_mut().enumerate() {
+            if arm.pattern.is_irrefutable() && arm.guard.is_none() && i + 1 < count {
+                let token = arm.pattern.token();
+                self.error(LoxError::semantic(
+                    token,
+                    ErrorCode::Resolve,
+                    "Wildcard arm must come last",
+                ));
+            }
+            self.begin_scope();
+            self.resolve_pattern(&mut arm.pattern)?;
+            if let Some(guard) = &mut arm.guard {
+                self.resolve(guard)?;
+            }
+            let res = self.resolve(&mut arm.body);
+            self.end_scope();
+            res?;
+        }
+        Ok(())
+    }
+
+    fn resolve_pattern(&mut self, pattern: &mut Pattern) -> Result<(), LoxError> {
+        match pattern {
+            Pattern::Binding(name) => {
+                self.declare(name)?;
+                self.define(name)?;
+            }
+            Pattern::Class(class, name) => {
+                self.visit_variable_stmt(class)?;
+                if let Some(name) = name {
+                    self.declare(name)?;
+                    self.define(name)?;
+                }
+            }
+            Pattern::List(_, elements) => {
+                for element in elements {
+                    self.resolve_pattern(element)?;
+                }
+            }
+            Pattern::Wildcard(_) | Pattern::Literal(..) => {}
+        }
         Ok(())
     }
 
@@ -99,6 +201,7 @@ impl Resolver {
         self.begin_scope();
         let currfn = self.curr_function;
         self.curr_function = FunctionType::LAMBDA;
+        let loop_depth = std::mem::take(&mut self.loop_depth);
 
         for param in &func.params {
             self.declare(&param)?;
@@ -107,6 +210,7 @@ impl Resolver {
         self.resolve(&mut func.body)?;
         self.end_scope();
         self.curr_function = currfn;
+        self.loop_depth = loop_depth;
         Ok(())
     }
 }
@@ -136,7 +240,7 @@ impl VisitorMut<()> for Resolver {
         Ok(())
     }
 
-    fn visit_literal_expr(&mut self, _val: &mut Literal) -> Result<(), LoxError> {
+    fn visit_literal_expr(&mut self, _val: &mut Literal, _span: Span) -> Result<(), LoxError> {
         Ok(())
     }
 
@@ -164,11 +268,12 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_this_expr(&mut self, val: &mut This) -> Result<(), LoxError> {
         if self.curr_class == ClassType::NONE {
-            return Err(LoxError::SemanticError(
-                val.keyword.lexeme.clone(),
-                val.keyword.line_no,
-                "Cannot use this outside class.".to_string(),
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ThisOutsideClass,
+                "Cannot use this outside class.",
             ));
+            return Ok(());
         }
         self.resolve_local(&mut val.keyword);
         Ok(())
@@ -176,16 +281,79 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_super_expr(&mut self, val: &mut Super) -> Result<(), LoxError> {
         if self.curr_class != ClassType::SUBCLASS {
-            return Err(LoxError::SemanticError(
-                val.keyword.lexeme.clone(),
-                val.keyword.line_no,
-                "No super class as such.".to_string(),
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::SuperOutsideSubclass,
+                "No super class as such.",
             ));
+            return Ok(());
         }
         self.resolve_local(&mut val.keyword);
         Ok(())
     }
 
+    fn visit_list_expr(&mut self, val: &mut List) -> Result<(), LoxError> {
+        for element in &mut val.elements {
+            self.resolve(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_map_expr(&mut self, val: &mut Map) -> Result<(), LoxError> {
+        for (key, value) in &mut val.entries {
+            self.resolve(key)?;
+            self.resolve(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_index_expr(&mut self, val: &mut Index) -> Result<(), LoxError> {
+        self.resolve(&mut val.object)?;
+        self.resolve(&mut val.index)?;
+        Ok(())
+    }
+
+    fn visit_set_index_expr(&mut self, val: &mut SetIndex) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        self.resolve(&mut val.object)?;
+        self.resolve(&mut val.index)?;
+        Ok(())
+    }
+
+    fn visit_compound_expr(&mut self, val: &mut Compound) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        self.resolve(&mut val.target)?;
+        Ok(())
+    }
+
+    fn visit_conditional_expr(&mut self, val: &mut Conditional) -> Result<(), LoxError> {
+        self.resolve(&mut val.condition)?;
+        self.resolve(&mut val.then_branch)?;
+        self.resolve(&mut val.else_branch)?;
+        Ok(())
+    }
+
+    fn visit_coalesce_expr(&mut self, val: &mut Coalesce) -> Result<(), LoxError> {
+        self.resolve(&mut val.left)?;
+        self.resolve(&mut val.right)?;
+        Ok(())
+    }
+
+    fn visit_safe_get_expr(&mut self, val: &mut SafeGet) -> Result<(), LoxError> {
+        self.resolve(&mut val.object)?;
+        Ok(())
+    }
+
+    fn visit_optional_chain_expr(&mut self, val: &mut OptionalChain) -> Result<(), LoxError> {
+        self.resolve(&mut val.expr)?;
+        Ok(())
+    }
+
+    fn visit_match_expr(&mut self, val: &mut MatchExpr) -> Result<(), LoxError> {
+        self.resolve(&mut val.subject)?;
+        self.resolve_arms(&mut val.arms)
+    }
+
     fn visit_expression_stmt(&mut self, val: &mut Expression) -> Result<(), LoxError> {
         self.resolve(&mut val.expr)?;
         Ok(())
@@ -198,11 +366,11 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_variable_stmt(&mut self, val: &mut Variable) -> Result<(), LoxError> {
         if let Some(scope) = self.scopes.last() {
-            if let Some(false) = scope.get(&val.name.lexeme) {
-                return Err(LoxError::SemanticError(
-                    val.name.lexeme.clone(),
-                    val.name.line_no,
-                    "Cannot read local variable in its own initializer.".to_string(),
+            if let Some((false, _)) = scope.get(&val.name.lexeme) {
+                self.error(LoxError::semantic(
+                    &val.name,
+                    ErrorCode::OwnInitializer,
+                    "Cannot read local variable in its own initializer.",
                 ));
             }
         }
@@ -244,18 +412,44 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_while_stmt(&mut self, val: &mut While) -> Result<(), LoxError> {
         self.resolve(&mut val.condition)?;
-        match self.resolve(&mut val.body) {
-            Err(LoxError::Break(_)) | Err(LoxError::Continue(_)) | Ok(_) => Ok(()),
-            Err(err) => Err(err),
-        }
+        self.loop_depth += 1;
+        self.resolve(&mut val.body)?;
+        self.loop_depth -= 1;
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(&mut self, val: &mut ForIn) -> Result<(), LoxError> {
+        self.resolve(&mut val.iterable)?;
+        self.begin_scope();
+        self.declare(&val.name)?;
+        self.define(&val.name)?;
+        self.loop_depth += 1;
+        self.resolve(&mut val.body)?;
+        self.loop_depth -= 1;
+        self.end_scope();
+        Ok(())
     }
 
     fn visit_break_stmt(&mut self, val: &mut Break) -> Result<(), LoxError> {
-        Err(LoxError::Break(val.keyword.line_no))
+        if self.loop_depth == 0 {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::BreakOutsideLoop,
+                "break statements allowed only inside loops.",
+            ));
+        }
+        Ok(())
     }
 
     fn visit_continue_stmt(&mut self, val: &mut Continue) -> Result<(), LoxError> {
-        Err(LoxError::Continue(val.keyword.line_no))
+        if self.loop_depth == 0 {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ContinueOutsideLoop,
+                "continue statements allowed only inside loops.",
+            ));
+        }
+        Ok(())
     }
 
     fn visit_function_stmt(&mut self, val: &mut Function) -> Result<(), LoxError> {
@@ -268,17 +462,17 @@ impl VisitorMut<()> for Resolver {
     fn visit_return_stmt(&mut self, val: &mut Return) -> Result<(), LoxError> {
         if let Some(value) = &mut val.value {
             if self.curr_function == FunctionType::NONE {
-                return Err(LoxError::SemanticError(
-                    val.keyword.lexeme.clone(),
-                    val.keyword.line_no,
-                    "Return only from function".to_string(),
+                self.error(LoxError::semantic(
+                    &val.keyword,
+                    ErrorCode::ReturnOutsideFunction,
+                    "Return only from function",
                 ));
             }
             if self.curr_function == FunctionType::INITIALIZER {
-                return Err(LoxError::SemanticError(
-                    val.keyword.lexeme.clone(),
-                    val.keyword.line_no,
-                    "Cannot return from initializer".to_string(),
+                self.error(LoxError::semantic(
+                    &val.keyword,
+                    ErrorCode::ReturnFromInitializer,
+                    "Cannot return from initializer",
                 ));
             }
             self.resolve(value)?;
@@ -286,6 +480,27 @@ impl VisitorMut<()> for Resolver {
         Ok(())
     }
 
+    fn visit_yield_stmt(&mut self, val: &mut Yield) -> Result<(), LoxError> {
+        if self.curr_function == FunctionType::NONE {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::YieldOutsideFunction,
+                "Yield only from function",
+            ));
+        }
+        if self.curr_function == FunctionType::INITIALIZER {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ReturnFromInitializer,
+                "Cannot yield from initializer",
+            ));
+        }
+        if let Some(value) = &mut val.value {
+            self.resolve(value)?;
+        }
+        Ok(())
+    }
+
     fn visit_class_stmt(&mut self, val: &mut Class) -> Result<(), LoxError> {
         let curr_class = self.curr_class;
         self.curr_class = ClassType::CLASS;
@@ -294,10 +509,10 @@ impl VisitorMut<()> for Resolver {
         self.resolve_local(&mut val.name);
         if let Some(sp_class) = &mut val.superclass {
             if sp_class.name.lexeme == val.name.lexeme {
-                return Err(LoxError::SemanticError(
-                    val.name.lexeme.clone(),
-                    val.name.line_no,
-                    "Class can't inherit itself".to_string(),
+                self.error(LoxError::semantic(
+                    &val.name,
+                    ErrorCode::InheritsItself,
+                    "Class can't inherit itself",
                 ));
             }
             self.curr_class = ClassType::SUBCLASS;
@@ -307,14 +522,14 @@ impl VisitorMut<()> for Resolver {
             self.scopes
                 .last_mut()
                 .unwrap()
-                .insert("super".to_string(), true);
+                .insert("super".to_string(), (true, None));
         }
 
         self.begin_scope();
         self.scopes
             .last_mut()
             .unwrap()
-            .insert("this".to_string(), true);
+            .insert("this".to_string(), (true, None));
         for method in &mut val.methods {
             let dec = if method.name.lexeme == "init".to_string() {
                 FunctionType::INITIALIZER
@@ -325,14 +540,47 @@ impl VisitorMut<()> for Resolver {
         }
 
         self.end_scope();
-        if let Some(_) = &val.superclass {
+        if val.superclass.is_some() {
             self.end_scope();
         }
         self.curr_class = curr_class;
         Ok(())
     }
 
-    fn visit_stack_trace_stmt(&mut self) -> Result<(), LoxError> {
-        todo!()
+    fn visit_try_stmt(&mut self, val: &mut Try) -> Result<(), LoxError> {
+        self.resolve_clause(&mut val.body, None)?;
+        if let Some(catch) = &mut val.catch {
+            self.resolve_clause(&mut catch.body, Some(&catch.name))?;
+        }
+        if let Some(finally) = &mut val.finally {
+            self.resolve_clause(finally, None)?;
+        }
+        Ok(())
+    }
+
+    fn visit_match_stmt(&mut self, val: &mut Match) -> Result<(), LoxError> {
+        self.resolve(&mut val.subject)?;
+        self.resolve_arms(&mut val.arms)
+    }
+
+    fn visit_throw_stmt(&mut self, val: &mut Throw) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        Ok(())
+    }
+
+    fn visit_import_stmt(&mut self, val: &mut Import) -> Result<(), LoxError> {
+        // modules are globals, and are run in the order their imports appear
+        if !self.scopes.is_empty() {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ImportOutsideTopLevel,
+                "Can't import outside top-level code.",
+            ));
+        }
+        Ok(())
+    }
+
+    fn visit_stack_trace_stmt(&mut self, _val: &mut StackTrace) -> Result<(), LoxError> {
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+pub mod diagnostics;
 pub mod error;
 pub mod frontend;
 pub mod runtime;
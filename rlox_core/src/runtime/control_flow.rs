@@ -0,0 +1,65 @@
+use crate::error::{ErrorCode, LoxError};
+use crate::runtime::definitions::object::Object;
+
+// What makes the interpreter leave the statements it's running early: a return, a break or
+// continue, or an error. Only errors get out of a function call. A `?.` on nil short-circuits
+// to the end of the OptionalChain it's in.
+#[derive(Debug)]
+pub enum ControlFlow {
+    Return(Object, u32),
+    Break(u32),
+    Continue(u32),
+    ShortCircuit,
+    Error(LoxError),
+}
+
+impl ControlFlow {
+    // What a function call ends with when its body was left early.
+    pub fn into_return(self) -> Result<Object, LoxError> {
+        match self {
+            ControlFlow::Return(value, _) => Ok(value),
+            flow => Err(flow.into_error()),
+        }
+    }
+
+    // The resolver only lets a return without a value out of a function, which the script
+    // it's in reports as an error.
+    pub fn into_error(self) -> LoxError {
+        let (keyword, line_no, code, msg) = match self {
+            ControlFlow::Error(err) => return err,
+            // the parser puts every `?.` in an OptionalChain, which stops this
+            ControlFlow::ShortCircuit => unreachable!(),
+            ControlFlow::Return(_, line_no) => (
+                "return",
+                line_no,
+                ErrorCode::ReturnOutsideFunction,
+                "Return statements allowed only inside function/lambdas.",
+            ),
+            ControlFlow::Break(line_no) => (
+                "break",
+                line_no,
+                ErrorCode::BreakOutsideLoop,
+                "break statements allowed only inside loops.",
+            ),
+            ControlFlow::Continue(line_no) => (
+                "continue",
+                line_no,
+                ErrorCode::ContinueOutsideLoop,
+                "continue statements allowed only inside loops.",
+            ),
+        };
+        LoxError::SemanticError {
+            lexeme: keyword.to_string(),
+            line_no,
+            message: msg.to_string(),
+            code,
+            span: None,
+        }
+    }
+}
+
+impl From<LoxError> for ControlFlow {
+    fn from(err: LoxError) -> Self {
+        ControlFlow::Error(err)
+    }
+}
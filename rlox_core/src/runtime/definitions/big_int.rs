@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+// An arbitrary-precision integer, which int arithmetic overflowing i64 promotes to. The magnitude
+// is kept in base 2^32, least significant digit first and without leading zeros, so zero has no
+// digits and is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn from_i64(val: i64) -> Self {
+        let mag = val.unsigned_abs();
+        BigInt::new(val < 0, vec![mag as u32, (mag >> 32) as u32])
+    }
+
+    // The integer a float with no fractional part equals.
+    pub fn from_f64(val: f64) -> Option<Self> {
+        if !val.is_finite() || val.fract() != 0.0 {
+            return None;
+        }
+        if val.abs() < i64::MAX as f64 {
+            return Some(BigInt::from_i64(val as i64));
+        }
+        // a float this large is its 53 bit mantissa shifted left
+        let bits = val.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as u32 - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let mag = BigInt::from_i64(mantissa as i64).shl(exponent);
+        Some(if val < 0.0 { mag.neg() } else { mag })
+    }
+
+    // Decimal digits with an optional sign.
+    pub fn parse(text: &str) -> Option<Self> {
+        BigInt::from_str_radix(text, 10)
+    }
+
+    pub fn from_str_radix(text: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match text.as_bytes().first()? {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        if digits.is_empty() {
+            return None;
+        }
+        let mut mag = vec![];
+        for ch in digits.chars() {
+            mul_add_small(&mut mag, radix, ch.to_digit(radix)?);
+        }
+        Some(BigInt::new(negative, mag))
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+        let mag = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0u64, |acc, digit| (acc << 32) | *digit as u64);
+        if self.negative {
+            0i64.checked_sub_unsigned(mag)
+        } else {
+            i64::try_from(mag).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let mag = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0.0, |acc, digit| acc * 4294967296.0 + *digit as f64);
+        if self.negative {
+            -mag
+        } else {
+            mag
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn neg(&self) -> Self {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_mag(&self.digits, &other.digits));
+        }
+        match cmp_mag(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::new(other.negative, sub_mag(&other.digits, &self.digits)),
+            _ => BigInt::new(self.negative, sub_mag(&self.digits, &other.digits)),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_mag(&self.digits, &other.digits),
+        )
+    }
+
+    // Division rounding towards negative infinity, the remainder taking the divisor's sign.
+    // None when dividing by zero.
+    pub fn div_mod_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quot, rem) = div_rem_mag(&self.digits, &other.digits);
+        let quot = BigInt::new(self.negative != other.negative, quot);
+        let rem = BigInt::new(self.negative, rem);
+        if !rem.is_zero() && self.negative != other.negative {
+            return Some((quot.sub(&BigInt::from_i64(1)), rem.add(other)));
+        }
+        Some((quot, rem))
+    }
+
+    pub fn shl(&self, bits: u32) -> Self {
+        let mut pow = vec![0; bits as usize / 32];
+        pow.push(1 << (bits % 32));
+        BigInt::new(self.negative, mul_mag(&self.digits, &pow))
+    }
+
+    // Exponentiation by squaring.
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::from_i64(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+
+    // Rounds towards negative infinity, as dividing by a power of two would.
+    pub fn shr(&self, bits: u32) -> Self {
+        let pow = BigInt::from_i64(1).shl(bits);
+        self.div_mod_floor(&pow)
+            .map(|(quot, _)| quot)
+            .unwrap_or_default()
+    }
+
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+}
+
+impl Default for BigInt {
+    fn default() -> Self {
+        BigInt::new(false, vec![])
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.digits, &other.digits),
+            (true, true) => cmp_mag(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, writer: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the magnitude is divided by 10^9 to get its decimal digits nine at a time
+        let mut mag = self.digits.clone();
+        let mut chunks = vec![];
+        while !mag.is_empty() {
+            chunks.push(div_small(&mut mag, 1_000_000_000));
+        }
+        if self.negative {
+            writer.write_str("-")?;
+        }
+        match chunks.pop() {
+            Some(first) => write!(writer, "{}", first)?,
+            None => writer.write_str("0")?,
+        }
+        for chunk in chunks.iter().rev() {
+            write!(writer, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let total = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    sum.push(carry as u32);
+    sum
+}
+
+// a must not be smaller than b.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, digit) in a.iter().enumerate() {
+        let mut total = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (total < 0) as i64;
+        total += borrow << 32;
+        diff.push(total as u32);
+    }
+    diff
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let total = *x as u64 * *y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    product
+}
+
+// Shift-and-subtract long division, one bit of the quotient at a time.
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if b.len() == 1 {
+        let mut quot = a.to_vec();
+        let rem = div_small(&mut quot, b[0]);
+        return (quot, vec![rem]);
+    }
+    let mut quot = vec![0u32; a.len()];
+    let mut rem: Vec<u32> = vec![];
+    for bit in (0..a.len() * 32).rev() {
+        // rem = rem * 2 + the next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for digit in rem.iter_mut() {
+            let next = *digit >> 31;
+            *digit = (*digit << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            rem.push(carry);
+        }
+        if cmp_mag(&rem, b) != Ordering::Less {
+            rem = sub_mag(&rem, b);
+            while rem.last() == Some(&0) {
+                rem.pop();
+            }
+            quot[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quot, rem)
+}
+
+// Divides the magnitude in place, returning the remainder.
+fn div_small(mag: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for digit in mag.iter_mut().rev() {
+        let total = (rem << 32) | *digit as u64;
+        *digit = (total / divisor as u64) as u32;
+        rem = total % divisor as u64;
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    rem as u32
+}
+
+// mag = mag * factor + addend
+fn mul_add_small(mag: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for digit in mag.iter_mut() {
+        let total = *digit as u64 * factor as u64 + carry;
+        *digit = total as u32;
+        carry = total >> 32;
+    }
+    if carry != 0 {
+        mag.push(carry as u32);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::error::LoxError;
+use crate::error::{ErrorCode, LoxError};
 use crate::frontend::definitions::token::Token;
 use crate::runtime::definitions::lox_callable::LoxCallable;
 use crate::runtime::definitions::lox_function::LoxFunction;
 use crate::runtime::definitions::object::Object;
+use crate::runtime::environment::GlobalEnvironment;
 use crate::runtime::interpreter::Interpreter;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -38,6 +39,14 @@ impl LoxClass {
         }
         None
     }
+    // Whether the class is other or inherits from it. Copies of a class share its methods.
+    pub fn is_subclass_of(&self, other: &LoxClass) -> bool {
+        Rc::ptr_eq(&self.methods, &other.methods)
+            || self
+                .super_class
+                .as_ref()
+                .is_some_and(|super_class| super_class.is_subclass_of(other))
+    }
     pub fn bind_method(
         &self,
         name: &Token,
@@ -51,10 +60,10 @@ impl LoxClass {
             }
         }
 
-        Err(LoxError::RuntimeError(
-            name.lexeme.clone(),
-            name.line_no,
-            "Only Instances have properties".to_string(),
+        Err(LoxError::runtime(
+            name,
+            ErrorCode::Undefined,
+            "Undefined property",
         ))
     }
 }
@@ -69,6 +78,9 @@ impl LoxClass {
 pub struct LoxInstance {
     pub klass: LoxClass,
     fields: RefCell<HashMap<String, Object>>,
+    // for the namespace of a module, the module's globals and the names it exports, which are
+    // read and assigned there rather than copied into fields
+    module: Option<(GlobalEnvironment, Vec<String>)>,
 }
 
 impl LoxInstance {
@@ -76,15 +88,35 @@ impl LoxInstance {
         LoxInstance {
             klass,
             fields: RefCell::new(HashMap::new()),
+            module: None,
+        }
+    }
+
+    pub fn module(klass: LoxClass, globals: GlobalEnvironment, exports: Vec<String>) -> Self {
+        LoxInstance {
+            module: Some((globals, exports)),
+            ..LoxInstance::new(klass)
         }
     }
 
     pub fn get(&self, name: &Token) -> Option<Object> {
-        self.fields.borrow().get(&name.lexeme).cloned()
+        match &self.module {
+            Some((globals, exports)) if exports.contains(&name.lexeme) => {
+                globals.get(name.lexeme.clone())
+            }
+            _ => self.fields.borrow().get(&name.lexeme).cloned(),
+        }
     }
 
     pub fn set(&self, name: &Token, value: Object) {
-        self.fields.borrow_mut().insert(name.lexeme.clone(), value);
+        match &self.module {
+            Some((globals, exports)) if exports.contains(&name.lexeme) => {
+                globals.assign(name.lexeme.clone(), value);
+            }
+            _ => {
+                self.fields.borrow_mut().insert(name.lexeme.clone(), value);
+            }
+        }
     }
 }
 
@@ -1,8 +1,10 @@
 use crate::error::LoxError;
 use crate::frontend::definitions::expr::Lambda;
 use crate::frontend::definitions::stmt::Function;
+use crate::runtime::control_flow::ControlFlow;
 use crate::runtime::definitions::lox_callable::LoxCallable;
 use crate::runtime::definitions::lox_class::LoxInstance;
+use crate::runtime::definitions::lox_generator::LoxGenerator;
 use crate::runtime::definitions::object::Object;
 use crate::runtime::environment::LocalEnvironment;
 use crate::runtime::interpreter::Interpreter;
@@ -52,17 +54,20 @@ impl LoxCallable for LoxFunction {
         for (param, arg) in self.declaration.borrow().params.iter().zip(args) {
             env.define_at(param.lexeme.clone(), arg, 0);
         }
-        let val = intrprt.execute_block(&self.declaration.borrow().body, env);
-        if let Err(LoxError::ReturnVal(val, _)) = val {
-            if self.is_init {
-                return Ok(self
-                    .closure
-                    .get_at("this".to_string(), 0)
-                    .unwrap_or(Object::Nil));
-            }
-            return Ok(val);
+        if self.declaration.borrow().is_generator {
+            let body = self.declaration.borrow().body.clone();
+            let generator = LoxGenerator::new(self.get_name(), body, env);
+            return Ok(Object::Generator(Rc::new(generator)));
         }
-        if self.is_init {
+        // globals are looked up in the module the function was declared in
+        let globals = std::mem::replace(&mut intrprt.global, self.closure.globals());
+        intrprt.enter_call(self.get_name());
+        let val = intrprt
+            .execute_block(&self.declaration.borrow().body, env)
+            .or_else(ControlFlow::into_return);
+        intrprt.exit_call(&val);
+        intrprt.global = globals;
+        if self.is_init && val.is_ok() {
             return Ok(self.closure.get_at("this".to_string(), 0).unwrap());
         }
         return val;
@@ -81,10 +86,18 @@ impl LoxCallable for LoxLambda {
         for (param, arg) in self.declaration.borrow().params.iter().zip(args) {
             env.define_at(param.lexeme.clone(), arg, 0);
         }
-        let val = intrprt.execute_block(&self.declaration.borrow_mut().body, env);
-        if let Err(LoxError::ReturnVal(val, _)) = val {
-            return Ok(val);
+        if self.declaration.borrow().is_generator {
+            let body = self.declaration.borrow().body.clone();
+            let generator = LoxGenerator::new(self.get_name(), body, env);
+            return Ok(Object::Generator(Rc::new(generator)));
         }
+        let globals = std::mem::replace(&mut intrprt.global, self.closure.globals());
+        intrprt.enter_call(self.get_name());
+        let val = intrprt
+            .execute_block(&self.declaration.borrow_mut().body, env)
+            .or_else(ControlFlow::into_return);
+        intrprt.exit_call(&val);
+        intrprt.global = globals;
         val
     }
     fn arity(&self) -> usize {
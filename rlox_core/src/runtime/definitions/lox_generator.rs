@@ -0,0 +1,149 @@
+use crate::error::LoxError;
+use crate::frontend::definitions::stmt::Stmt;
+use crate::runtime::control_flow::ControlFlow;
+use crate::runtime::definitions::lox_callable::LoxCallable;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::environment::{GlobalEnvironment, LocalEnvironment};
+use crate::runtime::interpreter::Interpreter;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// What a generator is resumed for: whether it has another value, or that value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resume {
+    HasNext,
+    Next,
+}
+
+impl Resume {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hasNext" => Some(Resume::HasNext),
+            "next" => Some(Resume::Next),
+            _ => None,
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Resume::HasNext => "hasNext",
+            Resume::Next => "next",
+        }
+    }
+}
+
+// What calling a function with a yield in it returns. Its body runs up to the next yield each
+// time a value is asked for, so generators work as iterators.
+#[derive(Debug)]
+pub struct LoxGenerator {
+    pub name: String,
+    pub body: Vec<Stmt>,
+    // the module the function was declared in
+    pub globals: GlobalEnvironment,
+    pub state: RefCell<GeneratorState>,
+    // the value a hasNext ran the body up to, for the next to return
+    pub next: RefCell<Option<Object>>,
+}
+
+impl LoxGenerator {
+    pub fn new(name: String, body: Vec<Stmt>, env: LocalEnvironment) -> Self {
+        LoxGenerator {
+            name,
+            body,
+            globals: env.globals(),
+            state: RefCell::new(GeneratorState::Suspended(BlockState::new(env))),
+            next: RefCell::new(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GeneratorState {
+    // not started yet, or stopped at a yield
+    Suspended(BlockState),
+    Running,
+    Done,
+}
+
+// Where within a statement with a yield in it a generator stopped, along with what the
+// statements around that yield need to carry on from there.
+#[derive(Debug)]
+pub enum Suspended {
+    Yield,
+    Block(Box<BlockState>),
+    // which branch was taken
+    If(bool, Box<Suspended>),
+    While(Box<Suspended>),
+    ForIn(Box<ForInState>),
+    // the arm that matched, and the scope holding its bindings
+    Match(usize, LocalEnvironment, Box<Suspended>),
+    Try(Box<TryState>),
+}
+
+// How far through a list of statements running in env a generator is. The statement at index
+// has been started when at is set.
+#[derive(Debug)]
+pub struct BlockState {
+    pub env: LocalEnvironment,
+    pub index: usize,
+    pub at: Option<Suspended>,
+}
+
+impl BlockState {
+    pub fn new(env: LocalEnvironment) -> Self {
+        BlockState {
+            env,
+            index: 0,
+            at: None,
+        }
+    }
+}
+
+// What a for-in loop takes its items from: a snapshot of a list, string or map, or an object
+// with the iterator protocol's hasNext and next.
+#[derive(Debug)]
+pub enum ForInItems {
+    Snapshot(std::vec::IntoIter<Object>),
+    Iterator(Object),
+}
+
+#[derive(Debug)]
+pub struct ForInState {
+    pub items: ForInItems,
+    // the scope holding the current item
+    pub env: LocalEnvironment,
+    pub at: Suspended,
+}
+
+// The clause of a try statement a generator stopped in. A finally keeps what the clauses
+// before it ended with, to carry on with after it.
+#[derive(Debug)]
+pub enum TryState {
+    Body(BlockState),
+    Catch(BlockState),
+    Finally(BlockState, Result<Object, ControlFlow>),
+}
+
+// A generator's hasNext or next, bound to it.
+#[derive(Debug, Clone)]
+pub struct GeneratorMethod {
+    generator: Rc<LoxGenerator>,
+    resume: Resume,
+}
+
+impl GeneratorMethod {
+    pub fn new(generator: Rc<LoxGenerator>, resume: Resume) -> Self {
+        GeneratorMethod { generator, resume }
+    }
+}
+
+impl LoxCallable for GeneratorMethod {
+    fn call(&self, intrprt: &mut Interpreter, _args: Vec<Object>) -> Result<Object, LoxError> {
+        intrprt.resume_generator(&self.generator, self.resume)
+    }
+    fn arity(&self) -> usize {
+        0
+    }
+    fn get_name(&self) -> String {
+        self.resume.name().to_string()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::runtime::definitions::big_int::BigInt;
+use crate::runtime::definitions::number::Number;
+use crate::runtime::definitions::object::Object;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Str(String),
+    Num(u64),
+    Int(i64),
+    Big(BigInt),
+    Bool(bool),
+    Instance(usize),
+}
+
+impl MapKey {
+    pub fn from_object(key: &Object) -> Option<Self> {
+        match key {
+            Object::Str(val) => Some(MapKey::Str(val.clone())),
+            // keys comparing equal hash the same: 1 and 1.0 are one key, as are -0.0 and 0
+            Object::Int(_) | Object::BigInt(_) | Object::Num(_) => {
+                let num = key.as_number()?;
+                Some(match num.clone().as_integer() {
+                    Some(Number::Int(val)) => MapKey::Int(val),
+                    Some(Number::Big(val)) => MapKey::Big(val),
+                    _ => MapKey::Num(num.as_f64().to_bits()),
+                })
+            }
+            Object::Bool(val) => Some(MapKey::Bool(*val)),
+            Object::Instance(val) => Some(MapKey::Instance(Rc::as_ptr(val) as usize)),
+            _ => None,
+        }
+    }
+}
+
+// entries are kept in insertion order so that key iteration is deterministic
+#[derive(Debug, Clone, Default)]
+pub struct LoxMap {
+    entries: Vec<(Object, Object)>,
+    index: HashMap<MapKey, usize>,
+}
+
+impl LoxMap {
+    pub fn new() -> Self {
+        LoxMap {
+            entries: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<Object> {
+        self.index.get(key).map(|pos| self.entries[*pos].1.clone())
+    }
+
+    pub fn set(&mut self, key: MapKey, key_obj: Object, value: Object) {
+        if let Some(pos) = self.index.get(&key) {
+            self.entries[*pos].1 = value;
+        } else {
+            self.index.insert(key, self.entries.len());
+            self.entries.push((key_obj, value));
+        }
+    }
+
+    pub fn keys(&self) -> Vec<Object> {
+        self.entries.iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn entries(&self) -> &Vec<(Object, Object)> {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -1,4 +1,8 @@
+pub mod big_int;
 pub mod lox_callable;
 pub mod lox_class;
 pub mod lox_function;
+pub mod lox_generator;
+pub mod lox_map;
+pub mod number;
 pub mod object;
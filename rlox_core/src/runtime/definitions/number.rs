@@ -0,0 +1,262 @@
+use super::big_int::BigInt;
+use crate::error::ErrorCode;
+use std::convert::TryFrom;
+
+// Arithmetic shared by both backends' Objects. An operation on two ints stays an int, promoting to
+// a BigInt when it overflows i64 and demoting back once the result fits again, while one with a
+// float operand promotes the int to a float. `/` always divides as floats, `~/` floors, `**` with a
+// negative exponent gives a float and the bitwise operators only take ints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Big(BigInt),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    IntDiv,
+    Mod,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+// The largest shift or exponent a BigInt takes, keeping results small enough to compute quickly.
+const MAX_SHIFT: i64 = 1 << 16;
+
+impl Number {
+    // The number a string holds, for the int and float natives.
+    pub fn parse(text: &str) -> Option<Number> {
+        let text = text.trim();
+        match text.parse::<i64>() {
+            Ok(val) => Some(Number::Int(val)),
+            Err(_) => match BigInt::parse(text) {
+                Some(val) => Some(Number::Big(val)),
+                None => text.parse::<f64>().ok().map(Number::Float),
+            },
+        }
+    }
+
+    // An int if the value fits in one.
+    pub fn from_big(val: BigInt) -> Number {
+        match val.to_i64() {
+            Some(val) => Number::Int(val),
+            None => Number::Big(val),
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Number::Int(val) => *val as f64,
+            Number::Big(val) => val.to_f64(),
+            Number::Float(val) => *val,
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            Number::Int(val) => BigInt::from_i64(*val),
+            Number::Big(val) => val.clone(),
+            Number::Float(_) => unreachable!(),
+        }
+    }
+
+    // Errors are the messages of the runtime errors the backends raise.
+    pub fn binary(self, op: BinaryOp, other: Number) -> Result<Number, (ErrorCode, &'static str)> {
+        use BinaryOp::*;
+        use Number::*;
+
+        match (&self, &other) {
+            (Float(_), _) | (_, Float(_)) => {
+                let (l, r) = (self.as_f64(), other.as_f64());
+                Ok(Float(match op {
+                    Add => l + r,
+                    Sub => l - r,
+                    Mul => l * r,
+                    Div => l / r,
+                    IntDiv => (l / r).floor(),
+                    Mod => l - r * (l / r).floor(),
+                    Pow => l.powf(r),
+                    BitAnd | BitOr | BitXor | Shl | Shr => {
+                        return Err((ErrorCode::OperandType, "Operands not Int"))
+                    }
+                }))
+            }
+            (Int(l), Int(r)) => {
+                let (l, r) = (*l, *r);
+                let result = match op {
+                    Add => l.checked_add(r),
+                    Sub => l.checked_sub(r),
+                    Mul => l.checked_mul(r),
+                    Div => return Ok(Float(l as f64 / r as f64)),
+                    IntDiv | Mod if r == 0 => {
+                        return Err((ErrorCode::DivisionByZero, "Division by zero"))
+                    }
+                    // rounded towards negative infinity, the remainder taking the divisor's sign
+                    IntDiv => l.checked_div(r).map(|q| {
+                        if l % r != 0 && (l < 0) != (r < 0) {
+                            q - 1
+                        } else {
+                            q
+                        }
+                    }),
+                    Mod => match l.wrapping_rem(r) {
+                        rem if rem != 0 && (rem < 0) != (r < 0) => Some(rem + r),
+                        rem => Some(rem),
+                    },
+                    Pow if r < 0 => return Ok(Float((l as f64).powf(r as f64))),
+                    Pow => u32::try_from(r).ok().and_then(|r| l.checked_pow(r)),
+                    BitAnd => Some(l & r),
+                    BitOr => Some(l | r),
+                    BitXor => Some(l ^ r),
+                    Shl if (0..64).contains(&r) && (l << r) >> r == l => Some(l << r),
+                    Shr if (0..64).contains(&r) => Some(l >> r),
+                    Shl | Shr => None,
+                };
+                match result {
+                    Some(val) => Ok(Int(val)),
+                    None => Number::big_binary(op, &self.to_big(), &other),
+                }
+            }
+            _ => Number::big_binary(op, &self.to_big(), &other),
+        }
+    }
+
+    // Arithmetic on ints that overflowed i64, or on an operand that already had.
+    fn big_binary(
+        op: BinaryOp,
+        l: &BigInt,
+        other: &Number,
+    ) -> Result<Number, (ErrorCode, &'static str)> {
+        use BinaryOp::*;
+
+        let r = other.to_big();
+        let result = match op {
+            Add => l.add(&r),
+            Sub => l.sub(&r),
+            Mul => l.mul(&r),
+            Div => return Ok(Number::Float(l.to_f64() / r.to_f64())),
+            IntDiv | Mod => match l.div_mod_floor(&r) {
+                Some((quot, _)) if op == IntDiv => quot,
+                Some((_, rem)) => rem,
+                None => return Err((ErrorCode::DivisionByZero, "Division by zero")),
+            },
+            Pow => match other {
+                Number::Int(exp) if (0..=MAX_SHIFT).contains(exp) => l.pow(*exp as u32),
+                _ if r < BigInt::default() => {
+                    return Ok(Number::Float(l.to_f64().powf(r.to_f64())))
+                }
+                _ => return Err((ErrorCode::ExponentTooLarge, "Exponent too large")),
+            },
+            BitAnd | BitOr | BitXor => {
+                return Err((ErrorCode::BitwiseRange, "Bitwise operands exceed 64 bits"))
+            }
+            Shl | Shr => {
+                let bits = match other {
+                    Number::Int(bits) if (0..=MAX_SHIFT).contains(bits) => *bits as u32,
+                    _ => return Err((ErrorCode::ShiftRange, "Shift out of range")),
+                };
+                if op == Shl {
+                    l.shl(bits)
+                } else {
+                    l.shr(bits)
+                }
+            }
+        };
+        Ok(Number::from_big(result))
+    }
+
+    pub fn bit_not(self) -> Result<Number, (ErrorCode, &'static str)> {
+        match self {
+            Number::Int(val) => Ok(Number::Int(!val)),
+            Number::Big(_) => Err((ErrorCode::BitwiseRange, "Bitwise operands exceed 64 bits")),
+            Number::Float(_) => Err((ErrorCode::OperandType, "Operands not Int")),
+        }
+    }
+
+    pub fn eq(self, other: Number) -> bool {
+        self.partial_cmp(other) == Some(std::cmp::Ordering::Equal)
+    }
+
+    pub fn partial_cmp(self, other: Number) -> Option<std::cmp::Ordering> {
+        match (&self, &other) {
+            (Number::Int(l), Number::Int(r)) => Some(l.cmp(r)),
+            (Number::Float(l), Number::Float(r)) => l.partial_cmp(r),
+            (Number::Float(l), _) => other.cmp_float(*l).map(std::cmp::Ordering::reverse),
+            (_, Number::Float(r)) => self.cmp_float(*r),
+            _ => Some(self.to_big().cmp(&other.to_big())),
+        }
+    }
+
+    // Compares an int with a float exactly, against the float's floor rather than rounding the
+    // int to a float, so that ints equal to a float also hash as the same map key.
+    fn cmp_float(&self, other: f64) -> Option<std::cmp::Ordering> {
+        use std::cmp::Ordering;
+        if other.is_nan() {
+            return None;
+        }
+        if other.is_infinite() {
+            return Some(if other > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+        match self.to_big().cmp(&BigInt::from_f64(other.floor())?) {
+            Ordering::Equal if other.fract() != 0.0 => Some(Ordering::Less),
+            ordering => Some(ordering),
+        }
+    }
+
+    // The integer a float with no fractional part equals, so that map keys 1 and 1.0 are the same.
+    pub fn as_integer(self) -> Option<Number> {
+        match self {
+            Number::Float(val) => BigInt::from_f64(val).map(Number::from_big),
+            val => Some(val),
+        }
+    }
+
+    pub fn as_int(self) -> Option<i64> {
+        match self.as_integer()? {
+            Number::Int(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    // The int(x) native: floats are truncated towards zero.
+    pub fn to_int(self) -> Result<Number, (ErrorCode, &'static str)> {
+        match self {
+            Number::Float(val) if val.is_nan() || val.is_infinite() => {
+                Err((ErrorCode::Conversion, "Cannot convert to Int"))
+            }
+            Number::Float(val) => Ok(Number::from_big(
+                BigInt::from_f64(val.trunc()).unwrap_or_default(),
+            )),
+            val => Ok(val),
+        }
+    }
+}
+
+impl std::ops::Neg for Number {
+    type Output = Number;
+
+    // Negating i64::MIN overflows into a BigInt.
+    fn neg(self) -> Number {
+        match self {
+            Number::Int(val) => match val.checked_neg() {
+                Some(val) => Number::Int(val),
+                None => Number::Big(BigInt::from_i64(val).neg()),
+            },
+            Number::Big(val) => Number::from_big(val.neg()),
+            Number::Float(val) => Number::Float(-val),
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use crate::error::LoxError;
+use crate::error::{ErrorCode, LoxError};
 use crate::frontend::definitions::literal::Literal;
+use crate::runtime::definitions::big_int::BigInt;
 use crate::runtime::definitions::lox_callable::LoxCallable;
 use crate::runtime::definitions::lox_class::{LoxClass, LoxInstance};
+use crate::runtime::definitions::lox_generator::LoxGenerator;
+use crate::runtime::definitions::lox_map::LoxMap;
+use crate::runtime::definitions::number::{BinaryOp, Number};
+use std::cell::RefCell;
 use std::fmt::Display;
 use std::rc::Rc;
 // obj.get not handled
@@ -9,17 +14,24 @@ use std::rc::Rc;
 pub enum Object {
     Str(String),
     Num(f64),
+    Int(i64),
+    BigInt(Rc<BigInt>),
     Bool(bool),
     Nil,
     Function(Rc<dyn LoxCallable>),
     Class(Rc<LoxClass>),
     Instance(Rc<LoxInstance>),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<LoxMap>>),
+    Generator(Rc<LoxGenerator>),
 }
 
 impl From<Literal> for Object {
     fn from(val: Literal) -> Self {
         match val {
             Literal::NUM(v) => Object::Num(v),
+            Literal::INT(v) => Object::Int(v),
+            Literal::BIG(v) => Object::BigInt(Rc::new(v)),
             Literal::STRING(v) => Object::Str(v),
             Literal::BOOL(v) => Object::Bool(v),
             Literal::NIL => Object::Nil,
@@ -27,15 +39,31 @@ impl From<Literal> for Object {
     }
 }
 
+impl From<Number> for Object {
+    fn from(val: Number) -> Self {
+        match val {
+            Number::Int(v) => Object::Int(v),
+            Number::Big(v) => Object::BigInt(Rc::new(v)),
+            Number::Float(v) => Object::Num(v),
+        }
+    }
+}
+
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         use Object::*;
 
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a.eq(b);
+        }
         match (self, other) {
             (&Str(ref a), &Str(ref b)) => a == b,
             (&Num(ref a), &Num(ref b)) => a == b,
             (&Bool(ref a), &Bool(ref b)) => a == b,
             (&Nil, &Nil) => true,
+            (List(a), List(b)) => Rc::ptr_eq(a, b),
+            (Map(a), Map(b)) => Rc::ptr_eq(a, b),
+            (Generator(a), Generator(b)) => Rc::ptr_eq(a, b),
             _ => false,
         }
     }
@@ -49,6 +77,8 @@ impl Display for Object {
         match self {
             Object::Str(val) => writer.write_str(&val.to_string()),
             Object::Num(val) => writer.write_str(&val.to_string()),
+            Object::Int(val) => writer.write_str(&val.to_string()),
+            Object::BigInt(val) => writer.write_str(&val.to_string()),
             Object::Bool(val) => writer.write_str(&val.to_string()),
             Object::Nil => writer.write_str("Nil"),
             Object::Function(val) => writer.write_fmt(format_args!("Function<{}>", val.get_name())),
@@ -56,114 +86,122 @@ impl Display for Object {
             Object::Instance(val) => {
                 writer.write_fmt(format_args!("Instance<{}>", &val.klass.name))
             }
+            Object::List(val) => {
+                writer.write_str("[")?;
+                for (i, element) in val.borrow().iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    element.fmt(writer)?;
+                }
+                writer.write_str("]")
+            }
+            Object::Map(val) => {
+                writer.write_str("{")?;
+                for (i, (key, value)) in val.borrow().entries().iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    writer.write_fmt(format_args!("{}: {}", key, value))?;
+                }
+                writer.write_str("}")
+            }
+            Object::Generator(val) => writer.write_fmt(format_args!("Generator<{}>", val.name)),
         }
     }
 }
 
 impl Object {
-    pub fn add(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Object::Num(val) => Some(Number::Float(*val)),
+            Object::Int(val) => Some(Number::Int(*val)),
+            Object::BigInt(val) => Some(Number::Big(BigInt::clone(val))),
+            _ => None,
+        }
+    }
+
+    // The arithmetic and bitwise operators, following the promotion rules of Number.
+    pub fn binary(&self, op: BinaryOp, other: &Self, line_no: u32) -> Result<Self, LoxError> {
+        let error = |(code, msg)| LoxError::runtime_on_line(other.to_string(), line_no, code, msg);
         match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l + r)),
-            (Str(ref l), Str(ref r)) => {
-                let mut l = l.clone();
-                l.push_str(r);
-                Ok(Object::Str(l))
+            (Object::Str(l), Object::Str(r)) if op == BinaryOp::Add => {
+                Ok(Object::Str(format!("{}{}", l, r)))
             }
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
+            _ => match (self.as_number(), other.as_number()) {
+                (Some(l), Some(r)) => l.binary(op, r).map(Object::from).map_err(error),
+                _ if op == BinaryOp::Add => Err(error((
+                    ErrorCode::OperandType,
+                    "Operands not Num or String",
+                ))),
+                _ => Err(error((ErrorCode::OperandType, "Operands not Num"))),
+            },
         }
     }
+    pub fn add(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Add, other, line_no)
+    }
     pub fn sub(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l - r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.binary(BinaryOp::Sub, other, line_no)
     }
     pub fn mul(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l * r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.binary(BinaryOp::Mul, other, line_no)
     }
     pub fn div(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l / r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.binary(BinaryOp::Div, other, line_no)
     }
     pub fn gt(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l > r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l > r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_gt())
     }
     pub fn gte(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l >= r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l >= r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_ge())
     }
     pub fn lt(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l < r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l < r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_lt())
     }
     pub fn lte(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l <= r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l <= r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
+        self.compare(other, line_no, |ord| ord.is_le())
+    }
+    pub fn neg(&self, line_no: u32) -> Result<Self, LoxError> {
+        self.unary(|num| Ok(-num), line_no)
+    }
+    pub fn bit_not(&self, line_no: u32) -> Result<Self, LoxError> {
+        self.unary(Number::bit_not, line_no)
+    }
+
+    // NaN compares false with everything.
+    fn compare(
+        &self,
+        other: &Self,
+        line_no: u32,
+        test: fn(std::cmp::Ordering) -> bool,
+    ) -> Result<Self, LoxError> {
+        let ordering = match (self, other) {
+            (Object::Str(l), Object::Str(r)) => l.partial_cmp(r),
+            _ => match (self.as_number(), other.as_number()) {
+                (Some(l), Some(r)) => l.partial_cmp(r),
+                _ => {
+                    return Err(LoxError::runtime_on_line(
+                        other.to_string(),
+                        line_no,
+                        ErrorCode::OperandType,
+                        "Operands not Num or String",
+                    ))
+                }
+            },
+        };
+        Ok(Object::Bool(ordering.is_some_and(test)))
+    }
+
+    fn unary(
+        &self,
+        op: fn(Number) -> Result<Number, (ErrorCode, &'static str)>,
+        line_no: u32,
+    ) -> Result<Self, LoxError> {
+        let error = |(code, msg)| LoxError::runtime_on_line(self.to_string(), line_no, code, msg);
+        match self.as_number() {
+            Some(val) => op(val).map(Object::from).map_err(error),
+            None => Err(error((ErrorCode::OperandType, "Operands not Num"))),
         }
     }
 }
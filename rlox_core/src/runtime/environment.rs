@@ -78,6 +78,15 @@ impl LocalEnvironment {
             None
         };
     }
+    // The environment at the root of the chain, holding the globals of the module it belongs to.
+    pub fn globals(&self) -> GlobalEnvironment {
+        match &self.env.borrow().parent {
+            Some(parent) => parent.globals(),
+            None => GlobalEnvironment {
+                env: Rc::clone(&self.env),
+            },
+        }
+    }
     pub fn get_at(&self, name: String, hops: usize) -> Option<Object> {
         if let Some(env) = &mut self.ancestor(hops) {
             return env.env.borrow_mut().get(name);
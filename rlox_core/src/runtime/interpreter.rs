@@ -5,25 +5,53 @@ use std::fmt::Debug;
 
 use crate::runtime::environment::{GlobalEnvironment, LocalEnvironment};
 
-use crate::error::LoxError;
+use crate::error::{format_backtrace, ErrorCode, LoxError, TraceFrame};
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::pattern::{MatchArm, Pattern};
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::token::Token;
 use crate::frontend::definitions::token_type::TokenType;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::control_flow::ControlFlow;
 use crate::runtime::definitions::lox_callable::LoxCallable;
 use crate::runtime::definitions::lox_class::{LoxClass, LoxInstance};
 use crate::runtime::definitions::lox_function::{LoxFunction, LoxLambda};
+use crate::runtime::definitions::lox_generator::{
+    BlockState, ForInItems, ForInState, GeneratorMethod, GeneratorState, LoxGenerator, Resume,
+    Suspended, TryState,
+};
+use crate::runtime::definitions::lox_map::{LoxMap, MapKey};
+use crate::runtime::definitions::number::{BinaryOp, Number};
 use crate::runtime::definitions::object::Object;
+use crate::runtime::module::{self, Modules};
+use crate::runtime::prelude::PRELUDE;
 use crate::runtime::system_calls::SystemCalls;
 use crate::runtime::visitor::{VisAcceptor, Visitor};
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell, RefMut};
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 pub struct Interpreter {
     pub env: LocalEnvironment,
     pub global: GlobalEnvironment,
     system_interface: Rc<RefCell<dyn SystemCalls>>,
+    // the file being run, which imports are relative to
+    path: Option<PathBuf>,
+    modules: Rc<RefCell<Modules<Object>>>,
+    // the Lox functions being run, innermost last, each with the line it was called from
+    call_stack: Vec<TraceFrame>,
+    // the line of the call being made, which the function called records
+    call_line: u32,
+    // where the error being propagated was raised, until it's caught or reported
+    backtrace: Option<Vec<TraceFrame>>,
+    // what backtraces call the top-level code: the script, or the module being run
+    frame_name: String,
+    // the prelude's classes, which built-in errors and modules are instances of whatever the
+    // script goes on to bind their names to
+    prelude: HashMap<String, Rc<LoxClass>>,
 }
 
 #[derive(Clone)]
@@ -41,134 +69,129 @@ impl LoxCallable for ClockFunc {
     }
 }
 
-impl Visitor<Object> for Interpreter {
-    fn visit_binary_expr(&mut self, val: &Binary) -> Result<Object, LoxError> {
+#[derive(Clone)]
+struct LenFunc;
+
+impl LoxCallable for LenFunc {
+    fn call(&self, _intr: &mut Interpreter, args: Vec<Object>) -> Result<Object, LoxError> {
+        match &args[0] {
+            Object::Str(val) => Ok(Object::Int(val.chars().count() as i64)),
+            Object::List(val) => Ok(Object::Int(val.borrow().len() as i64)),
+            Object::Map(val) => Ok(Object::Int(val.borrow().len() as i64)),
+            val => Err(LoxError::RuntimeError {
+                lexeme: val.to_string(),
+                line_no: 0,
+                message: "len expects a Str, List or Map".to_string(),
+                code: ErrorCode::NativeArgument,
+                span: None,
+                backtrace: Box::new([]),
+            }),
+        }
+    }
+    fn arity(&self) -> usize {
+        1
+    }
+    fn get_name(&self) -> String {
+        String::from("len")
+    }
+}
+
+#[derive(Clone)]
+struct IntFunc;
+
+impl LoxCallable for IntFunc {
+    fn call(&self, _intr: &mut Interpreter, args: Vec<Object>) -> Result<Object, LoxError> {
+        number_arg(&args[0], "int")?
+            .to_int()
+            .map(Object::from)
+            .map_err(|(code, msg)| LoxError::RuntimeError {
+                lexeme: args[0].to_string(),
+                line_no: 0,
+                message: msg.to_string(),
+                code,
+                span: None,
+                backtrace: Box::new([]),
+            })
+    }
+    fn arity(&self) -> usize {
+        1
+    }
+    fn get_name(&self) -> String {
+        String::from("int")
+    }
+}
+
+#[derive(Clone)]
+struct FloatFunc;
+
+impl LoxCallable for FloatFunc {
+    fn call(&self, _intr: &mut Interpreter, args: Vec<Object>) -> Result<Object, LoxError> {
+        Ok(Object::Num(number_arg(&args[0], "float")?.as_f64()))
+    }
+    fn arity(&self) -> usize {
+        1
+    }
+    fn get_name(&self) -> String {
+        String::from("float")
+    }
+}
+
+// The number passed to the int or float native, strings being parsed.
+fn number_arg(val: &Object, native: &str) -> Result<Number, LoxError> {
+    let error = |code, msg: String| LoxError::RuntimeError {
+        lexeme: val.to_string(),
+        line_no: 0,
+        message: msg,
+        code,
+        span: None,
+        backtrace: Box::new([]),
+    };
+    match val {
+        Object::Str(text) => Number::parse(text)
+            .ok_or_else(|| error(ErrorCode::Conversion, "Cannot convert to Num".to_string())),
+        val => val.as_number().ok_or_else(|| {
+            error(
+                ErrorCode::NativeArgument,
+                format!("{} expects a Num or Str", native),
+            )
+        }),
+    }
+}
+
+#[derive(Clone)]
+struct KeysFunc;
+
+impl LoxCallable for KeysFunc {
+    fn call(&self, _intr: &mut Interpreter, args: Vec<Object>) -> Result<Object, LoxError> {
+        if let Object::Map(val) = &args[0] {
+            return Ok(Object::List(Rc::new(RefCell::new(val.borrow().keys()))));
+        }
+        Err(LoxError::RuntimeError {
+            lexeme: args[0].to_string(),
+            line_no: 0,
+            message: "keys expects a Map".to_string(),
+            code: ErrorCode::NativeArgument,
+            span: None,
+            backtrace: Box::new([]),
+        })
+    }
+    fn arity(&self) -> usize {
+        1
+    }
+    fn get_name(&self) -> String {
+        String::from("keys")
+    }
+}
+
+impl Visitor<Object, ControlFlow> for Interpreter {
+    fn visit_binary_expr(&mut self, val: &Binary) -> Result<Object, ControlFlow> {
         let right = self.evaluate(&val.right)?;
         let left = self.evaluate(&val.left)?;
 
-        return match val.operator.token_type {
-            //TODO: TRy do these stuff with traits
-            TokenType::MINUS => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Num(left - right));
-                    }
-                }
-
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::SLASH => {
-                if let Object::Num(right) = right {
-                    if right == 0.0 {
-                        return Err(LoxError::RuntimeError(
-                            val.operator.lexeme.clone(),
-                            val.operator.line_no,
-                            "Division by zero".to_string(),
-                        ));
-                    }
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Num(left / right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::STAR => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Num(left * right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::PLUS => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Num(left + right));
-                    }
-                }
-                if let Object::Str(right) = right {
-                    if let Object::Str(mut left) = left {
-                        left.push_str(right.as_str());
-                        return Ok(Object::Str(left));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num or String".to_string(),
-                ))
-            }
-            TokenType::GREATER => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Bool(left > right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::GreaterEqual => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Bool(left >= right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::LESS => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Bool(left < right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::LessEqual => {
-                if let Object::Num(right) = right {
-                    if let Object::Num(left) = left {
-                        return Ok(Object::Bool(left <= right));
-                    }
-                }
-                Err(LoxError::RuntimeError(
-                    val.operator.lexeme.clone(),
-                    val.operator.line_no,
-                    "Operands not Num".to_string(),
-                ))
-            }
-            TokenType::BangEqual => Ok(Object::Bool(left != right)),
-            TokenType::EqualEqual => Ok(Object::Bool(left == right)),
-            _ => Err(LoxError::RuntimeError(
-                val.operator.lexeme.clone(),
-                val.operator.line_no,
-                "Operator Unhandled".to_string(),
-            )),
-        };
+        self.binary(&val.operator, left, right)
     }
 
-    fn visit_call_expr(&mut self, val: &Call) -> Result<Object, LoxError> {
+    fn visit_call_expr(&mut self, val: &Call) -> Result<Object, ControlFlow> {
         let callee = self.evaluate(&val.callee)?;
         let mut args = Vec::new();
         for arg in &val.arguments {
@@ -182,51 +205,78 @@ impl Visitor<Object> for Interpreter {
         } else if let Object::Class(callee) = callee {
             fn_def = callee;
         } else {
-            return Err(LoxError::RuntimeError(
-                callee.to_string(),
-                val.paren.line_no,
-                "Not a function".to_string(),
-            ));
+            return Err(LoxError::RuntimeError {
+                lexeme: callee.to_string(),
+                line_no: val.paren.line_no,
+                message: "Not a function".to_string(),
+                code: ErrorCode::NotCallable,
+                span: Some(Box::new(val.paren.span)),
+                backtrace: Box::new([]),
+            }
+            .into());
         }
         if args.len() != fn_def.arity() {
-            return Err(LoxError::RuntimeError(
-                fn_def.get_name(),
-                val.paren.line_no,
-                "No. of args don't match".to_string(),
-            ));
+            return Err(LoxError::RuntimeError {
+                lexeme: fn_def.get_name(),
+                line_no: val.paren.line_no,
+                message: "No. of args don't match".to_string(),
+                code: ErrorCode::ArgumentCount,
+                span: Some(Box::new(val.paren.span)),
+                backtrace: Box::new([]),
+            }
+            .into());
+        }
+        self.call_line = val.paren.line_no;
+        match fn_def.call(self, args) {
+            // natives don't know where they were called from
+            Err(LoxError::RuntimeError {
+                lexeme: literal,
+                line_no: 0,
+                message: msg,
+                code,
+                ..
+            }) => Err(LoxError::RuntimeError {
+                lexeme: literal,
+                line_no: val.paren.line_no,
+                message: msg,
+                code,
+                span: Some(Box::new(val.paren.span)),
+                backtrace: Box::new([]),
+            }
+            .into()),
+            res => Ok(res?),
         }
-        let x = fn_def.call(self, args)?;
-        return Ok(x);
     }
 
-    fn visit_grouping_expr(&mut self, val: &Grouping) -> Result<Object, LoxError> {
+    fn visit_grouping_expr(&mut self, val: &Grouping) -> Result<Object, ControlFlow> {
         self.evaluate(&val.expression)
     }
 
-    fn visit_unary_expr(&mut self, val: &Unary) -> Result<Object, LoxError> {
+    fn visit_unary_expr(&mut self, val: &Unary) -> Result<Object, ControlFlow> {
         let right = self.evaluate(&val.right)?;
 
         Ok(match val.operator.token_type {
-            TokenType::MINUS => match right {
-                Object::Num(v) => Object::Num(-v),
-                _ => {
-                    return Err(LoxError::RuntimeError(
-                        val.operator.lexeme.clone(),
-                        val.operator.line_no,
-                        "Unexpected Token found".to_string(),
-                    ))
-                }
-            },
+            TokenType::MINUS | TokenType::TILDE => {
+                let result = match (&val.operator.token_type, right.as_number()) {
+                    (TokenType::MINUS, Some(num)) => Ok(-num),
+                    (_, Some(num)) => num.bit_not(),
+                    (_, None) => Err((ErrorCode::OperandType, "Unexpected Token found")),
+                };
+                result
+                    .map(Object::from)
+                    .map_err(|(code, msg)| LoxError::runtime(&val.operator, code, msg))?
+            }
             TokenType::BANG => Object::Bool(!self.is_true(&right)),
+            TokenType::STRINGIFY => Object::Str(right.to_string()),
             _ => Object::Nil,
         })
     }
 
-    fn visit_literal_expr(&mut self, val: &Literal) -> Result<Object, LoxError> {
+    fn visit_literal_expr(&mut self, val: &Literal, _span: Span) -> Result<Object, ControlFlow> {
         Ok(val.clone().into())
     }
 
-    fn visit_logical_expr(&mut self, val: &Logical) -> Result<Object, LoxError> {
+    fn visit_logical_expr(&mut self, val: &Logical) -> Result<Object, ControlFlow> {
         let left = self.evaluate(&val.left)?;
 
         if val.operator.token_type == TokenType::OR {
@@ -242,53 +292,41 @@ impl Visitor<Object> for Interpreter {
         self.evaluate(&val.right)
     }
 
-    fn visit_get_expr(&mut self, val: &Get) -> Result<Object, LoxError> {
+    fn visit_get_expr(&mut self, val: &Get) -> Result<Object, ControlFlow> {
         let obj = self.evaluate(&val.object)?;
-        if let Object::Instance(inst) = obj {
-            return if let Some(val) = inst.get(&val.name) {
-                Ok(val)
-            } else {
-                Ok(Object::Function(
-                    inst.klass.bind_method(&val.name, Rc::clone(&inst))?,
-                ))
-            };
-        }
-        Err(LoxError::RuntimeError(
-            val.name.lexeme.clone(),
-            val.name.line_no,
-            "Only Instance have properties".to_string(),
-        ))
+        Ok(self.property(&obj, &val.name)?)
     }
 
-    fn visit_set_expr(&mut self, val: &Set) -> Result<Object, LoxError> {
+    fn visit_set_expr(&mut self, val: &Set) -> Result<Object, ControlFlow> {
         let mut obj = self.evaluate(&val.object)?;
         return if let Object::Instance(obj) = &mut obj {
             let value = self.evaluate(&val.value)?;
             obj.set(&val.name, value.clone());
             Ok(value)
         } else {
-            Err(LoxError::RuntimeError(
-                val.name.lexeme.clone(),
-                val.name.line_no,
-                "Only Instances have feilds".to_string(),
-            ))
+            Err(LoxError::runtime(
+                &val.name,
+                ErrorCode::NotInstance,
+                "Only Instances have feilds",
+            )
+            .into())
         };
     }
 
-    fn visit_lambda_expr(&mut self, val: &Lambda) -> Result<Object, LoxError> {
+    fn visit_lambda_expr(&mut self, val: &Lambda) -> Result<Object, ControlFlow> {
         let func = LoxLambda::new(val.clone(), self.env.clone());
         return Ok(Object::Function(Rc::new(func)));
     }
 
-    fn visit_this_expr(&mut self, val: &This) -> Result<Object, LoxError> {
-        self.variable_lookup(&val.keyword)
+    fn visit_this_expr(&mut self, val: &This) -> Result<Object, ControlFlow> {
+        Ok(self.variable_lookup(&val.keyword)?)
     }
 
-    fn visit_super_expr(&mut self, val: &Super) -> Result<Object, LoxError> {
-        let err = LoxError::RuntimeError(
-            val.keyword.lexeme.clone(),
-            val.keyword.line_no,
-            "Only Instance have properties".to_string(),
+    fn visit_super_expr(&mut self, val: &Super) -> Result<Object, ControlFlow> {
+        let err = LoxError::runtime(
+            &val.keyword,
+            ErrorCode::NotInstance,
+            "Only Instance have properties",
         );
         if let Some(dist) = val.keyword.scope {
             let super_class = self
@@ -308,24 +346,131 @@ impl Visitor<Object> for Interpreter {
                 }
             }
         }
-        Err(err)
+        Err(err.into())
     }
 
-    fn visit_expression_stmt(&mut self, val: &Expression) -> Result<Object, LoxError> {
+    fn visit_list_expr(&mut self, val: &List) -> Result<Object, ControlFlow> {
+        let mut elements = Vec::new();
+        for element in &val.elements {
+            elements.push(self.evaluate(element)?);
+        }
+        Ok(Object::List(Rc::new(RefCell::new(elements))))
+    }
+
+    fn visit_map_expr(&mut self, val: &Map) -> Result<Object, ControlFlow> {
+        let mut map = LoxMap::new();
+        for (key, value) in &val.entries {
+            let key = self.evaluate(key)?;
+            let value = self.evaluate(value)?;
+            map.set(self.map_key(&val.brace, &key)?, key, value);
+        }
+        Ok(Object::Map(Rc::new(RefCell::new(map))))
+    }
+
+    fn visit_index_expr(&mut self, val: &Index) -> Result<Object, ControlFlow> {
+        let obj = self.evaluate(&val.object)?;
+        let index = self.evaluate(&val.index)?;
+        Ok(self.index_get(&val.bracket, &obj, &index)?)
+    }
+
+    fn visit_set_index_expr(&mut self, val: &SetIndex) -> Result<Object, ControlFlow> {
+        let obj = self.evaluate(&val.object)?;
+        let index = self.evaluate(&val.index)?;
+        let value = self.evaluate(&val.value)?;
+        self.index_set(&val.bracket, &obj, index, value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_compound_expr(&mut self, val: &Compound) -> Result<Object, ControlFlow> {
+        // the target's object and index are evaluated once, for both the read and the write
+        let (old, new) = match &val.target {
+            Expr::Variable(var) => {
+                let old = self.variable_lookup(&var.name)?;
+                let new = self.compound_value(val, old.clone())?;
+                self.assign(&var.name, new.clone())?;
+                (old, new)
+            }
+            Expr::Get(get) => {
+                let obj = self.evaluate(&get.object)?;
+                let old = self.property(&obj, &get.name)?;
+                let new = self.compound_value(val, old.clone())?;
+                if let Object::Instance(inst) = obj {
+                    inst.set(&get.name, new.clone());
+                }
+                (old, new)
+            }
+            Expr::Index(index) => {
+                let obj = self.evaluate(&index.object)?;
+                let key = self.evaluate(&index.index)?;
+                let old = self.index_get(&index.bracket, &obj, &key)?;
+                let new = self.compound_value(val, old.clone())?;
+                self.index_set(&index.bracket, &obj, key, new.clone())?;
+                (old, new)
+            }
+            _ => {
+                return Err(LoxError::runtime(
+                    &val.operator,
+                    ErrorCode::InvalidAssignment,
+                    "Invalid assignment",
+                )
+                .into())
+            }
+        };
+        Ok(if val.postfix { old } else { new })
+    }
+
+    fn visit_conditional_expr(&mut self, val: &Conditional) -> Result<Object, ControlFlow> {
+        let condition = self.evaluate(&val.condition)?;
+        if self.is_true(&condition) {
+            self.evaluate(&val.then_branch)
+        } else {
+            self.evaluate(&val.else_branch)
+        }
+    }
+
+    fn visit_coalesce_expr(&mut self, val: &Coalesce) -> Result<Object, ControlFlow> {
+        let left = self.evaluate(&val.left)?;
+        if let Object::Nil = left {
+            return self.evaluate(&val.right);
+        }
+        Ok(left)
+    }
+
+    fn visit_safe_get_expr(&mut self, val: &SafeGet) -> Result<Object, ControlFlow> {
+        let obj = self.evaluate(&val.object)?;
+        if let Object::Nil = obj {
+            return Err(ControlFlow::ShortCircuit);
+        }
+        Ok(self.property(&obj, &val.name)?)
+    }
+
+    fn visit_optional_chain_expr(&mut self, val: &OptionalChain) -> Result<Object, ControlFlow> {
+        match self.evaluate(&val.expr) {
+            Err(ControlFlow::ShortCircuit) => Ok(Object::Nil),
+            res => res,
+        }
+    }
+
+    fn visit_match_expr(&mut self, val: &MatchExpr) -> Result<Object, ControlFlow> {
+        let subject = self.evaluate(&val.subject)?;
+        Ok(self.match_arms(&val.arms, &subject)?.unwrap_or(Object::Nil))
+    }
+
+    fn visit_expression_stmt(&mut self, val: &Expression) -> Result<Object, ControlFlow> {
         self.evaluate(&val.expr)
     }
 
-    fn visit_print_stmt(&mut self, val: &Print) -> std::result::Result<Object, LoxError> {
+    fn visit_print_stmt(&mut self, val: &Print) -> std::result::Result<Object, ControlFlow> {
         let res = self.evaluate(&val.expr)?;
         self.system_interface.borrow_mut().print(&res);
         return Ok(res);
     }
 
-    fn visit_variable_stmt(&mut self, val: &Variable) -> Result<Object, LoxError> {
-        self.variable_lookup(&val.name)
+    fn visit_variable_stmt(&mut self, val: &Variable) -> Result<Object, ControlFlow> {
+        Ok(self.variable_lookup(&val.name)?)
     }
 
-    fn visit_var_stmt(&mut self, val: &Var) -> Result<Object, LoxError> {
+    fn visit_var_stmt(&mut self, val: &Var) -> Result<Object, ControlFlow> {
         let mut value = Object::Nil;
         if let Some(var) = &val.initializer {
             value = self.evaluate(var)?;
@@ -338,29 +483,17 @@ impl Visitor<Object> for Interpreter {
         return Ok(Object::Nil);
     }
 
-    fn visit_assign_stmt(&mut self, val: &Assign) -> Result<Object, LoxError> {
+    fn visit_assign_stmt(&mut self, val: &Assign) -> Result<Object, ControlFlow> {
         let value = self.evaluate(&val.value)?;
-        if !(if let Some(dist) = val.name.scope {
-            self.env
-                .assign_at(val.name.lexeme.clone(), value.clone(), dist)
-        } else {
-            self.global.assign(val.name.lexeme.clone(), value.clone())
-        }) {
-            return Err(LoxError::RuntimeError(
-                val.name.lexeme.clone(),
-                val.name.line_no,
-                "Undefined assign".to_string(),
-            ));
-        }
-
+        self.assign(&val.name, value.clone())?;
         return Ok(value);
     }
 
-    fn visit_block_stmt(&mut self, val: &Block) -> Result<Object, LoxError> {
+    fn visit_block_stmt(&mut self, val: &Block) -> Result<Object, ControlFlow> {
         let env = LocalEnvironment::build(self.env.clone());
         return self.execute_block(&val.statements, env);
     }
-    fn visit_if_stmt(&mut self, val: &If) -> Result<Object, LoxError> {
+    fn visit_if_stmt(&mut self, val: &If) -> Result<Object, ControlFlow> {
         let mut is_true;
         if let Object::Bool(truthy) = self.evaluate(&val.condition)? {
             is_true = truthy;
@@ -377,44 +510,71 @@ impl Visitor<Object> for Interpreter {
         return Ok(Object::Nil);
     }
 
-    fn visit_while_stmt(&mut self, val: &While) -> Result<Object, LoxError> {
+    fn visit_while_stmt(&mut self, val: &While) -> Result<Object, ControlFlow> {
         let mut res = self.evaluate(&val.condition)?;
         while self.is_true(&res) {
             match self.evaluate(&val.body) {
-                Err(LoxError::Break(_)) => break,
-                Err(LoxError::Continue(_)) => continue,
-                Err(LoxError::ReturnVal(obj, ln)) => return Err(LoxError::ReturnVal(obj, ln)),
-                _ => {}
+                Err(ControlFlow::Break(_)) => break,
+                Ok(_) | Err(ControlFlow::Continue(_)) => {}
+                Err(flow) => return Err(flow),
             }
             res = self.evaluate(&val.condition)?;
         }
         return Ok(Object::Nil);
     }
 
-    fn visit_break_stmt(&mut self, val: &Break) -> Result<Object, LoxError> {
-        Err(LoxError::Break(val.keyword.line_no))
+    fn visit_for_in_stmt(&mut self, val: &ForIn) -> Result<Object, ControlFlow> {
+        let mut items = self.for_in_items(val)?;
+        while let Some(item) = self.next_item(&mut items, &val.keyword)? {
+            if !self.for_in_body(val, item)? {
+                break;
+            }
+        }
+        Ok(Object::Nil)
+    }
+
+    fn visit_yield_stmt(&mut self, val: &Yield) -> Result<Object, ControlFlow> {
+        // the bodies of generators are only run by resume_stmt
+        Err(LoxError::semantic(
+            &val.keyword,
+            ErrorCode::YieldOutsideFunction,
+            "Yield only from function",
+        )
+        .into())
+    }
+
+    fn visit_break_stmt(&mut self, val: &Break) -> Result<Object, ControlFlow> {
+        Err(ControlFlow::Break(val.keyword.line_no))
     }
 
-    fn visit_continue_stmt(&mut self, val: &Continue) -> Result<Object, LoxError> {
-        Err(LoxError::Continue(val.keyword.line_no))
+    fn visit_continue_stmt(&mut self, val: &Continue) -> Result<Object, ControlFlow> {
+        Err(ControlFlow::Continue(val.keyword.line_no))
     }
 
-    fn visit_function_stmt(&mut self, val: &Function) -> Result<Object, LoxError> {
+    fn visit_function_stmt(&mut self, val: &Function) -> Result<Object, ControlFlow> {
         let func = LoxFunction::new(val.clone(), self.env.clone(), false);
         self.env
             .define_at(val.name.lexeme.clone(), Object::Function(Rc::new(func)), 0);
         return Ok(Object::Nil);
     }
 
-    fn visit_return_stmt(&mut self, val: &Return) -> Result<Object, LoxError> {
+    fn visit_return_stmt(&mut self, val: &Return) -> Result<Object, ControlFlow> {
         let mut ret_value = Object::Nil;
         if let Some(value) = &val.value {
             ret_value = self.evaluate(value)?;
         }
-        return Err(LoxError::ReturnVal(ret_value, val.keyword.line_no));
+        return Err(ControlFlow::Return(ret_value, val.keyword.line_no));
     }
 
-    fn visit_class_stmt(&mut self, val: &Class) -> Result<Object, LoxError> {
+    fn visit_class_stmt(&mut self, val: &Class) -> Result<Object, ControlFlow> {
+        if let Some(hops) = val.name.scope {
+            self.env
+                .define_at(val.name.lexeme.clone(), Object::Nil, hops);
+        } else {
+            self.global.define(val.name.lexeme.clone(), Object::Nil);
+        }
+
+        let enclosing = self.env.clone();
         let mut super_class = None;
         if let Some(sp_class) = &val.superclass {
             if let Object::Class(value) = &self.visit_variable_stmt(sp_class)? {
@@ -423,25 +583,18 @@ impl Visitor<Object> for Interpreter {
                 self.env
                     .define_at("super".to_string(), Object::Class(Rc::clone(value)), 0);
             } else {
-                return Err(LoxError::RuntimeError(
-                    val.name.lexeme.clone(),
-                    val.name.line_no,
-                    "SuperClass must be a class".to_string(),
-                ));
+                return Err(LoxError::runtime(
+                    &val.name,
+                    ErrorCode::SuperclassNotClass,
+                    "SuperClass must be a class",
+                )
+                .into());
             }
         }
 
-        if let Some(hops) = val.name.scope {
-            self.env
-                .define_at(val.name.lexeme.clone(), Object::Nil, hops);
-        } else {
-            self.global.define(val.name.lexeme.clone(), Object::Nil);
-        }
-
         let mut methods = HashMap::new();
 
         for method in &val.methods {
-            println!("{}", method.name.lexeme.clone());
             let func = Rc::new(LoxFunction::new(
                 method.clone(),
                 self.env.clone(),
@@ -458,6 +611,8 @@ impl Visitor<Object> for Interpreter {
             super_class,
         )));
 
+        // the class is defined in the scope enclosing the one holding `super`
+        self.env = enclosing;
         if let Some(hops) = val.name.scope {
             self.env.assign_at(val.name.lexeme.clone(), klass, hops);
         } else {
@@ -466,8 +621,57 @@ impl Visitor<Object> for Interpreter {
         return Ok(Object::Nil);
     }
 
-    fn visit_stack_trace_stmt(&mut self) -> Result<Object, LoxError> {
-        todo!()
+    fn visit_try_stmt(&mut self, val: &Try) -> Result<Object, ControlFlow> {
+        let env = LocalEnvironment::build(self.env.clone());
+        let mut res = self.execute_block(&val.body, env);
+        if let (Some(catch), Err(ControlFlow::Error(err))) = (&val.catch, &res) {
+            if let Some(exception) = self.exception_value(err) {
+                self.backtrace = None;
+                let env = LocalEnvironment::build(self.env.clone());
+                env.define_at(catch.name.lexeme.clone(), exception, 0);
+                res = self.execute_block(&catch.body, env);
+            }
+        }
+        if let Some(finally) = &val.finally {
+            let env = LocalEnvironment::build(self.env.clone());
+            self.execute_block(finally, env)?;
+        }
+        res
+    }
+
+    fn visit_match_stmt(&mut self, val: &Match) -> Result<Object, ControlFlow> {
+        let subject = self.evaluate(&val.subject)?;
+        self.match_arms(&val.arms, &subject)?;
+        Ok(Object::Nil)
+    }
+
+    fn visit_throw_stmt(&mut self, val: &Throw) -> Result<Object, ControlFlow> {
+        let value = self.evaluate(&val.value)?;
+        Err(LoxError::Throw(value, val.keyword.line_no, Some(val.keyword.span)).into())
+    }
+
+    fn visit_import_stmt(&mut self, val: &Import) -> Result<Object, ControlFlow> {
+        let module = self.import_module(&val.path, val.span)?;
+        if let Some(name) = &val.name {
+            self.global.define(name.lexeme.clone(), module.clone());
+        }
+        if let Object::Instance(module) = &module {
+            for name in &val.names {
+                let value = module.get(name).ok_or_else(|| {
+                    LoxError::runtime(name, ErrorCode::Undefined, "Undefined module member")
+                })?;
+                self.global.define(name.lexeme.clone(), value);
+            }
+        }
+        Ok(Object::Nil)
+    }
+
+    fn visit_stack_trace_stmt(&mut self, val: &StackTrace) -> Result<Object, ControlFlow> {
+        let trace = format_backtrace(&self.backtrace(val.keyword.line_no));
+        self.system_interface
+            .borrow_mut()
+            .print(&Object::Str(trace));
+        Ok(Object::Nil)
     }
 }
 
@@ -475,20 +679,209 @@ impl Interpreter {
     pub fn new(syscall: Rc<RefCell<dyn SystemCalls>>) -> Self {
         let env = GlobalEnvironment::new();
         env.define("clock".to_string(), Object::Function(Rc::new(ClockFunc {})));
-        Interpreter {
+        env.define("len".to_string(), Object::Function(Rc::new(LenFunc {})));
+        env.define("keys".to_string(), Object::Function(Rc::new(KeysFunc {})));
+        env.define("int".to_string(), Object::Function(Rc::new(IntFunc {})));
+        env.define("float".to_string(), Object::Function(Rc::new(FloatFunc {})));
+        let mut interpreter = Interpreter {
             env: LocalEnvironment::from(env.clone()),
             global: env,
             system_interface: syscall,
+            path: None,
+            modules: Rc::new(RefCell::new(Modules::new())),
+            call_stack: vec![],
+            call_line: 0,
+            backtrace: None,
+            frame_name: "script".to_string(),
+            prelude: HashMap::new(),
+        };
+        interpreter.load_prelude();
+        interpreter
+    }
+
+    pub fn set_path<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.modules.borrow_mut().enter(path.clone());
+        self.path = Some(path);
+    }
+
+    // The modules imported so far, and the files they were read from.
+    pub fn modules(&self) -> Ref<'_, Modules<Object>> {
+        self.modules.borrow()
+    }
+
+    pub fn modules_mut(&self) -> RefMut<'_, Modules<Object>> {
+        self.modules.borrow_mut()
+    }
+
+    fn import_module(&mut self, path: &Token, import: Span) -> Result<Object, LoxError> {
+        let file = module::resolve(self.path.as_deref(), path)?;
+        if let Some(module) = self.modules.borrow().get(&file) {
+            return Ok(module.clone());
+        }
+        if !self.modules.borrow_mut().enter(file.clone()) {
+            return Err(module::cycle_error(
+                &self.modules.borrow(),
+                &file,
+                path,
+                import,
+            ));
+        }
+        let module = self.run_module(&file, path);
+        self.modules.borrow_mut().exit();
+        let module = module?;
+        self.modules.borrow_mut().insert(file, module.clone());
+        Ok(module)
+    }
+
+    // Runs a module with globals of its own, its namespace being a Module instance reading them.
+    fn run_module(&mut self, file: &Path, path: &Token) -> Result<Object, LoxError> {
+        let mut ast = module::load(&mut self.modules.borrow_mut(), file, path)?;
+        Resolver::new()
+            .resolve_all(&mut ast)
+            .map_err(|mut errors| errors.remove(0))?;
+        let mut interpreter = Interpreter::new(Rc::clone(&self.system_interface));
+        interpreter.path = Some(file.to_path_buf());
+        interpreter.modules = Rc::clone(&self.modules);
+        interpreter.frame_name = module::frame_name(path);
+        if let Err(err) = interpreter.run_statements(&mut ast) {
+            // the module's frames go on top of the ones of the code importing it
+            let mut frames = interpreter.take_backtrace(&err);
+            frames.extend(self.backtrace(path.line_no));
+            self.backtrace = Some(frames);
+            return Err(err);
+        }
+
+        let class = match self.prelude.get("Module") {
+            Some(class) => (**class).clone(),
+            None => LoxClass::new("Module".to_string(), Rc::new(HashMap::new()), None),
+        };
+        let namespace = LoxInstance::module(class, interpreter.global, module::exports(&ast));
+        Ok(Object::Instance(Rc::new(namespace)))
+    }
+
+    fn load_prelude(&mut self) {
+        let tokens = Lexer::new().parse(&PRELUDE.to_string()).unwrap();
+        let mut prelude = Parser::new(tokens).parse().unwrap();
+        Resolver::new().resolve_all(&mut prelude).unwrap();
+        self.interpret(&mut prelude).unwrap();
+        for name in module::exports(&prelude) {
+            if let Some(Object::Class(class)) = self.global.get(name.clone()) {
+                self.prelude.insert(name, class);
+            }
         }
     }
 
+    // The value a catch clause binds: thrown values as is, and runtime errors as
+    // instances of the prelude's Error classes. Other errors are not catchable.
+    fn exception_value(&self, err: &LoxError) -> Option<Object> {
+        let line_no = match err {
+            LoxError::Throw(value, ..) => return Some(value.clone()),
+            LoxError::RuntimeError { line_no, .. } => *line_no,
+            _ => return None,
+        };
+        let message = Object::Str(err.error_message());
+        if let Some(class) = self.prelude.get(err.error_class()) {
+            let instance = LoxInstance::new((**class).clone());
+            instance.set(&Self::field("message", line_no), message);
+            instance.set(&Self::field("line", line_no), Object::Int(line_no as i64));
+            return Some(Object::Instance(Rc::new(instance)));
+        }
+        Some(message)
+    }
+
+    pub fn enter_call(&mut self, name: String) {
+        self.call_stack.push(TraceFrame::new(name, self.call_line));
+    }
+
+    // Keeps the backtrace of an error leaving the function, unless a deeper call has already.
+    pub fn exit_call(&mut self, res: &Result<Object, LoxError>) {
+        if let Err(LoxError::RuntimeError { line_no, .. }) | Err(LoxError::Throw(_, line_no, _)) =
+            res
+        {
+            if self.backtrace.is_none() {
+                self.backtrace = Some(self.backtrace(*line_no));
+            }
+        }
+        self.call_stack.pop();
+    }
+
+    // The calls being run innermost first, the innermost one having got to line_no.
+    fn backtrace(&self, line_no: u32) -> Vec<TraceFrame> {
+        let mut frames = vec![];
+        let mut line_no = line_no;
+        for call in self.call_stack.iter().rev() {
+            frames.push(TraceFrame::new(call.name.clone(), line_no));
+            line_no = call.line_no;
+        }
+        frames.push(TraceFrame::new(self.frame_name.clone(), line_no));
+        frames
+    }
+
+    // Errors nothing caught are reported with the backtrace of where they were raised.
+    fn traced(&mut self, err: LoxError) -> LoxError {
+        let frames = self.take_backtrace(&err);
+        self.call_stack.clear();
+        let err = match err {
+            LoxError::Throw(val, line_no, span) => Self::uncaught(val, line_no, span),
+            err => err,
+        };
+        err.with_backtrace(frames)
+    }
+
+    fn take_backtrace(&mut self, err: &LoxError) -> Vec<TraceFrame> {
+        match (self.backtrace.take(), err) {
+            (Some(frames), _) => frames,
+            (None, LoxError::RuntimeError { line_no, .. })
+            | (None, LoxError::Throw(_, line_no, _)) => self.backtrace(*line_no),
+            _ => vec![],
+        }
+    }
+
+    // An uncaught thrown value is reported like any other runtime error.
+    fn uncaught(value: Object, line_no: u32, span: Option<Span>) -> LoxError {
+        if let Object::Instance(instance) = &value {
+            if let Some(message) = instance.get(&Self::field("message", line_no)) {
+                let line_no = match instance.get(&Self::field("line", line_no)) {
+                    Some(Object::Int(line)) => line as u32,
+                    Some(Object::Num(line)) => line as u32,
+                    _ => line_no,
+                };
+                let class = instance.klass.name.clone();
+                let message = format!("Uncaught {}: {}", class, message);
+                return LoxError::RuntimeError {
+                    lexeme: class,
+                    line_no,
+                    message,
+                    code: ErrorCode::Uncaught,
+                    span: span.map(Box::new),
+                    backtrace: Box::new([]),
+                };
+            }
+        }
+        let message = format!("Uncaught exception: {}", value);
+        LoxError::RuntimeError {
+            lexeme: value.to_string(),
+            line_no,
+            message,
+            code: ErrorCode::Uncaught,
+            span: span.map(Box::new),
+            backtrace: Box::new([]),
+        }
+    }
+
+    fn field(name: &str, line_no: u32) -> Token {
+        Token::new(TokenType::IDENTIFIER, line_no, None, name.to_string())
+    }
+
     pub fn execute_block(
         &mut self,
         stmts: &Vec<Stmt>,
         env: LocalEnvironment,
-    ) -> Result<Object, LoxError>
+    ) -> Result<Object, ControlFlow>
     where
-        Self: Visitor<Object>,
+        Self: Visitor<Object, ControlFlow>,
     {
         let prev = std::mem::replace(&mut self.env, env);
         let ret_val = Object::Nil;
@@ -507,7 +900,7 @@ impl Interpreter {
 
     fn is_true(&self, obj: &Object) -> bool
     where
-        Self: Visitor<Object>,
+        Self: Visitor<Object, ControlFlow>,
     {
         return match obj {
             Object::Bool(v) => *v,
@@ -516,15 +909,631 @@ impl Interpreter {
         };
     }
 
+    fn list_index(&self, bracket: &Token, index: &Object, len: usize) -> Result<usize, LoxError> {
+        if let Some(num) = index.as_number() {
+            if let Some(index) = num.as_int().filter(|index| (0..len as i64).contains(index)) {
+                return Ok(index as usize);
+            }
+            return Err(LoxError::RuntimeError {
+                lexeme: index.to_string(),
+                line_no: bracket.line_no,
+                message: "Index out of bounds".to_string(),
+                code: ErrorCode::IndexOutOfBounds,
+                span: Some(Box::new(bracket.span)),
+                backtrace: Box::new([]),
+            });
+        }
+        Err(LoxError::runtime(
+            bracket,
+            ErrorCode::IndexType,
+            "Index not a Num",
+        ))
+    }
+
+    // The value of a binary operator, errors being reported at the operator.
+    fn binary(&self, operator: &Token, left: Object, right: Object) -> Result<Object, ControlFlow> {
+        let error =
+            |code, msg: &str| -> ControlFlow { LoxError::runtime(operator, code, msg).into() };
+        let op = match operator.token_type {
+            TokenType::PLUS => BinaryOp::Add,
+            TokenType::MINUS => BinaryOp::Sub,
+            TokenType::STAR => BinaryOp::Mul,
+            TokenType::SLASH => BinaryOp::Div,
+            TokenType::TildeSlash => BinaryOp::IntDiv,
+            TokenType::PERCENT => BinaryOp::Mod,
+            TokenType::StarStar => BinaryOp::Pow,
+            TokenType::AMPERSAND => BinaryOp::BitAnd,
+            TokenType::PIPE => BinaryOp::BitOr,
+            TokenType::CARET => BinaryOp::BitXor,
+            TokenType::LessLess => BinaryOp::Shl,
+            TokenType::GreaterGreater => BinaryOp::Shr,
+            TokenType::GREATER
+            | TokenType::GreaterEqual
+            | TokenType::LESS
+            | TokenType::LessEqual => {
+                let ordering = match (left.as_number(), right.as_number()) {
+                    (Some(left), Some(right)) => left.partial_cmp(right),
+                    _ => return Err(error(ErrorCode::OperandType, "Operands not Num")),
+                };
+                return Ok(Object::Bool(ordering.is_some_and(
+                    |ord| match operator.token_type {
+                        TokenType::GREATER => ord.is_gt(),
+                        TokenType::GreaterEqual => ord.is_ge(),
+                        TokenType::LESS => ord.is_lt(),
+                        _ => ord.is_le(),
+                    },
+                )));
+            }
+            TokenType::BangEqual => return Ok(Object::Bool(left != right)),
+            TokenType::EqualEqual => return Ok(Object::Bool(left == right)),
+            _ => return Err(error(ErrorCode::Run, "Operator Unhandled")),
+        };
+        if op == BinaryOp::Div && right.as_number().is_some_and(|r| r.as_f64() == 0.0) {
+            return Err(error(ErrorCode::DivisionByZero, "Division by zero"));
+        }
+        left.binary(op, &right, operator.line_no)
+            .map_err(|err| match err {
+                LoxError::RuntimeError { code, message, .. } => error(code, &message),
+                err => err.into(),
+            })
+    }
+
+    // A field of an instance, else one of its methods bound to it.
+    fn property(&self, obj: &Object, name: &Token) -> Result<Object, LoxError> {
+        if let Object::Instance(inst) = obj {
+            return if let Some(val) = inst.get(name) {
+                Ok(val)
+            } else {
+                Ok(Object::Function(
+                    inst.klass.bind_method(name, Rc::clone(inst))?,
+                ))
+            };
+        }
+        if let Object::Generator(generator) = obj {
+            if let Some(resume) = Resume::from_name(&name.lexeme) {
+                let method = GeneratorMethod::new(Rc::clone(generator), resume);
+                return Ok(Object::Function(Rc::new(method)));
+            }
+            return Err(LoxError::runtime(
+                name,
+                ErrorCode::Undefined,
+                "Undefined property",
+            ));
+        }
+        Err(LoxError::runtime(
+            name,
+            ErrorCode::NotInstance,
+            "Only Instance have properties",
+        ))
+    }
+
+    fn index_get(&self, bracket: &Token, obj: &Object, index: &Object) -> Result<Object, LoxError> {
+        match obj {
+            Object::List(list) => {
+                let list = list.borrow();
+                let pos = self.list_index(bracket, index, list.len())?;
+                Ok(list[pos].clone())
+            }
+            Object::Map(map) => {
+                let key = self.map_key(bracket, index)?;
+                Ok(map.borrow().get(&key).unwrap_or(Object::Nil))
+            }
+            _ => Err(LoxError::runtime(
+                bracket,
+                ErrorCode::NotIndexable,
+                "Only lists and maps can be indexed",
+            )),
+        }
+    }
+
+    fn index_set(
+        &self,
+        bracket: &Token,
+        obj: &Object,
+        index: Object,
+        value: Object,
+    ) -> Result<(), LoxError> {
+        match obj {
+            Object::List(list) => {
+                let pos = self.list_index(bracket, &index, list.borrow().len())?;
+                list.borrow_mut()[pos] = value;
+                Ok(())
+            }
+            Object::Map(map) => {
+                let key = self.map_key(bracket, &index)?;
+                map.borrow_mut().set(key, index, value);
+                Ok(())
+            }
+            _ => Err(LoxError::runtime(
+                bracket,
+                ErrorCode::NotIndexable,
+                "Only lists and maps can be indexed",
+            )),
+        }
+    }
+
+    // The value of the body of the first arm that matches subject, run in a scope holding the
+    // names the arm's pattern binds.
+    fn match_arms<T: VisAcceptor<Object, ControlFlow>>(
+        &mut self,
+        arms: &[MatchArm<T>],
+        subject: &Object,
+    ) -> Result<Option<Object>, ControlFlow> {
+        let (index, env) = match self.select_arm(arms, subject)? {
+            Some(arm) => arm,
+            None => return Ok(None),
+        };
+        let prev = std::mem::replace(&mut self.env, env);
+        let res = self.evaluate(&arms[index].body);
+        self.env = prev;
+        res.map(Some)
+    }
+
+    // The first arm whose pattern matches subject and whose guard holds, with the scope holding
+    // the names its pattern binds.
+    fn select_arm<T>(
+        &mut self,
+        arms: &[MatchArm<T>],
+        subject: &Object,
+    ) -> Result<Option<(usize, LocalEnvironment)>, ControlFlow> {
+        for (index, arm) in arms.iter().enumerate() {
+            let env = LocalEnvironment::build(self.env.clone());
+            if !self.match_pattern(&arm.pattern, subject, &env)? {
+                continue;
+            }
+            if let Some(guard) = &arm.guard {
+                let prev = std::mem::replace(&mut self.env, env.clone());
+                let guard = self.evaluate(guard);
+                self.env = prev;
+                if !self.is_true(&guard?) {
+                    continue;
+                }
+            }
+            return Ok(Some((index, env)));
+        }
+        Ok(None)
+    }
+
+    // Whether value matches the pattern, defining the names it binds in env.
+    fn match_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Object,
+        env: &LocalEnvironment,
+    ) -> Result<bool, LoxError> {
+        match pattern {
+            Pattern::Wildcard(_) => Ok(true),
+            Pattern::Binding(name) => {
+                env.define_at(name.lexeme.clone(), value.clone(), 0);
+                Ok(true)
+            }
+            Pattern::Literal(literal, _) => Ok(*value == literal.clone().into()),
+            Pattern::Class(class, name) => {
+                let klass = match self.variable_lookup(&class.name)? {
+                    Object::Class(klass) => klass,
+                    _ => {
+                        return Err(LoxError::runtime(
+                            &class.name,
+                            ErrorCode::NotMatchable,
+                            "Only classes can be matched against",
+                        ))
+                    }
+                };
+                match value {
+                    Object::Instance(inst) if inst.klass.is_subclass_of(&klass) => {
+                        if let Some(name) = name {
+                            env.define_at(name.lexeme.clone(), value.clone(), 0);
+                        }
+                        Ok(true)
+                    }
+                    _ => Ok(false),
+                }
+            }
+            Pattern::List(_, elements) => {
+                let items = match value {
+                    Object::List(list) => list.borrow().clone(),
+                    _ => return Ok(false),
+                };
+                if items.len() != elements.len() {
+                    return Ok(false);
+                }
+                for (element, item) in elements.iter().zip(&items) {
+                    if !self.match_pattern(element, item, env)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    fn for_in_items(&mut self, val: &ForIn) -> Result<ForInItems, ControlFlow> {
+        let iterable = self.evaluate(&val.iterable)?;
+        // natives are iterated over a snapshot, so the body may change them
+        let items: Vec<Object> = match &iterable {
+            Object::List(list) => list.borrow().clone(),
+            Object::Str(string) => string
+                .chars()
+                .map(|ch| Object::Str(ch.to_string()))
+                .collect(),
+            Object::Map(map) => map.borrow().keys(),
+            Object::Instance(_) => {
+                return Ok(ForInItems::Iterator(self.invoke(
+                    &iterable,
+                    &val.keyword,
+                    "iterator",
+                )?))
+            }
+            // generators are their own iterators
+            Object::Generator(_) => return Ok(ForInItems::Iterator(iterable)),
+            _ => {
+                return Err(LoxError::runtime(
+                    &val.keyword,
+                    ErrorCode::NotIterable,
+                    "Only lists, maps, strings and instances can be iterated over",
+                )
+                .into())
+            }
+        };
+        Ok(ForInItems::Snapshot(items.into_iter()))
+    }
+
+    fn next_item(
+        &mut self,
+        items: &mut ForInItems,
+        keyword: &Token,
+    ) -> Result<Option<Object>, LoxError> {
+        match items {
+            ForInItems::Snapshot(items) => Ok(items.next()),
+            ForInItems::Iterator(iterator) => {
+                let has_next = self.invoke(iterator, keyword, "hasNext")?;
+                if !self.is_true(&has_next) {
+                    return Ok(None);
+                }
+                self.invoke(iterator, keyword, "next").map(Some)
+            }
+        }
+    }
+
+    // Runs the body of a for-in loop with its name bound to item, returning false on a break.
+    fn for_in_body(&mut self, val: &ForIn, item: Object) -> Result<bool, ControlFlow> {
+        let env = LocalEnvironment::build(self.env.clone());
+        env.define_at(val.name.lexeme.clone(), item, 0);
+        let prev = std::mem::replace(&mut self.env, env);
+        let res = self.evaluate(&val.body);
+        self.env = prev;
+        match res {
+            Err(ControlFlow::Break(_)) => Ok(false),
+            Ok(_) | Err(ControlFlow::Continue(_)) => Ok(true),
+            Err(flow) => Err(flow),
+        }
+    }
+
+    // Calls the method of obj with no arguments, as the iterator protocol does.
+    fn invoke(&mut self, obj: &Object, keyword: &Token, method: &str) -> Result<Object, LoxError> {
+        let mut name = keyword.clone();
+        name.lexeme = method.to_string();
+        let fn_def = match self.property(obj, &name)? {
+            Object::Function(fn_def) if fn_def.arity() == 0 => fn_def,
+            _ => {
+                return Err(LoxError::runtime(
+                    &name,
+                    ErrorCode::ArgumentCount,
+                    "Iterator methods take no arguments",
+                ))
+            }
+        };
+        self.call_line = keyword.line_no;
+        fn_def.call(self, vec![])
+    }
+
+    // Runs the generator up to its next yield, unless a hasNext already has, for the value that
+    // yield handed over.
+    pub fn resume_generator(
+        &mut self,
+        generator: &LoxGenerator,
+        resume: Resume,
+    ) -> Result<Object, LoxError> {
+        let next = generator.next.borrow_mut().take();
+        let next = match next {
+            Some(value) => Some(value),
+            None => self.run_generator(generator)?,
+        };
+        Ok(match resume {
+            Resume::HasNext => {
+                let has_next = next.is_some();
+                *generator.next.borrow_mut() = next;
+                Object::Bool(has_next)
+            }
+            // a finished generator has nothing more to hand over
+            Resume::Next => next.unwrap_or(Object::Nil),
+        })
+    }
+
+    fn run_generator(&mut self, generator: &LoxGenerator) -> Result<Option<Object>, LoxError> {
+        let state = generator.state.replace(GeneratorState::Running);
+        let block = match state {
+            GeneratorState::Suspended(block) => block,
+            GeneratorState::Done => {
+                generator.state.replace(GeneratorState::Done);
+                return Ok(None);
+            }
+            GeneratorState::Running => {
+                return Err(LoxError::RuntimeError {
+                    lexeme: generator.name.clone(),
+                    line_no: self.call_line,
+                    message: "Generator is already running".to_string(),
+                    code: ErrorCode::GeneratorRunning,
+                    span: None,
+                    backtrace: Box::new([]),
+                })
+            }
+        };
+        let globals = std::mem::replace(&mut self.global, generator.globals.clone());
+        self.enter_call(generator.name.clone());
+        let (state, res) = match self.resume_block(&generator.body, block) {
+            Ok(Some((value, block))) => (GeneratorState::Suspended(block), Ok(Some(value))),
+            Ok(None) => (GeneratorState::Done, Ok(None)),
+            // a return ends the generator, and what it returns is dropped
+            Err(flow) => (GeneratorState::Done, flow.into_return().map(|_| None)),
+        };
+        self.exit_call(&res.clone().map(|_| Object::Nil));
+        self.global = globals;
+        generator.state.replace(state);
+        res
+    }
+
+    // Runs statements in the block's scope from where it stopped, up to the next yield, for
+    // the value yielded and where the block stopped. None when the statements ran to the end.
+    fn resume_block(
+        &mut self,
+        stmts: &[Stmt],
+        mut block: BlockState,
+    ) -> Result<Option<(Object, BlockState)>, ControlFlow> {
+        let prev = std::mem::replace(&mut self.env, block.env.clone());
+        while block.index < stmts.len() {
+            match self.resume_stmt(&stmts[block.index], block.at.take()) {
+                Ok(Some((value, at))) => {
+                    self.env = prev;
+                    block.at = Some(at);
+                    return Ok(Some((value, block)));
+                }
+                Ok(None) => block.index += 1,
+                Err(flow) => {
+                    self.env = prev;
+                    return Err(flow);
+                }
+            }
+        }
+        self.env = prev;
+        Ok(None)
+    }
+
+    // Runs a statement of a generator, or carries on with it from where it stopped, up to the
+    // next yield in it. Statements without a yield in them are run as usual.
+    fn resume_stmt(
+        &mut self,
+        stmt: &Stmt,
+        at: Option<Suspended>,
+    ) -> Result<Option<(Object, Suspended)>, ControlFlow> {
+        if at.is_none() && !stmt.yields() {
+            self.evaluate(stmt)?;
+            return Ok(None);
+        }
+        match stmt {
+            Stmt::Yield(val) => {
+                if at.is_some() {
+                    return Ok(None);
+                }
+                let value = match &val.value {
+                    Some(value) => self.evaluate(value)?,
+                    None => Object::Nil,
+                };
+                Ok(Some((value, Suspended::Yield)))
+            }
+            Stmt::Block(val) => {
+                let block = match at {
+                    Some(Suspended::Block(block)) => *block,
+                    _ => BlockState::new(LocalEnvironment::build(self.env.clone())),
+                };
+                let res = self.resume_block(&val.statements, block)?;
+                Ok(res.map(|(value, block)| (value, Suspended::Block(Box::new(block)))))
+            }
+            Stmt::If(val) => {
+                let (then, at) = match at {
+                    Some(Suspended::If(then, at)) => (then, Some(*at)),
+                    _ => {
+                        let condition = self.evaluate(&val.condition)?;
+                        (self.is_true(&condition), None)
+                    }
+                };
+                let branch = if then {
+                    Some(&val.then_branch)
+                } else {
+                    val.else_branch.as_ref()
+                };
+                match branch {
+                    Some(branch) => Ok(self
+                        .resume_stmt(branch, at)?
+                        .map(|(value, at)| (value, Suspended::If(then, Box::new(at))))),
+                    None => Ok(None),
+                }
+            }
+            Stmt::While(val) => {
+                let mut at = match at {
+                    Some(Suspended::While(at)) => Some(*at),
+                    _ => None,
+                };
+                loop {
+                    if at.is_none() {
+                        let condition = self.evaluate(&val.condition)?;
+                        if !self.is_true(&condition) {
+                            break;
+                        }
+                    }
+                    match self.resume_stmt(&val.body, at.take()) {
+                        Ok(Some((value, at))) => {
+                            return Ok(Some((value, Suspended::While(Box::new(at)))))
+                        }
+                        Err(ControlFlow::Break(_)) => break,
+                        Ok(None) | Err(ControlFlow::Continue(_)) => {}
+                        Err(flow) => return Err(flow),
+                    }
+                }
+                Ok(None)
+            }
+            Stmt::ForIn(val) => {
+                let (mut items, mut current) = match at {
+                    Some(Suspended::ForIn(state)) => (state.items, Some((state.env, state.at))),
+                    _ => (self.for_in_items(val)?, None),
+                };
+                loop {
+                    let (env, at) = match current.take() {
+                        Some((env, at)) => (env, Some(at)),
+                        None => match self.next_item(&mut items, &val.keyword)? {
+                            Some(item) => {
+                                let env = LocalEnvironment::build(self.env.clone());
+                                env.define_at(val.name.lexeme.clone(), item, 0);
+                                (env, None)
+                            }
+                            None => break,
+                        },
+                    };
+                    let prev = std::mem::replace(&mut self.env, env.clone());
+                    let res = self.resume_stmt(&val.body, at);
+                    self.env = prev;
+                    match res {
+                        Ok(Some((value, at))) => {
+                            let state = ForInState { items, env, at };
+                            return Ok(Some((value, Suspended::ForIn(Box::new(state)))));
+                        }
+                        Err(ControlFlow::Break(_)) => break,
+                        Ok(None) | Err(ControlFlow::Continue(_)) => {}
+                        Err(flow) => return Err(flow),
+                    }
+                }
+                Ok(None)
+            }
+            Stmt::Match(val) => {
+                let (index, env, at) = match at {
+                    Some(Suspended::Match(index, env, at)) => (index, env, Some(*at)),
+                    _ => {
+                        let subject = self.evaluate(&val.subject)?;
+                        match self.select_arm(&val.arms, &subject)? {
+                            Some((index, env)) => (index, env, None),
+                            None => return Ok(None),
+                        }
+                    }
+                };
+                let prev = std::mem::replace(&mut self.env, env.clone());
+                let res = self.resume_stmt(&val.arms[index].body, at);
+                self.env = prev;
+                Ok(res?.map(|(value, at)| (value, Suspended::Match(index, env, Box::new(at)))))
+            }
+            Stmt::Try(val) => {
+                let state = match at {
+                    Some(Suspended::Try(state)) => *state,
+                    _ => TryState::Body(BlockState::new(LocalEnvironment::build(self.env.clone()))),
+                };
+                let res = self.resume_try(val, state)?;
+                Ok(res.map(|(value, state)| (value, Suspended::Try(Box::new(state)))))
+            }
+            _ => {
+                self.evaluate(stmt)?;
+                Ok(None)
+            }
+        }
+    }
+
+    // Carries on with a try statement of a generator, as visit_try_stmt runs it.
+    fn resume_try(
+        &mut self,
+        val: &Try,
+        mut state: TryState,
+    ) -> Result<Option<(Object, TryState)>, ControlFlow> {
+        loop {
+            let catching = matches!(state, TryState::Body(_));
+            let res = match state {
+                TryState::Body(block) => match self.resume_block(&val.body, block) {
+                    Ok(Some((value, block))) => return Ok(Some((value, TryState::Body(block)))),
+                    Ok(None) => Ok(Object::Nil),
+                    Err(flow) => Err(flow),
+                },
+                TryState::Catch(block) => {
+                    let catch = val.catch.as_ref().unwrap();
+                    match self.resume_block(&catch.body, block) {
+                        Ok(Some((value, block))) => {
+                            return Ok(Some((value, TryState::Catch(block))))
+                        }
+                        Ok(None) => Ok(Object::Nil),
+                        Err(flow) => Err(flow),
+                    }
+                }
+                TryState::Finally(block, res) => {
+                    let finally = val.finally.as_ref().unwrap();
+                    return match self.resume_block(finally, block)? {
+                        Some((value, block)) => Ok(Some((value, TryState::Finally(block, res)))),
+                        None => res.map(|_| None),
+                    };
+                }
+            };
+            if let (true, Some(catch), Err(ControlFlow::Error(err))) = (catching, &val.catch, &res)
+            {
+                if let Some(exception) = self.exception_value(err) {
+                    self.backtrace = None;
+                    let env = LocalEnvironment::build(self.env.clone());
+                    env.define_at(catch.name.lexeme.clone(), exception, 0);
+                    state = TryState::Catch(BlockState::new(env));
+                    continue;
+                }
+            }
+            match val.finally {
+                Some(_) => {
+                    let env = LocalEnvironment::build(self.env.clone());
+                    state = TryState::Finally(BlockState::new(env), res);
+                }
+                None => return res.map(|_| None),
+            }
+        }
+    }
+
+    fn map_key(&self, token: &Token, key: &Object) -> Result<MapKey, LoxError> {
+        MapKey::from_object(key).ok_or_else(|| LoxError::RuntimeError {
+            lexeme: key.to_string(),
+            line_no: token.line_no,
+            message: "Map keys must be Str, Num, Bool or Instance".to_string(),
+            code: ErrorCode::MapKey,
+            span: Some(Box::new(token.span)),
+            backtrace: Box::new([]),
+        })
+    }
+
+    fn assign(&mut self, name: &Token, value: Object) -> Result<(), LoxError> {
+        if !(if let Some(dist) = name.scope {
+            self.env.assign_at(name.lexeme.clone(), value, dist)
+        } else {
+            self.global.assign(name.lexeme.clone(), value)
+        }) {
+            return Err(LoxError::runtime(
+                name,
+                ErrorCode::Undefined,
+                "Undefined assign",
+            ));
+        }
+        Ok(())
+    }
+
+    // The target's old value combined with the compound's value by its operator.
+    fn compound_value(&mut self, val: &Compound, old: Object) -> Result<Object, ControlFlow> {
+        let value = self.evaluate(&val.value)?;
+        self.binary(&val.operator, old, value)
+    }
+
     fn variable_lookup(&mut self, name: &Token) -> Result<Object, LoxError>
     where
-        Self: Visitor<Object>,
+        Self: Visitor<Object, ControlFlow>,
     {
-        let err = LoxError::RuntimeError(
-            name.lexeme.clone(),
-            name.line_no,
-            "Undefined get".to_string(),
-        );
+        let err = LoxError::runtime(name, ErrorCode::Undefined, "Undefined get");
         return if let Some(dist) = name.scope {
             self.env
                 .get_at(name.lexeme.clone(), dist)
@@ -534,25 +1543,44 @@ impl Interpreter {
         };
     }
 
-    fn evaluate<T: VisAcceptor<Object> + Sized>(&mut self, expr: &T) -> Result<Object, LoxError>
+    fn evaluate<T: VisAcceptor<Object, ControlFlow> + Sized>(
+        &mut self,
+        expr: &T,
+    ) -> Result<Object, ControlFlow>
     where
-        Self: Visitor<Object>,
+        Self: Visitor<Object, ControlFlow>,
     {
         expr.accept(self)
     }
 
     pub fn interpret(&mut self, statements: &mut Vec<Stmt>) -> Result<(), LoxError>
     where
-        Self: Visitor<Object>,
+        Self: Visitor<Object, ControlFlow>,
         Object: Debug,
     {
+        self.interpret_line(statements)?;
+        Ok(())
+    }
+
+    // Like interpret, but hands back the value of a trailing expression statement, which the
+    // REPL echoes.
+    pub fn interpret_line(&mut self, statements: &mut Vec<Stmt>) -> Result<Option<Object>, LoxError>
+    where
+        Self: Visitor<Object, ControlFlow>,
+    {
+        self.run_statements(statements)
+            .map_err(|err| self.traced(err))
+    }
+
+    fn run_statements(&mut self, statements: &mut Vec<Stmt>) -> Result<Option<Object>, LoxError> {
+        let mut value = None;
         for stmt in statements {
-            let val = self.evaluate(stmt);
-            match val {
-                Ok(_) => {}
-                Err(err) => return Err(err),
-            }
+            let val = self.evaluate(stmt).map_err(ControlFlow::into_error)?;
+            value = match stmt {
+                Stmt::Expression(_) => Some(val),
+                _ => None,
+            };
         }
-        Ok(())
+        Ok(value)
     }
 }
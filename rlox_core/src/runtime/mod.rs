@@ -1,6 +1,9 @@
+pub mod control_flow;
 pub mod definitions;
 pub mod environment;
 pub mod interpreter;
+pub mod module;
+pub mod prelude;
 pub mod runner;
 pub mod system_calls;
 pub mod visitor;
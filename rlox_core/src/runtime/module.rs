@@ -0,0 +1,178 @@
+use crate::error::{ErrorCode, LoxError};
+use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::span::Span;
+use crate::frontend::definitions::stmt::Stmt;
+use crate::frontend::definitions::token::Token;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::parser::Parser;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+// Book keeping shared by every module of a program: modules already run, the chain of
+// modules being run, which an import must not lead back into, and the files read so far
+// along with their source, which errors raised in them are shown against.
+#[derive(Debug)]
+pub struct Modules<T> {
+    loaded: HashMap<PathBuf, T>,
+    loading: Vec<PathBuf>,
+    files: Vec<(PathBuf, String)>,
+}
+
+impl<T> Modules<T> {
+    pub fn new() -> Self {
+        Modules {
+            loaded: HashMap::new(),
+            loading: vec![],
+            files: vec![],
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&T> {
+        self.loaded.get(path)
+    }
+
+    // false if the module is already being run, i.e. the import closes a cycle
+    pub fn enter(&mut self, path: PathBuf) -> bool {
+        if self.loading.contains(&path) {
+            return false;
+        }
+        self.loading.push(path);
+        true
+    }
+
+    pub fn exit(&mut self) {
+        self.loading.pop();
+    }
+
+    pub fn insert(&mut self, path: PathBuf, module: T) {
+        self.loaded.insert(path, module);
+    }
+
+    // Files are numbered in the order they're first read, from 1 as 0 is the script run.
+    pub fn add_file(&mut self, path: &Path, source: String) -> usize {
+        match self.files.iter().position(|(file, _)| file == path) {
+            Some(pos) => {
+                self.files[pos].1 = source;
+                pos + 1
+            }
+            None => {
+                self.files.push((path.to_path_buf(), source));
+                self.files.len()
+            }
+        }
+    }
+
+    // Each REPL input is a file of its own, as errors may be raised in it after later ones.
+    pub fn add_input(&mut self, source: &str) -> usize {
+        self.files
+            .push((PathBuf::from("<input>"), source.to_string()));
+        self.files.len()
+    }
+
+    pub fn file(&self, file_id: usize) -> Option<(&Path, &str)> {
+        self.files
+            .get(file_id.checked_sub(1)?)
+            .map(|(path, source)| (path.as_path(), source.as_str()))
+    }
+}
+
+impl<T> Default for Modules<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Module paths are relative to the importing file, or to the working directory when the
+// importer is not a file.
+pub fn resolve(importer: Option<&Path>, path: &Token) -> Result<PathBuf, LoxError> {
+    let file = file_name(path);
+    let dir = importer
+        .and_then(Path::parent)
+        .unwrap_or_else(|| Path::new(""));
+    dir.join(&file)
+        .canonicalize()
+        .map_err(|_| LoxError::RuntimeError {
+            lexeme: file,
+            line_no: path.line_no,
+            message: "Module not found".to_string(),
+            code: ErrorCode::ModuleNotFound,
+            span: Some(Box::new(path.span)),
+            backtrace: Box::new([]),
+        })
+}
+
+// The error of an import, spanning import, of a module which is already being run. The message
+// goes round the cycle, from the module imported back to it.
+pub fn cycle_error<T>(modules: &Modules<T>, file: &Path, path: &Token, import: Span) -> LoxError {
+    let start = modules
+        .loading
+        .iter()
+        .position(|loading| loading == file)
+        .unwrap_or_default();
+    let mut cycle: Vec<&Path> = modules.loading[start..]
+        .iter()
+        .map(PathBuf::as_path)
+        .collect();
+    cycle.push(file);
+    let chain = cycle
+        .iter()
+        .map(|file| file.file_name().unwrap_or_default().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    LoxError::RuntimeError {
+        lexeme: file_name(path),
+        line_no: path.line_no,
+        message: format!("Import cycle detected: {}", chain),
+        code: ErrorCode::ImportCycle,
+        span: Some(Box::new(import)),
+        backtrace: Box::new([]),
+    }
+}
+
+// Backtraces show a module's top-level code as a frame of its own, named as it was imported.
+pub fn frame_name(path: &Token) -> String {
+    format!("module {}", file_name(path))
+}
+
+fn file_name(path: &Token) -> String {
+    match &path.literal {
+        Some(Literal::STRING(file)) => file.clone(),
+        _ => path.lexeme.clone(),
+    }
+}
+
+// Reads and parses a module, keeping its source for the errors raised in it.
+pub fn load<T>(
+    modules: &mut Modules<T>,
+    path: &Path,
+    token: &Token,
+) -> Result<Vec<Stmt>, LoxError> {
+    let script = read_to_string(path).map_err(|_| LoxError::RuntimeError {
+        lexeme: file_name(token),
+        line_no: token.line_no,
+        message: "Module not readable".to_string(),
+        code: ErrorCode::ModuleNotFound,
+        span: Some(Box::new(token.span)),
+        backtrace: Box::new([]),
+    })?;
+    let file_id = modules.add_file(path, script.clone());
+    Parser::new(Lexer::for_file(file_id).parse(&script)?).parse()
+}
+
+// The globals a module declares, which make up its namespace.
+pub fn exports(stmts: &[Stmt]) -> Vec<String> {
+    let mut names = vec![];
+    for stmt in stmts {
+        match stmt {
+            Stmt::Var(val) => names.push(val.name.lexeme.clone()),
+            Stmt::Function(val) => names.push(val.name.lexeme.clone()),
+            Stmt::Class(val) => names.push(val.name.lexeme.clone()),
+            Stmt::Import(val) => {
+                names.extend(val.name.iter().chain(&val.names).map(|n| n.lexeme.clone()))
+            }
+            _ => {}
+        }
+    }
+    names
+}
@@ -0,0 +1,15 @@
+// Lox source run ahead of every script by both the tree-walker and the VM.
+// Runtime errors raised by the interpreters are caught as instances of the Error classes,
+// and imported modules are bound as instances of Module.
+pub const PRELUDE: &str = r#"
+class Error {
+    init(message) {
+        this.message = message;
+    }
+}
+class TypeError < Error {}
+class NameError < Error {}
+class IndexError < Error {}
+class ArithmeticError < Error {}
+class Module {}
+"#;
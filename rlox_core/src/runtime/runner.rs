@@ -1,6 +1,9 @@
+use crate::diagnostics::{Diagnostic, Format};
+use crate::frontend::definitions::stmt::Stmt;
 use crate::frontend::lexer::*;
 use crate::frontend::parser::Parser;
 use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
 use crate::runtime::interpreter::Interpreter;
 use std::fs::read_to_string;
 use std::io::{self, stdin, Write};
@@ -8,7 +11,7 @@ use std::path::Path;
 
 use crate::runtime::system_calls::{SystemCalls, SystemInterface};
 
-use crate::error::LoxError;
+use crate::error::{Error, LoxError};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -20,41 +23,186 @@ use std::rc::Rc;
  * macros in tests
  * add tests that check for error cases too
  * */
+// The interpreter and resolver live as long as the runner, so a REPL session keeps its globals
+// from one input to the next.
 pub struct Runner {
     sys_interface: Rc<RefCell<dyn SystemCalls>>,
+    interpreter: Interpreter,
+    resolver: Resolver,
+    error_format: Format,
 }
 
 impl Runner {
     pub fn new() -> Self {
+        Runner::build(Rc::new(RefCell::new(SystemInterface {})))
+    }
+
+    pub fn build(sys_interface: Rc<RefCell<dyn SystemCalls>>) -> Self {
         Runner {
-            sys_interface: Rc::new(RefCell::new(SystemInterface {})),
+            interpreter: Interpreter::new(Rc::clone(&sys_interface)),
+            resolver: Resolver::new(),
+            error_format: Format::Plain,
+            sys_interface,
         }
     }
 
+    pub fn set_error_format(&mut self, format: Format) {
+        self.error_format = format;
+    }
+
     pub fn run_file(&mut self, path: &String) {
-        let path = Path::new(path);
         let script = read_to_string(path).unwrap();
-        if let Err(err) = self.run(&script) {
-            self.sys_interface.borrow_mut().print_error(err);
+        self.interpreter.set_path(Path::new(path));
+        if let Err(errors) = self.run(&script) {
+            for err in errors {
+                self.report(self.diagnostic(&Error::from(err), Some(path), &script));
+            }
         }
     }
+
     pub fn run_prompt(&mut self) {
         let mut buff = String::new();
-        let inp = stdin();
-        loop {
-            print!("> ");
-            io::stdout().flush().unwrap();
-            buff.clear();
-            inp.read_line(&mut buff).unwrap();
-            if let Err(err) = self.run(&buff) {
-                self.sys_interface.borrow_mut().print_error(err);
+        while read_input(&mut buff) {
+            if let Err(errors) = self.run_line(&buff) {
+                for err in errors {
+                    self.report(self.diagnostic(&err, None, &buff));
+                }
+            }
+        }
+    }
+
+    pub fn diagnostic(&self, err: &Error, file: Option<&str>, source: &str) -> Diagnostic {
+        Diagnostic::from_program(err, &self.interpreter.modules(), file, source)
+    }
+
+    fn report(&self, diagnostic: Diagnostic) {
+        let report = diagnostic.render(self.error_format);
+        self.sys_interface.borrow_mut().print_error(&report);
+    }
+
+    // Runs one REPL input, and prints the value of a bare expression statement ending it.
+    pub fn run_line(&mut self, script: &str) -> Result<(), Vec<Error>> {
+        self.eval_line(script)
+            .map_err(|errors| errors.into_iter().map(Error::from).collect())
+    }
+
+    fn eval_line(&mut self, script: &str) -> Result<(), Vec<LoxError>> {
+        let file_id = self.interpreter.modules_mut().add_input(script);
+        let mut ast = parse_line(script, file_id)?;
+        self.resolver.resolve_all(&mut ast)?;
+        if let Some(value) = self
+            .interpreter
+            .interpret_line(&mut ast)
+            .map_err(|err| vec![err])?
+        {
+            if value != Object::Nil {
+                self.sys_interface.borrow_mut().print(&value);
             }
         }
+        Ok(())
     }
-    fn run(&mut self, script: &String) -> Result<(), LoxError> {
-        let mut ast = Parser::new(Lexer::new().parse(script)?).parse()?;
-        Resolver::new().resolve(&mut ast)?;
-        Interpreter::new(Rc::clone(&mut self.sys_interface)).interpret(&mut ast)?;
+
+    // Every syntax or semantic error of the script, or else the runtime error which stopped it.
+    fn run(&mut self, script: &str) -> Result<(), Vec<LoxError>> {
+        let mut ast = parse_script(script)?;
+        self.resolver.resolve_all(&mut ast)?;
+        self.interpreter
+            .interpret(&mut ast)
+            .map_err(|err| vec![err])?;
         Ok(())
     }
 }
+
+// Reads one REPL input, which goes on over lines until its braces balance. false at the end
+// of input.
+pub fn read_input(buff: &mut String) -> bool {
+    buff.clear();
+    print!("> ");
+    io::stdout().flush().unwrap();
+    loop {
+        if stdin().read_line(buff).unwrap() == 0 {
+            return false;
+        }
+        if is_complete(buff) {
+            return true;
+        }
+        print!("... ");
+        io::stdout().flush().unwrap();
+    }
+}
+
+// The statements of a script, or all of its syntax errors. Tokens which couldn't be scanned
+// would only confuse the parser, so a script with scanner errors isn't parsed.
+pub fn parse_script(script: &str) -> Result<Vec<Stmt>, Vec<LoxError>> {
+    parse_file(script, 0)
+}
+
+// The statements of the source of a file, its spans carrying file_id.
+pub fn parse_file(script: &str, file_id: usize) -> Result<Vec<Stmt>, Vec<LoxError>> {
+    let tokens = match Lexer::for_file(file_id).parse_all(&script.to_string()) {
+        (tokens, errors) if errors.is_empty() => tokens,
+        (_, errors) => return Err(errors),
+    };
+    match Parser::new(tokens).parse_all() {
+        (ast, errors) if errors.is_empty() => Ok(ast),
+        (_, errors) => Err(errors),
+    }
+}
+
+pub fn parse_line(script: &str, file_id: usize) -> Result<Vec<Stmt>, Vec<LoxError>> {
+    parse_file(script, file_id).or_else(|errors| {
+        // a bare expression may be typed without its ';'
+        parse_file(&format!("{};", script.trim_end()), file_id).map_err(|_| errors)
+    })
+}
+
+// Whether an input closes every brace it opens, braces in strings and comments aside.
+pub fn is_complete(script: &str) -> bool {
+    let mut depth = 0;
+    let mut in_string = false;
+    // how many block comments deep the input is
+    let mut comments = 0;
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        if comments > 0 {
+            match (c, chars.peek()) {
+                ('/', Some('*')) => {
+                    chars.next();
+                    comments += 1;
+                }
+                ('*', Some('/')) => {
+                    chars.next();
+                    comments -= 1;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                comments += 1;
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    !in_string && comments == 0 && depth <= 0
+}
@@ -1,10 +1,14 @@
 use crate::error::LoxError;
 use crate::frontend::definitions::expr::{
-    Assign, Binary, Call, Expr, Get, Grouping, Lambda, Logical, Set, Super, This, Unary, Variable,
+    Assign, Binary, Call, Coalesce, Compound, Conditional, Expr, Get, Grouping, Index, Lambda,
+    List, Logical, Map, MatchExpr, OptionalChain, SafeGet, Set, SetIndex, Super, This, Unary,
+    Variable,
 };
 use crate::frontend::definitions::literal::Literal;
+use crate::frontend::definitions::span::Span;
 use crate::frontend::definitions::stmt::{
-    Block, Break, Class, Continue, Expression, Function, If, Print, Return, Stmt, Var, While,
+    Block, Break, Class, Continue, Expression, ForIn, Function, If, Import, Match, Print, Return,
+    StackTrace, Stmt, Throw, Try, Var, While, Yield,
 };
 
 pub trait VisitorMut<R> {
@@ -12,13 +16,23 @@ pub trait VisitorMut<R> {
     fn visit_call_expr(&mut self, expr: &mut Call) -> Result<R, LoxError>;
     fn visit_grouping_expr(&mut self, expr: &mut Grouping) -> Result<R, LoxError>;
     fn visit_unary_expr(&mut self, expr: &mut Unary) -> Result<R, LoxError>;
-    fn visit_literal_expr(&mut self, expr: &mut Literal) -> Result<R, LoxError>;
+    fn visit_literal_expr(&mut self, expr: &mut Literal, span: Span) -> Result<R, LoxError>;
     fn visit_logical_expr(&mut self, expr: &mut Logical) -> Result<R, LoxError>;
     fn visit_get_expr(&mut self, expr: &mut Get) -> Result<R, LoxError>;
     fn visit_set_expr(&mut self, expr: &mut Set) -> Result<R, LoxError>;
     fn visit_lambda_expr(&mut self, expr: &mut Lambda) -> Result<R, LoxError>;
     fn visit_this_expr(&mut self, expr: &mut This) -> Result<R, LoxError>;
     fn visit_super_expr(&mut self, expr: &mut Super) -> Result<R, LoxError>;
+    fn visit_list_expr(&mut self, expr: &mut List) -> Result<R, LoxError>;
+    fn visit_map_expr(&mut self, expr: &mut Map) -> Result<R, LoxError>;
+    fn visit_index_expr(&mut self, expr: &mut Index) -> Result<R, LoxError>;
+    fn visit_set_index_expr(&mut self, expr: &mut SetIndex) -> Result<R, LoxError>;
+    fn visit_compound_expr(&mut self, expr: &mut Compound) -> Result<R, LoxError>;
+    fn visit_conditional_expr(&mut self, expr: &mut Conditional) -> Result<R, LoxError>;
+    fn visit_coalesce_expr(&mut self, expr: &mut Coalesce) -> Result<R, LoxError>;
+    fn visit_safe_get_expr(&mut self, expr: &mut SafeGet) -> Result<R, LoxError>;
+    fn visit_optional_chain_expr(&mut self, expr: &mut OptionalChain) -> Result<R, LoxError>;
+    fn visit_match_expr(&mut self, expr: &mut MatchExpr) -> Result<R, LoxError>;
     fn visit_expression_stmt(&mut self, expr: &mut Expression) -> Result<R, LoxError>;
     fn visit_print_stmt(&mut self, expr: &mut Print) -> Result<R, LoxError>;
     fn visit_variable_stmt(&mut self, expr: &mut Variable) -> Result<R, LoxError>;
@@ -27,12 +41,18 @@ pub trait VisitorMut<R> {
     fn visit_block_stmt(&mut self, expr: &mut Block) -> Result<R, LoxError>;
     fn visit_if_stmt(&mut self, expr: &mut If) -> Result<R, LoxError>;
     fn visit_while_stmt(&mut self, expr: &mut While) -> Result<R, LoxError>;
+    fn visit_for_in_stmt(&mut self, expr: &mut ForIn) -> Result<R, LoxError>;
     fn visit_break_stmt(&mut self, expr: &mut Break) -> Result<R, LoxError>;
     fn visit_continue_stmt(&mut self, expr: &mut Continue) -> Result<R, LoxError>;
     fn visit_function_stmt(&mut self, expr: &mut Function) -> Result<R, LoxError>;
     fn visit_return_stmt(&mut self, expr: &mut Return) -> Result<R, LoxError>;
+    fn visit_yield_stmt(&mut self, expr: &mut Yield) -> Result<R, LoxError>;
     fn visit_class_stmt(&mut self, expr: &mut Class) -> Result<R, LoxError>;
-    fn visit_stack_trace_stmt(&mut self) -> Result<R, LoxError>;
+    fn visit_try_stmt(&mut self, expr: &mut Try) -> Result<R, LoxError>;
+    fn visit_throw_stmt(&mut self, expr: &mut Throw) -> Result<R, LoxError>;
+    fn visit_import_stmt(&mut self, expr: &mut Import) -> Result<R, LoxError>;
+    fn visit_stack_trace_stmt(&mut self, expr: &mut StackTrace) -> Result<R, LoxError>;
+    fn visit_match_stmt(&mut self, expr: &mut Match) -> Result<R, LoxError>;
 }
 
 pub trait VisitorMutAcceptor<T>: Sized {
@@ -45,7 +65,7 @@ impl<T> VisitorMutAcceptor<T> for Expr {
             Expr::Binary(v) => vis.visit_binary_expr(v),
             Expr::Grouping(v) => vis.visit_grouping_expr(v),
             Expr::Unary(v) => vis.visit_unary_expr(v),
-            Expr::Literal(v) => vis.visit_literal_expr(v),
+            Expr::Literal(v, span) => vis.visit_literal_expr(v, *span),
             Expr::Variable(v) => vis.visit_variable_stmt(v),
             Expr::Assign(v) => vis.visit_assign_stmt(v),
             Expr::Logical(v) => vis.visit_logical_expr(v),
@@ -55,6 +75,16 @@ impl<T> VisitorMutAcceptor<T> for Expr {
             Expr::Set(v) => vis.visit_set_expr(v),
             Expr::This(v) => vis.visit_this_expr(v),
             Expr::Super(v) => vis.visit_super_expr(v),
+            Expr::List(v) => vis.visit_list_expr(v),
+            Expr::Map(v) => vis.visit_map_expr(v),
+            Expr::Index(v) => vis.visit_index_expr(v),
+            Expr::SetIndex(v) => vis.visit_set_index_expr(v),
+            Expr::Compound(v) => vis.visit_compound_expr(v),
+            Expr::Conditional(v) => vis.visit_conditional_expr(v),
+            Expr::Coalesce(v) => vis.visit_coalesce_expr(v),
+            Expr::SafeGet(v) => vis.visit_safe_get_expr(v),
+            Expr::OptionalChain(v) => vis.visit_optional_chain_expr(v),
+            Expr::Match(v) => vis.visit_match_expr(v),
         }
     }
 }
@@ -68,15 +98,18 @@ impl<T> VisitorMutAcceptor<T> for Stmt {
             Stmt::Block(v) => vis.visit_block_stmt(v),
             Stmt::If(v) => vis.visit_if_stmt(v),
             Stmt::While(v) => vis.visit_while_stmt(v),
+            Stmt::ForIn(v) => vis.visit_for_in_stmt(v),
             Stmt::Break(v) => vis.visit_break_stmt(v),
             Stmt::Continue(v) => vis.visit_continue_stmt(v),
             Stmt::Function(v) => vis.visit_function_stmt(v),
             Stmt::Return(v) => vis.visit_return_stmt(v),
-            Stmt::Class(v) => {
-                let x = vis.visit_class_stmt(v);
-                x
-            }
-            Stmt::StackTrace => vis.visit_stack_trace_stmt(),
+            Stmt::Yield(v) => vis.visit_yield_stmt(v),
+            Stmt::Class(v) => vis.visit_class_stmt(v),
+            Stmt::Try(v) => vis.visit_try_stmt(v),
+            Stmt::Throw(v) => vis.visit_throw_stmt(v),
+            Stmt::Import(v) => vis.visit_import_stmt(v),
+            Stmt::StackTrace(v) => vis.visit_stack_trace_stmt(v),
+            Stmt::Match(v) => vis.visit_match_stmt(v),
         }
     }
 }
@@ -94,45 +127,62 @@ where
     }
 }
 
-pub trait Visitor<R> {
-    fn visit_binary_expr(&mut self, expr: &Binary) -> Result<R, LoxError>;
-    fn visit_call_expr(&mut self, expr: &Call) -> Result<R, LoxError>;
-    fn visit_grouping_expr(&mut self, expr: &Grouping) -> Result<R, LoxError>;
-    fn visit_unary_expr(&mut self, expr: &Unary) -> Result<R, LoxError>;
-    fn visit_literal_expr(&mut self, expr: &Literal) -> Result<R, LoxError>;
-    fn visit_logical_expr(&mut self, expr: &Logical) -> Result<R, LoxError>;
-    fn visit_get_expr(&mut self, expr: &Get) -> Result<R, LoxError>;
-    fn visit_set_expr(&mut self, expr: &Set) -> Result<R, LoxError>;
-    fn visit_lambda_expr(&mut self, expr: &Lambda) -> Result<R, LoxError>;
-    fn visit_this_expr(&mut self, expr: &This) -> Result<R, LoxError>;
-    fn visit_super_expr(&mut self, expr: &Super) -> Result<R, LoxError>;
-    fn visit_expression_stmt(&mut self, expr: &Expression) -> Result<R, LoxError>;
-    fn visit_print_stmt(&mut self, expr: &Print) -> Result<R, LoxError>;
-    fn visit_variable_stmt(&mut self, expr: &Variable) -> Result<R, LoxError>;
-    fn visit_var_stmt(&mut self, expr: &Var) -> Result<R, LoxError>;
-    fn visit_assign_stmt(&mut self, expr: &Assign) -> Result<R, LoxError>;
-    fn visit_block_stmt(&mut self, expr: &Block) -> Result<R, LoxError>;
-    fn visit_if_stmt(&mut self, expr: &If) -> Result<R, LoxError>;
-    fn visit_while_stmt(&mut self, expr: &While) -> Result<R, LoxError>;
-    fn visit_break_stmt(&mut self, expr: &Break) -> Result<R, LoxError>;
-    fn visit_continue_stmt(&mut self, expr: &Continue) -> Result<R, LoxError>;
-    fn visit_function_stmt(&mut self, expr: &Function) -> Result<R, LoxError>;
-    fn visit_return_stmt(&mut self, expr: &Return) -> Result<R, LoxError>;
-    fn visit_class_stmt(&mut self, expr: &Class) -> Result<R, LoxError>;
-    fn visit_stack_trace_stmt(&mut self) -> Result<R, LoxError>;
+// E is what unwinds a walk: errors, and for the interpreter also returns, breaks and continues.
+pub trait Visitor<R, E = LoxError> {
+    fn visit_binary_expr(&mut self, expr: &Binary) -> Result<R, E>;
+    fn visit_call_expr(&mut self, expr: &Call) -> Result<R, E>;
+    fn visit_grouping_expr(&mut self, expr: &Grouping) -> Result<R, E>;
+    fn visit_unary_expr(&mut self, expr: &Unary) -> Result<R, E>;
+    fn visit_literal_expr(&mut self, expr: &Literal, span: Span) -> Result<R, E>;
+    fn visit_logical_expr(&mut self, expr: &Logical) -> Result<R, E>;
+    fn visit_get_expr(&mut self, expr: &Get) -> Result<R, E>;
+    fn visit_set_expr(&mut self, expr: &Set) -> Result<R, E>;
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> Result<R, E>;
+    fn visit_this_expr(&mut self, expr: &This) -> Result<R, E>;
+    fn visit_super_expr(&mut self, expr: &Super) -> Result<R, E>;
+    fn visit_list_expr(&mut self, expr: &List) -> Result<R, E>;
+    fn visit_map_expr(&mut self, expr: &Map) -> Result<R, E>;
+    fn visit_index_expr(&mut self, expr: &Index) -> Result<R, E>;
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> Result<R, E>;
+    fn visit_compound_expr(&mut self, expr: &Compound) -> Result<R, E>;
+    fn visit_conditional_expr(&mut self, expr: &Conditional) -> Result<R, E>;
+    fn visit_coalesce_expr(&mut self, expr: &Coalesce) -> Result<R, E>;
+    fn visit_safe_get_expr(&mut self, expr: &SafeGet) -> Result<R, E>;
+    fn visit_optional_chain_expr(&mut self, expr: &OptionalChain) -> Result<R, E>;
+    fn visit_match_expr(&mut self, expr: &MatchExpr) -> Result<R, E>;
+    fn visit_expression_stmt(&mut self, expr: &Expression) -> Result<R, E>;
+    fn visit_print_stmt(&mut self, expr: &Print) -> Result<R, E>;
+    fn visit_variable_stmt(&mut self, expr: &Variable) -> Result<R, E>;
+    fn visit_var_stmt(&mut self, expr: &Var) -> Result<R, E>;
+    fn visit_assign_stmt(&mut self, expr: &Assign) -> Result<R, E>;
+    fn visit_block_stmt(&mut self, expr: &Block) -> Result<R, E>;
+    fn visit_if_stmt(&mut self, expr: &If) -> Result<R, E>;
+    fn visit_while_stmt(&mut self, expr: &While) -> Result<R, E>;
+    fn visit_for_in_stmt(&mut self, expr: &ForIn) -> Result<R, E>;
+    fn visit_break_stmt(&mut self, expr: &Break) -> Result<R, E>;
+    fn visit_continue_stmt(&mut self, expr: &Continue) -> Result<R, E>;
+    fn visit_function_stmt(&mut self, expr: &Function) -> Result<R, E>;
+    fn visit_return_stmt(&mut self, expr: &Return) -> Result<R, E>;
+    fn visit_yield_stmt(&mut self, expr: &Yield) -> Result<R, E>;
+    fn visit_class_stmt(&mut self, expr: &Class) -> Result<R, E>;
+    fn visit_try_stmt(&mut self, expr: &Try) -> Result<R, E>;
+    fn visit_throw_stmt(&mut self, expr: &Throw) -> Result<R, E>;
+    fn visit_import_stmt(&mut self, expr: &Import) -> Result<R, E>;
+    fn visit_stack_trace_stmt(&mut self, expr: &StackTrace) -> Result<R, E>;
+    fn visit_match_stmt(&mut self, expr: &Match) -> Result<R, E>;
 }
 
-pub trait VisAcceptor<T>: Sized {
-    fn accept(&self, vis: &mut dyn Visitor<T>) -> Result<T, LoxError>;
+pub trait VisAcceptor<T, E = LoxError>: Sized {
+    fn accept(&self, vis: &mut dyn Visitor<T, E>) -> Result<T, E>;
 }
 
-impl<T> VisAcceptor<T> for Expr {
-    fn accept(&self, vis: &mut dyn Visitor<T>) -> Result<T, LoxError> {
+impl<T, E> VisAcceptor<T, E> for Expr {
+    fn accept(&self, vis: &mut dyn Visitor<T, E>) -> Result<T, E> {
         match self {
             Expr::Binary(v) => vis.visit_binary_expr(v),
             Expr::Grouping(v) => vis.visit_grouping_expr(v),
             Expr::Unary(v) => vis.visit_unary_expr(v),
-            Expr::Literal(v) => vis.visit_literal_expr(v),
+            Expr::Literal(v, span) => vis.visit_literal_expr(v, *span),
             Expr::Variable(v) => vis.visit_variable_stmt(v),
             Expr::Assign(v) => vis.visit_assign_stmt(v),
             Expr::Logical(v) => vis.visit_logical_expr(v),
@@ -142,12 +192,22 @@ impl<T> VisAcceptor<T> for Expr {
             Expr::Set(v) => vis.visit_set_expr(v),
             Expr::This(v) => vis.visit_this_expr(v),
             Expr::Super(v) => vis.visit_super_expr(v),
+            Expr::List(v) => vis.visit_list_expr(v),
+            Expr::Map(v) => vis.visit_map_expr(v),
+            Expr::Index(v) => vis.visit_index_expr(v),
+            Expr::SetIndex(v) => vis.visit_set_index_expr(v),
+            Expr::Compound(v) => vis.visit_compound_expr(v),
+            Expr::Conditional(v) => vis.visit_conditional_expr(v),
+            Expr::Coalesce(v) => vis.visit_coalesce_expr(v),
+            Expr::SafeGet(v) => vis.visit_safe_get_expr(v),
+            Expr::OptionalChain(v) => vis.visit_optional_chain_expr(v),
+            Expr::Match(v) => vis.visit_match_expr(v),
         }
     }
 }
 
-impl<T> VisAcceptor<T> for Stmt {
-    fn accept(&self, vis: &mut dyn Visitor<T>) -> Result<T, LoxError> {
+impl<T, E> VisAcceptor<T, E> for Stmt {
+    fn accept(&self, vis: &mut dyn Visitor<T, E>) -> Result<T, E> {
         match self {
             Stmt::Expression(v) => vis.visit_expression_stmt(v),
             Stmt::Print(v) => vis.visit_print_stmt(v),
@@ -155,22 +215,28 @@ impl<T> VisAcceptor<T> for Stmt {
             Stmt::Block(v) => vis.visit_block_stmt(v),
             Stmt::If(v) => vis.visit_if_stmt(v),
             Stmt::While(v) => vis.visit_while_stmt(v),
+            Stmt::ForIn(v) => vis.visit_for_in_stmt(v),
             Stmt::Break(v) => vis.visit_break_stmt(v),
             Stmt::Continue(v) => vis.visit_continue_stmt(v),
             Stmt::Function(v) => vis.visit_function_stmt(v),
             Stmt::Return(v) => vis.visit_return_stmt(v),
+            Stmt::Yield(v) => vis.visit_yield_stmt(v),
             Stmt::Class(v) => vis.visit_class_stmt(v),
-            Stmt::StackTrace => vis.visit_stack_trace_stmt(),
+            Stmt::Try(v) => vis.visit_try_stmt(v),
+            Stmt::Throw(v) => vis.visit_throw_stmt(v),
+            Stmt::Import(v) => vis.visit_import_stmt(v),
+            Stmt::StackTrace(v) => vis.visit_stack_trace_stmt(v),
+            Stmt::Match(v) => vis.visit_match_stmt(v),
         }
     }
 }
 
-impl<T, X> VisAcceptor<T> for Vec<X>
+impl<T, E, X> VisAcceptor<T, E> for Vec<X>
 where
-    X: Sized + VisAcceptor<T>,
+    X: Sized + VisAcceptor<T, E>,
     T: Default,
 {
-    fn accept(&self, vis: &mut dyn Visitor<T>) -> Result<T, LoxError> {
+    fn accept(&self, vis: &mut dyn Visitor<T, E>) -> Result<T, E> {
         for stm in self.iter() {
             stm.accept(vis)?;
         }
@@ -23,6 +23,55 @@ test_succeed!(
     "c"
 );
 
+test_succeed!(
+    compound,
+    "../test-scripts/assignment/compound.lox",
+    3,
+    -2,
+    -6,
+    -1.5,
+    "foobar",
+    6,
+    6,
+    2
+);
+
+test_succeed!(
+    compound_field,
+    "../test-scripts/assignment/compound_field.lox",
+    3,
+    3,
+    5,
+    3,
+    15
+);
+
+test_succeed!(
+    compound_index,
+    "../test-scripts/assignment/compound_index.lox",
+    20,
+    20,
+    2,
+    21,
+    2,
+    2,
+    1
+);
+
+test_succeed!(
+    increment,
+    "../test-scripts/assignment/increment.lox",
+    1,
+    2,
+    3,
+    3,
+    1,
+    6,
+    0,
+    1,
+    2
+);
+
 test_succeed!(
     global,
     "../test-scripts/assignment/global.lox",
@@ -51,29 +100,41 @@ test_succeed!(
 test_fail!(
     grouping,
     "../test-scripts/assignment/grouping.lox",
-    LoxError::RuntimeError(String::from("="), 2, "".to_string())
+    LoxError::runtime_error("=", 2)
 );
 
 test_fail!(
     infix_operator,
     "../test-scripts/assignment/infix_operator.lox",
-    LoxError::RuntimeError(String::from("="), 3, "".to_string())
+    LoxError::runtime_error("=", 3)
 );
 
 test_fail!(
     prefix_operator,
     "../test-scripts/assignment/prefix_operator.lox",
-    LoxError::RuntimeError(String::from("="), 2, "".to_string())
+    LoxError::runtime_error("=", 2)
 );
 
 test_fail!(
     undefined,
     "../test-scripts/assignment/undefined.lox",
-    LoxError::RuntimeError(String::from("unknown"), 1, "".to_string())
+    LoxError::runtime_error("unknown", 1)
 );
 
 test_fail!(
     to_this,
     "../test-scripts/assignment/to_this.lox",
-    LoxError::RuntimeError(String::from("="), 3, "".to_string())
+    LoxError::runtime_error("=", 3)
+);
+
+test_fail!(
+    compound_invalid,
+    "../test-scripts/assignment/compound_invalid.lox",
+    LoxError::runtime_error("+=", 2)
+);
+
+test_fail!(
+    increment_invalid,
+    "../test-scripts/assignment/increment_invalid.lox",
+    LoxError::runtime_error("++", 2)
 );
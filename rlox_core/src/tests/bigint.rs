@@ -0,0 +1,85 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_succeed!(
+    literal,
+    ".././test-scripts/bigint/literal.lox",
+    "9223372036854775808",
+    "4722366482869645213695",
+    "-9223372036854775809"
+);
+
+test_succeed!(
+    overflow,
+    ".././test-scripts/bigint/overflow.lox",
+    "9223372036854775808",
+    "-9223372036854775809",
+    "18446744073709551616",
+    "9223372036854775808",
+    "18446744073709551616"
+);
+
+test_succeed!(
+    factorial,
+    ".././test-scripts/bigint/factorial.lox",
+    2432902008176640000.0,
+    "15511210043330985984000000",
+    "265252859812191058636308480000000",
+    870
+);
+
+test_succeed!(
+    demotion,
+    ".././test-scripts/bigint/demotion.lox",
+    true,
+    "4611686018427387904",
+    true,
+    8,
+    2,
+    "-922337203685477581",
+    "4611686018427387904",
+    0
+);
+
+test_succeed!(
+    comparison,
+    ".././test-scripts/bigint/comparison.lox",
+    true,
+    true,
+    true,
+    true,
+    true,
+    4611686018427387904.0,
+    "big",
+    "big"
+);
+
+test_succeed!(
+    conversions,
+    ".././test-scripts/bigint/conversions.lox",
+    "123456789012345678901234567890",
+    "-123456789012345678901234567889",
+    100000000000000000000.0,
+    "100000000000000000000",
+    true
+);
+
+test_fail!(
+    bitwise,
+    ".././test-scripts/bigint/bitwise.lox",
+    LoxError::runtime_error("&", 2)
+);
@@ -18,25 +18,25 @@ use std::sync::atomic::{AtomicBool, Ordering};
 test_fail!(
     class,
     ".././test-scripts/break/class.lox",
-    LoxError::ParserError(String::from("break"), 2, String::from(""))
+    LoxError::parser_error("break", 2)
 );
 
 test_fail!(
     function,
     ".././test-scripts/break/function.lox",
-    LoxError::Break(2)
+    LoxError::semantic_error("break", 2)
 );
 
 test_fail!(
     global_scope,
     ".././test-scripts/break/global_scope.lox",
-    LoxError::Break(1)
+    LoxError::semantic_error("break", 1)
 );
 
 test_fail!(
     local_scope,
     ".././test-scripts/break/local_scope.lox",
-    LoxError::Break(2)
+    LoxError::semantic_error("break", 2)
 );
 
 test_succeed!(loops, ".././test-scripts/break/loop.lox", 1, 2);
@@ -26,7 +26,7 @@ test_succeed!(
 test_fail!(
     inherit_self,
     "../test-scripts/class/inherit_self.lox",
-    LoxError::SemanticError(String::from("Foo"), 1, String::from(""))
+    LoxError::semantic_error("Foo", 1)
 );
 
 test_succeed!(
@@ -40,13 +40,15 @@ test_succeed!(
 test_succeed!(
     local_inherit_other,
     "../test-scripts/class/local_inherit_other.lox",
-    LoxClass::new(String::from("B"), Rc::new(HashMap::new()), None)
+    "[line 5] in f()\n[line 9] in script",
+    LoxClass::new(String::from("B"), Rc::new(HashMap::new()), None),
+    "[line 10] in script"
 );
 
 test_fail!(
     local_inherit_self,
     "../test-scripts/class/local_inherit_self.lox",
-    LoxError::SemanticError(String::from("Foo"), 2, String::from(""))
+    LoxError::semantic_error("Foo", 2)
 );
 
 test_succeed!(
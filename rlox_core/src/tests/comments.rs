@@ -32,3 +32,42 @@ test_succeed!(
 );
 
 test_succeed!(unicode, ".././test-scripts/comments/unicode.lox", "ok");
+
+test_succeed!(
+    block,
+    ".././test-scripts/comments/block.lox",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e"
+);
+
+test_fail!(
+    block_line,
+    ".././test-scripts/comments/block_line.lox",
+    LoxError::parser_error(";", 3)
+);
+
+test_fail!(
+    unterminated_block,
+    ".././test-scripts/comments/unterminated_block.lox",
+    LoxError::scanner_error("/*", 2)
+);
+
+#[test]
+fn doc_comments() {
+    let script = read_to_string(".././test-scripts/comments/doc.lox").unwrap();
+    let tokens = Lexer::new().parse(&script).unwrap();
+    let docs: Vec<(&str, &str)> = tokens
+        .iter()
+        .filter_map(|token| Some((token.lexeme.as_str(), token.doc.as_deref()?)))
+        .collect();
+    assert_eq!(
+        docs,
+        vec![
+            ("fun", "Adds two numbers.\nReturns their sum."),
+            ("class", "A point.")
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_fail!(
+    class,
+    ".././test-scripts/continue/class.lox",
+    LoxError::parser_error("continue", 2)
+);
+
+test_fail!(
+    function,
+    ".././test-scripts/continue/function.lox",
+    LoxError::semantic_error("continue", 2)
+);
+
+test_fail!(
+    global_scope,
+    ".././test-scripts/continue/global_scope.lox",
+    LoxError::semantic_error("continue", 1)
+);
+
+test_fail!(
+    local_scope,
+    ".././test-scripts/continue/local_scope.lox",
+    LoxError::semantic_error("continue", 2)
+);
+
+test_succeed!(loops, ".././test-scripts/continue/loop.lox", 5, 5);
+
+test_succeed!(
+    last_iteration,
+    ".././test-scripts/continue/last_iteration.lox",
+    1,
+    2
+);
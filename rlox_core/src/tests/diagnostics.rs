@@ -0,0 +1,286 @@
+#![cfg(test)]
+
+use super::*;
+use crate::diagnostics::{Diagnostic, Format, Note};
+use crate::error::{Error, ErrorCode, LoxError};
+use crate::frontend::definitions::span::Span;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::runner::Runner;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::rc::Rc;
+
+fn diagnose(path: &str) -> Diagnostic {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    })));
+    let err = run_script(path, &mut interpreter).unwrap_err();
+    Diagnostic::new(&err, &read_to_string(path).unwrap()).file("script.lox")
+}
+
+// The diagnostic of the first error of running source as a REPL line.
+fn diagnose_line(source: &str) -> Diagnostic {
+    let err = Runner::build(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    })))
+    .run_line(source)
+    .unwrap_err()
+    .remove(0);
+    Diagnostic::from_error(&err, source)
+}
+
+fn span(start: usize, end: usize, line: u32, column: u32, end_column: u32) -> Option<Span> {
+    Some(Span {
+        file_id: 0,
+        start,
+        end,
+        line,
+        column,
+        end_line: line,
+        end_column,
+    })
+}
+
+// A span in the first REPL input, which is the first file of the program.
+fn input_span(start: usize, end: usize, line: u32, column: u32, end_column: u32) -> Option<Span> {
+    span(start, end, line, column, end_column).map(|span| Span { file_id: 1, ..span })
+}
+
+#[test]
+fn runtime_error() {
+    let diagnostic = diagnose(".././test-scripts/diagnostics/operands.lox");
+    assert_eq!(diagnostic.code, "E0301");
+    assert_eq!(diagnostic.kind, "RuntimeError");
+    assert_eq!(diagnostic.span, span(25, 26, 2, 12, 13));
+    assert_eq!(
+        diagnostic.render(Format::Plain),
+        "error[E0301]: Operands not Num\n \
+         --> script.lox:2:12\n  \
+         |\n\
+         2 |   return n / \"2\";\n  \
+         |            ^\n  \
+         |\n  \
+         = help: convert the operands first, as with int(), float() or \"${x}\"\n  \
+         = note: [line 2] in half()\n  \
+         = note: [line 5] in script\n"
+    );
+}
+
+#[test]
+fn redeclared() {
+    let diagnostic = diagnose(".././test-scripts/diagnostics/redeclared.lox");
+    assert_eq!(diagnostic.code, "E0201");
+    assert_eq!(
+        diagnostic.notes,
+        vec![Note {
+            message: "`count` first declared here".to_string(),
+            span: span(8, 13, 2, 7, 12),
+        }]
+    );
+    assert_eq!(
+        diagnostic.render(Format::Plain),
+        "error[E0201]: Already exists\n \
+         --> script.lox:3:7\n  \
+         |\n\
+         2 |   var count = 1;\n  \
+         |       ----- `count` first declared here\n\
+         3 |   var count = 2;\n  \
+         |       ^^^^^\n  \
+         |\n  \
+         = help: pick another name, or assign to the existing variable\n"
+    );
+}
+
+#[test]
+fn parameter_redeclared() {
+    let diagnostic = diagnose_line("fun f(a, b) {\n  var b;\n}");
+    assert_eq!(diagnostic.span, input_span(20, 21, 2, 7, 8));
+    assert_eq!(diagnostic.notes[0].span, input_span(9, 10, 1, 10, 11));
+}
+
+#[test]
+fn block_redeclared() {
+    let diagnostic = diagnose_line("{ var c = 1; var c = 2; }");
+    assert_eq!(diagnostic.span, input_span(17, 18, 1, 18, 19));
+    assert_eq!(diagnostic.notes[0].message, "`c` first declared here");
+    assert_eq!(diagnostic.notes[0].span, input_span(6, 7, 1, 7, 8));
+}
+
+#[test]
+fn inner_scope_redeclared() {
+    let diagnostic = diagnose_line("fun g() {\n  var a = 1;\n  { var a = 2; }\n  var a = 3;\n}");
+    assert_eq!(diagnostic.span.map(|span| span.line), Some(4));
+    assert_eq!(diagnostic.notes[0].span, input_span(16, 17, 2, 7, 8));
+}
+
+#[test]
+fn whole_names() {
+    let diagnostic = diagnose_line("var ab = a;");
+    assert_eq!(diagnostic.code, "E0302");
+    assert_eq!(diagnostic.span, input_span(9, 10, 1, 10, 11));
+}
+
+#[test]
+fn use_in_own_initializer() {
+    let diagnostic = diagnose_line("var q = q;");
+    assert_eq!(diagnostic.span, input_span(8, 9, 1, 9, 10));
+}
+
+#[test]
+fn operator_in_string() {
+    let diagnostic = diagnose_line("print \"a+b\" + \"c\" + nil;");
+    assert_eq!(diagnostic.span, input_span(18, 19, 1, 19, 20));
+}
+
+#[test]
+fn end_of_file() {
+    let diagnostic = diagnose_line("var x = 1 +\n\n");
+    assert_eq!(diagnostic.span, input_span(11, 11, 1, 12, 12));
+}
+
+#[test]
+fn escape() {
+    let diagnostic = diagnose_line("print \"a\\qb\";");
+    assert_eq!(diagnostic.code, "E0001");
+    assert_eq!(diagnostic.span, input_span(8, 10, 1, 9, 11));
+}
+
+#[test]
+fn unexpected_character() {
+    let diagnostic = diagnose_line("var a = 1 @ 2;");
+    assert_eq!(diagnostic.code, "E0003");
+    assert_eq!(diagnostic.message, "Unexpected character.");
+    assert_eq!(diagnostic.span, input_span(10, 11, 1, 11, 12));
+}
+
+#[test]
+fn code_from_where_raised() {
+    let diagnostic = diagnose(".././test-scripts/match/not_a_class.lox");
+    assert_eq!(diagnostic.code, "E0320");
+    assert_eq!(diagnostic.help, None);
+}
+
+#[test]
+fn without_span() {
+    let err = LoxError::runtime_error("x", 1);
+    let diagnostic = Diagnostic::new(&err, "print 1;");
+    assert_eq!(diagnostic.span, None);
+    assert_eq!(diagnostic.code, "E0300");
+    assert_eq!(diagnostic.message, "x");
+    assert_eq!(
+        diagnostic.render(Format::Plain),
+        "error[E0300]: x\n --> <input>:1\n"
+    );
+}
+
+#[test]
+fn colored() {
+    let rendered = diagnose_line("break;").render(Format::Colored);
+    assert!(rendered.starts_with("\x1b[1;31merror[E0209]\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^^^^^\x1b[0m"));
+}
+
+#[test]
+fn json() {
+    let diagnostic = diagnose_line("print \"\\\"\" + x;").file("a.lox");
+    assert_eq!(
+        diagnostic.render(Format::Json),
+        "{\"kind\":\"RuntimeError\",\"code\":\"E0302\",\"message\":\"Undefined get\",\
+         \"file\":\"a.lox\",\"line\":1,\"span\":{\"start\":13,\"end\":14,\"line\":1,\
+         \"column\":14,\"end_line\":1,\"end_column\":15},\
+         \"help\":\"check the name for typos\",\
+         \"notes\":[{\"message\":\"[line 1] in script\",\"span\":null}]}\n"
+    );
+}
+
+#[test]
+fn format_args() {
+    let mut cli_args = vec![
+        "rlox".to_string(),
+        "--error-format".to_string(),
+        "json".to_string(),
+        "a.lox".to_string(),
+    ];
+    assert_eq!(Format::from_args(&mut cli_args), Some(Format::Json));
+    assert_eq!(cli_args, vec!["rlox".to_string(), "a.lox".to_string()]);
+    let mut cli_args = vec!["rlox".to_string(), "--error-format".to_string()];
+    assert_eq!(Format::from_args(&mut cli_args), None);
+}
+
+#[test]
+fn malformed_number() {
+    let diagnostic = diagnose_line("print 1_;");
+    assert_eq!(diagnostic.code, "E0005");
+    assert_eq!(diagnostic.span, input_span(6, 8, 1, 7, 9));
+}
+
+#[test]
+fn uncaught() {
+    let diagnostic = diagnose(".././test-scripts/diagnostics/uncaught.lox");
+    assert_eq!(diagnostic.code, "E0313");
+    assert_eq!(diagnostic.message, "Uncaught Oops: out of cheese");
+
+    let diagnostic = diagnose_line("throw \"boom\";");
+    assert_eq!(diagnostic.code, "E0313");
+    assert_eq!(diagnostic.message, "Uncaught exception: boom");
+}
+
+#[test]
+fn module() {
+    let path = ".././test-scripts/diagnostics/module.lox";
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    })));
+    let err = Error::from(run_script(path, &mut interpreter).unwrap_err());
+    let source = read_to_string(path).unwrap();
+    let diagnostic =
+        Diagnostic::from_program(&err, &interpreter.modules(), Some("script.lox"), &source);
+    assert!(diagnostic.file.unwrap().ends_with("lib/broken.lox"));
+    assert_eq!(
+        diagnostic.span.map(|span| (span.line, span.column)),
+        Some((2, 13))
+    );
+    let notes: Vec<_> = diagnostic.notes.iter().map(|note| &note.message).collect();
+    assert_eq!(
+        notes,
+        vec!["[line 2] in module lib/broken.lox", "[line 2] in script"]
+    );
+}
+
+#[test]
+fn earlier_input() {
+    let mut runner = Runner::build(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    })));
+    runner
+        .run_line("fun f() {\n\n\n  return 1 + nil; }")
+        .unwrap();
+    let err = runner.run_line("f();").unwrap_err().remove(0);
+    let rendered = runner.diagnostic(&err, None, "f();").render(Format::Plain);
+    assert!(rendered.contains("4 |   return 1 + nil; }\n  |            ^\n"));
+}
+
+#[test]
+fn span_outside_source() {
+    let diagnostic = Diagnostic::new(
+        &LoxError::RuntimeError {
+            lexeme: "+".to_string(),
+            line_no: 5,
+            message: "Operands not Num".to_string(),
+            code: ErrorCode::OperandType,
+            span: span(40, 41, 5, 3, 4).map(Box::new),
+            backtrace: Box::new([]),
+        },
+        "f();",
+    );
+    assert_eq!(
+        diagnostic.render(Format::Plain),
+        "error[E0301]: Operands not Num\n \
+         --> <input>:5:3\n  \
+         |\n  \
+         |\n  \
+         = help: convert the operands first, as with int(), float() or \"${x}\"\n"
+    );
+}
@@ -0,0 +1,126 @@
+#![cfg(test)]
+
+use crate::error::{format_backtrace, Error, ErrorCode, ErrorKind, LoxError, TraceFrame};
+use crate::frontend::definitions::span::Span;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::runner::Runner;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn run_line(line: &str) -> Vec<Error> {
+    let mut runner = Runner::build(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    })));
+    runner.run_line(line).unwrap_err()
+}
+
+#[test]
+fn from_runtime_error() {
+    let span = Span {
+        file_id: 0,
+        start: 20,
+        end: 21,
+        line: 3,
+        column: 10,
+        end_line: 3,
+        end_column: 11,
+    };
+    let err = Error::from(LoxError::RuntimeError {
+        lexeme: "x".to_string(),
+        line_no: 3,
+        message: "Undefined get".to_string(),
+        code: ErrorCode::Undefined,
+        span: Some(Box::new(span)),
+        backtrace: Box::new([
+            TraceFrame::new("f".to_string(), 3),
+            TraceFrame::new("script".to_string(), 5),
+        ]),
+    });
+    assert_eq!(err.kind, ErrorKind::Runtime);
+    assert_eq!(err.lexeme, "x");
+    assert_eq!(err.line_no, 3);
+    assert_eq!(err.span, Some(span));
+    assert_eq!(err.message, "Undefined get");
+    assert_eq!(
+        err.backtrace,
+        vec![
+            TraceFrame::new("f".to_string(), 3),
+            TraceFrame::new("script".to_string(), 5)
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        "[RuntimeError:L3:x] Undefined get\n[line 3] in f()\n[line 5] in script"
+    );
+}
+
+#[test]
+fn from_throw() {
+    let err = Error::from(LoxError::Throw(Object::Num(1.0), 2, None));
+    assert_eq!(err.kind, ErrorKind::Runtime);
+    assert_eq!(err.message, "Uncaught exception: 1");
+    assert!(err.backtrace.is_empty());
+}
+
+#[test]
+fn token_span() {
+    let errors = run_line("{\n  var a = 1; var a = 2;\n}");
+    let span = errors[0].span.unwrap();
+    assert_eq!((span.line, span.column, span.end_column), (2, 18, 19));
+}
+
+#[test]
+fn backtrace_format() {
+    let frames = vec![
+        TraceFrame::new("inner".to_string(), 2),
+        TraceFrame::new("Lambda".to_string(), 4),
+        TraceFrame::new("module lib/util.lox".to_string(), 7),
+        TraceFrame::new("script".to_string(), 9),
+    ];
+    assert_eq!(
+        format_backtrace(&frames),
+        "[line 2] in inner()\n[line 4] in Lambda()\n[line 7] in module lib/util.lox\n\
+         [line 9] in script"
+    );
+}
+
+#[test]
+fn run_line_errors() {
+    let errors = run_line("var a = ;\nprint (1 + ;");
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|err| err.kind == ErrorKind::Parser));
+    assert_eq!(errors[1].span.map(|span| span.line), Some(2));
+
+    let errors = run_line("fun f() { return nope; }\nf();");
+    assert_eq!(errors.len(), 1);
+    let err = &errors[0];
+    assert_eq!(err.kind, ErrorKind::Runtime);
+    assert_eq!(err.message, "Undefined get");
+    assert_eq!(err.span.map(|span| span.column), Some(18));
+    assert_eq!(
+        err.backtrace,
+        vec![
+            TraceFrame::new("f".to_string(), 1),
+            TraceFrame::new("script".to_string(), 2)
+        ]
+    );
+}
+
+#[test]
+fn is_std_error() {
+    let errors = run_line("print 1 / 0;");
+    let err: Box<dyn std::error::Error> = Box::new(errors[0].clone());
+    assert_eq!(
+        err.to_string(),
+        "[RuntimeError:L1:/] Division by zero\n[line 1] in script"
+    );
+    let err: Box<dyn std::error::Error> = Box::new(LoxError::ParserError {
+        lexeme: ";".to_string(),
+        line_no: 1,
+        message: "Expect Expression".to_string(),
+        code: ErrorCode::ExpectExpression,
+        span: None,
+    });
+    assert_eq!(err.to_string(), "[ParserError:L1:;] Expect Expression");
+}
@@ -0,0 +1,115 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_succeed!(
+    throw_catch,
+    ".././test-scripts/exception/throw_catch.lox",
+    "before",
+    "oops"
+);
+
+test_succeed!(
+    finally,
+    ".././test-scripts/exception/finally.lox",
+    "body",
+    "finally",
+    "caught",
+    "finally"
+);
+
+test_succeed!(
+    error_class,
+    ".././test-scripts/exception/error_class.lox",
+    "custom"
+);
+
+test_succeed!(
+    shadowed_class,
+    ".././test-scripts/exception/shadowed_class.lox",
+    "prelude's TypeError"
+);
+
+test_succeed!(
+    builtin_error,
+    ".././test-scripts/exception/builtin_error.lox",
+    LoxInstance::new(LoxClass::new(
+        String::from("TypeError"),
+        Rc::new(HashMap::new()),
+        None
+    )),
+    "Operands not Num or String"
+);
+
+test_succeed!(
+    index_error,
+    ".././test-scripts/exception/index_error.lox",
+    LoxInstance::new(LoxClass::new(
+        String::from("IndexError"),
+        Rc::new(HashMap::new()),
+        None
+    ))
+);
+
+test_succeed!(
+    across_calls,
+    ".././test-scripts/exception/across_calls.lox",
+    "deep",
+    "after"
+);
+
+test_succeed!(
+    nested,
+    ".././test-scripts/exception/nested.lox",
+    "inner finally",
+    "inner",
+    "second"
+);
+
+test_succeed!(
+    closure,
+    ".././test-scripts/exception/closure.lox",
+    2,
+    "captured"
+);
+
+test_succeed!(
+    finally_return,
+    ".././test-scripts/exception/finally_return.lox",
+    "cleanup",
+    "value",
+    1,
+    2
+);
+
+test_fail!(
+    uncaught,
+    ".././test-scripts/exception/uncaught.lox",
+    LoxError::runtime_error("Error", 2)
+);
+
+test_fail!(
+    rethrow,
+    ".././test-scripts/exception/rethrow.lox",
+    LoxError::runtime_error("+", 8)
+);
+
+test_fail!(
+    missing_catch,
+    ".././test-scripts/exception/missing_catch.lox",
+    LoxError::parser_error("print", 4)
+);
@@ -26,7 +26,7 @@ test_succeed!(
 test_fail!(
     call_nonfunction_field,
     ".././test-scripts/field/call_nonfunction_field.lox",
-    LoxError::RuntimeError(String::from("not fn"), 6, String::from(""))
+    LoxError::runtime_error("not fn", 6)
 );
 
 test_succeed!(
@@ -41,37 +41,37 @@ test_succeed!(
 test_fail!(
     get_on_bool,
     ".././test-scripts/field/get_on_bool.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     get_on_class,
     ".././test-scripts/field/get_on_class.lox",
-    LoxError::RuntimeError(String::from("bar"), 2, String::from(""))
+    LoxError::runtime_error("bar", 2)
 );
 
 test_fail!(
     get_on_function,
     ".././test-scripts/field/get_on_function.lox",
-    LoxError::RuntimeError(String::from("bar"), 3, String::from(""))
+    LoxError::runtime_error("bar", 3)
 );
 
 test_fail!(
     get_on_nil,
     ".././test-scripts/field/get_on_nil.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     get_on_num,
     ".././test-scripts/field/get_on_num.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     get_on_string,
     ".././test-scripts/field/get_on_string.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_succeed!(
@@ -184,47 +184,47 @@ test_succeed!(
 test_fail!(
     set_evaluation_order,
     ".././test-scripts/field/set_evaluation_order.lox",
-    LoxError::RuntimeError(String::from("undefined1"), 1, String::from(""))
+    LoxError::runtime_error("undefined1", 1)
 );
 
 test_fail!(
     set_on_bool,
     ".././test-scripts/field/set_on_bool.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     set_on_class,
     ".././test-scripts/field/set_on_class.lox",
-    LoxError::RuntimeError(String::from("bar"), 2, String::from(""))
+    LoxError::runtime_error("bar", 2)
 );
 
 test_fail!(
     set_on_function,
     ".././test-scripts/field/set_on_function.lox",
-    LoxError::RuntimeError(String::from("bar"), 3, String::from(""))
+    LoxError::runtime_error("bar", 3)
 );
 
 test_fail!(
     set_on_nil,
     ".././test-scripts/field/set_on_nil.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     set_on_num,
     ".././test-scripts/field/set_on_num.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     set_on_string,
     ".././test-scripts/field/set_on_string.lox",
-    LoxError::RuntimeError(String::from("foo"), 1, String::from(""))
+    LoxError::runtime_error("foo", 1)
 );
 
 test_fail!(
     undefined,
     ".././test-scripts/field/undefined.lox",
-    LoxError::RuntimeError(String::from("bar"), 4, String::from(""))
+    LoxError::runtime_error("bar", 4)
 );
@@ -0,0 +1,98 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_fail!(
+    already_running,
+    "../test-scripts/generator/already_running.lox",
+    LoxError::runtime_error("selfish", 3)
+);
+
+test_fail!(
+    at_top_level,
+    "../test-scripts/generator/at_top_level.lox",
+    LoxError::semantic_error("yield", 1)
+);
+
+test_succeed!(
+    basic,
+    "../test-scripts/generator/basic.lox",
+    true,
+    true,
+    0,
+    1,
+    false,
+    Object::Nil,
+    0,
+    Object::Nil
+);
+
+test_succeed!(closure, "../test-scripts/generator/closure.lox", 0, 10);
+
+test_succeed!(
+    control_flow,
+    "../test-scripts/generator/control_flow.lox",
+    1,
+    20,
+    "try",
+    "thrown",
+    "finally",
+    3,
+    4
+);
+
+test_succeed!(
+    exception,
+    "../test-scripts/generator/exception.lox",
+    1,
+    "boom",
+    false
+);
+
+test_succeed!(
+    for_in,
+    "../test-scripts/generator/for_in.lox",
+    0,
+    2,
+    4,
+    1,
+    2,
+    4
+);
+
+test_fail!(
+    in_initializer,
+    "../test-scripts/generator/in_initializer.lox",
+    LoxError::semantic_error("yield", 3)
+);
+
+test_succeed!(
+    in_lambda,
+    "../test-scripts/generator/in_lambda.lox",
+    "a",
+    "b",
+    1
+);
+
+test_succeed!(method, "../test-scripts/generator/method.lox", 3, 4);
+
+test_succeed!(
+    return_stmt,
+    "../test-scripts/generator/return.lox",
+    "first",
+    false,
+    false
+);
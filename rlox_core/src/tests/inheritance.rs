@@ -24,19 +24,19 @@ test_succeed!(
 test_fail!(
     inherit_from_function,
     ".././test-scripts/inheritance/inherit_from_function.lox",
-    LoxError::RuntimeError(String::from("Subclass"), 3, String::from(""))
+    LoxError::runtime_error("Subclass", 3)
 );
 
 test_fail!(
     inherit_from_nil,
     ".././test-scripts/inheritance/inherit_from_nil.lox",
-    LoxError::RuntimeError(String::from("Foo"), 2, String::from(""))
+    LoxError::runtime_error("Foo", 2)
 );
 
 test_fail!(
     inherit_from_number,
     ".././test-scripts/inheritance/inherit_from_number.lox",
-    LoxError::RuntimeError(String::from("Foo"), 2, String::from(""))
+    LoxError::runtime_error("Foo", 2)
 );
 
 test_succeed!(
@@ -50,7 +50,7 @@ test_succeed!(
 test_fail!(
     parenthesized_superclass,
     ".././test-scripts/inheritance/parenthesized_superclass.lox",
-    LoxError::ParserError(String::from("("), 4, String::from(""))
+    LoxError::parser_error("(", 4)
 );
 
 test_succeed!(
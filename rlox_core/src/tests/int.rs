@@ -0,0 +1,129 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_succeed!(
+    arithmetic,
+    ".././test-scripts/int/arithmetic.lox",
+    9,
+    -3,
+    42,
+    3.5,
+    3,
+    -4,
+    1,
+    2,
+    -2,
+    "9007199254740993"
+);
+
+test_succeed!(
+    bitwise,
+    ".././test-scripts/int/bitwise.lox",
+    48,
+    255,
+    240,
+    -6,
+    1024,
+    -4,
+    true,
+    8
+);
+
+test_succeed!(
+    promotion,
+    ".././test-scripts/int/promotion.lox",
+    1.5,
+    1.5,
+    3,
+    1.5,
+    true,
+    true,
+    "one",
+    20
+);
+
+test_succeed!(
+    exact_compare,
+    ".././test-scripts/int/exact_compare.lox",
+    false,
+    true,
+    true,
+    true,
+    true,
+    true,
+    "float",
+    "int"
+);
+
+test_succeed!(
+    conversions,
+    ".././test-scripts/int/conversions.lox",
+    3,
+    -3,
+    42,
+    2,
+    3,
+    1000,
+    4
+);
+
+test_fail!(
+    int_divide_by_zero,
+    ".././test-scripts/int/int_divide_by_zero.lox",
+    LoxError::runtime_error("~/", 2)
+);
+
+test_fail!(
+    bitwise_float,
+    ".././test-scripts/int/bitwise_float.lox",
+    LoxError::runtime_error("&", 2)
+);
+
+test_fail!(
+    shift_out_of_range,
+    ".././test-scripts/int/shift_out_of_range.lox",
+    LoxError::runtime_error("<<", 2)
+);
+
+test_fail!(
+    convert_string,
+    ".././test-scripts/int/convert_string.lox",
+    LoxError::runtime_error("abc", 2)
+);
+
+// ints and floats print alike, so the variants are checked
+#[test]
+fn result_types() {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    })));
+    run_script(".././test-scripts/int/types.lox", &mut interpreter).unwrap();
+    let printed = print_cache.borrow();
+    assert!(matches!(
+        printed.as_slice(),
+        [
+            Object::Int(3),
+            Object::Num(_),
+            Object::Num(_),
+            Object::Int(2),
+            Object::Num(_),
+            Object::Int(3),
+            Object::Num(_),
+        ]
+    ));
+}
@@ -0,0 +1,64 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_succeed!(
+    literal,
+    ".././test-scripts/list/literal.lox",
+    1,
+    "two",
+    true,
+    Object::Nil,
+    6,
+    9,
+    true
+);
+
+test_succeed!(
+    index_assign,
+    ".././test-scripts/list/index_assign.lox",
+    "a",
+    10,
+    10,
+    "b",
+    4
+);
+
+test_succeed!(local, ".././test-scripts/list/local.lox", 2, 4);
+
+test_fail!(
+    out_of_bounds,
+    ".././test-scripts/list/out_of_bounds.lox",
+    LoxError::runtime_error("3", 2)
+);
+
+test_fail!(
+    non_integer_index,
+    ".././test-scripts/list/non_integer_index.lox",
+    LoxError::runtime_error("1.5", 2)
+);
+
+test_fail!(
+    index_non_list,
+    ".././test-scripts/list/index_non_list.lox",
+    LoxError::runtime_error("[", 2)
+);
+
+test_fail!(
+    missing_bracket,
+    ".././test-scripts/list/missing_bracket.lox",
+    LoxError::parser_error(";", 1)
+);
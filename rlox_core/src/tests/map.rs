@@ -0,0 +1,63 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_succeed!(
+    instance_key,
+    ".././test-scripts/map/instance_key.lox",
+    "a",
+    "b",
+    2
+);
+
+test_fail!(
+    invalid_key,
+    ".././test-scripts/map/invalid_key.lox",
+    LoxError::runtime_error("Nil", 2)
+);
+
+test_succeed!(
+    keys,
+    ".././test-scripts/map/keys.lox",
+    4,
+    "x",
+    "y",
+    "z",
+    "w",
+    19
+);
+
+test_succeed!(
+    literal,
+    ".././test-scripts/map/literal.lox",
+    1,
+    "two",
+    "yes",
+    Object::Nil,
+    3,
+    0,
+    "nested"
+);
+
+test_succeed!(local, ".././test-scripts/map/local.lox", 3, 1, 5);
+
+test_fail!(
+    missing_colon,
+    ".././test-scripts/map/missing_colon.lox",
+    LoxError::parser_error("1", 1)
+);
+
+test_succeed!(set, ".././test-scripts/map/set.lox", 2, 11, 2, 3);
@@ -0,0 +1,108 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+test_fail!(
+    binding_not_last,
+    ".././test-scripts/match/binding_not_last.lox",
+    LoxError::semantic_error("x", 2)
+);
+
+test_succeed!(
+    block_arm,
+    ".././test-scripts/match/block_arm.lox",
+    "two",
+    true
+);
+
+test_succeed!(
+    class,
+    ".././test-scripts/match/class.lox",
+    "circle r",
+    "square s",
+    "some shape",
+    "not a shape"
+);
+
+test_succeed!(
+    expression,
+    ".././test-scripts/match/expression.lox",
+    "one",
+    "two",
+    "many",
+    Object::Nil,
+    "inner outer",
+    "inner"
+);
+
+test_succeed!(
+    guard,
+    ".././test-scripts/match/guard.lox",
+    "large",
+    "medium",
+    "empty",
+    "small"
+);
+
+test_succeed!(
+    guard_closure,
+    ".././test-scripts/match/guard_closure.lox",
+    "captured",
+    "captured"
+);
+
+test_succeed!(
+    list,
+    ".././test-scripts/match/list.lox",
+    "empty",
+    "one a",
+    "point x y",
+    "nested n p q",
+    "pair",
+    "other"
+);
+
+test_succeed!(
+    literal,
+    ".././test-scripts/match/literal.lox",
+    "zero",
+    "minus one",
+    "greeting",
+    "yes",
+    "nothing",
+    "other",
+    "other"
+);
+
+test_fail!(
+    missing_arrow,
+    ".././test-scripts/match/missing_arrow.lox",
+    LoxError::parser_error("print", 2)
+);
+
+test_succeed!(no_match, ".././test-scripts/match/no_match.lox", "done");
+
+test_fail!(
+    not_a_class,
+    ".././test-scripts/match/not_a_class.lox",
+    LoxError::runtime_error("notClass", 3)
+);
+
+test_fail!(
+    wildcard_not_last,
+    ".././test-scripts/match/wildcard_not_last.lox",
+    LoxError::semantic_error("_", 2)
+);
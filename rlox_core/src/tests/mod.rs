@@ -1,6 +1,6 @@
 #![allow(unused_imports)]
 
-use crate::error::LoxError;
+use crate::error::{Error, LoxError};
 use crate::frontend::lexer::*;
 use crate::frontend::parser::Parser;
 use crate::frontend::resolver::Resolver;
@@ -20,8 +20,11 @@ use std::sync::atomic::{AtomicBool, Ordering};
 fn run_script(path: &str, interpreter: &mut Interpreter) -> Result<(), LoxError> {
     let path = Path::new(path);
     let script = read_to_string(path).unwrap();
+    interpreter.set_path(path);
     let mut ast = Parser::new(Lexer::new().parse(&script)?).parse()?;
-    Resolver::new().resolve(&mut ast)?;
+    Resolver::new()
+        .resolve_all(&mut ast)
+        .map_err(|mut errors| errors.remove(0))?;
     interpreter.interpret(&mut ast)?;
     Ok(())
 }
@@ -97,22 +100,12 @@ macro_rules! test_fail {
                 print_cache: Rc::clone(&print_cache),
             })));
             if let Err(err) = run_script($file_path, &mut interpreter) {
-                use LoxError::*;
-                match (err.clone(), $err_val) {
-                    (ScannerError(lex1, line1, _), ScannerError(lex2, line2, _))
-                        if lex1 == lex2 && line1 == line2 => {}
-                    (ParserError(lex1, line1, _), ParserError(lex2, line2, _))
-                        if lex1 == lex2 && line1 == line2 => {}
-                    (RuntimeError(lex1, line1, _), RuntimeError(lex2, line2, _))
-                        if lex1 == lex2 && line1 == line2 => {}
-                    (SemanticError(lex1, line1, _), SemanticError(lex2, line2, _))
-                        if lex1 == lex2 && line1 == line2 => {}
-                    (Break(line1), Break(line2)) if line1 == line2 => {}
-                    (Continue(line1), Continue(line2)) if line1 == line2 => {}
-                    (ReturnVal(_, line1), ReturnVal(_, line2)) if line1 == line2 => {}
-                    _ => {
-                        panic!("unhandled error {:?}", err)
-                    }
+                // errors are told apart by their kind, lexeme and line
+                let (found, expected) = (Error::from(err.clone()), Error::from($err_val));
+                if (found.kind, &found.lexeme, found.line_no)
+                    != (expected.kind, &expected.lexeme, expected.line_no)
+                {
+                    panic!("unhandled error {:?}", err)
                 }
                 return;
             }
@@ -133,6 +126,7 @@ macro_rules! test_fail {
 // }
 
 mod assignment;
+mod bigint;
 mod block;
 mod bool;
 mod break_stmt;
@@ -141,20 +135,34 @@ mod class;
 mod closure;
 mod comments;
 mod constructor;
+mod continue_stmt;
+mod diagnostics;
+mod error;
+mod exception;
 mod field;
 mod for_stmt;
 mod function;
+mod generator;
 mod if_stmt;
 mod inheritance;
+mod int;
+mod list;
 mod logical_operator;
+mod map;
+mod match_stmt;
 mod method;
 mod miscellaneous;
+mod module;
 mod nil;
 mod number;
 mod operator;
 mod print;
+mod recovery;
 mod regression;
+mod repl;
 mod return_stmt;
+mod span;
+mod stacktrace;
 mod string;
 mod super_stmt;
 mod this;
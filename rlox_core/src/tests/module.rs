@@ -0,0 +1,141 @@
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+test_succeed!(
+    import_as,
+    ".././test-scripts/module/import_as.lox",
+    "math loaded",
+    3,
+    16,
+    3
+);
+
+test_succeed!(
+    shadowed_prelude,
+    ".././test-scripts/module/shadowed_prelude.lox",
+    1,
+    "math loaded",
+    3,
+    "Index out of bounds"
+);
+
+test_succeed!(
+    from_import,
+    ".././test-scripts/module/from_import.lox",
+    "math loaded",
+    9
+);
+
+test_succeed!(
+    once,
+    ".././test-scripts/module/once.lox",
+    "math loaded",
+    true
+);
+
+test_succeed!(
+    isolation,
+    ".././test-scripts/module/isolation.lox",
+    1,
+    2,
+    100
+);
+
+test_succeed!(
+    relative,
+    ".././test-scripts/module/relative.lox",
+    "hello inner",
+    "inner"
+);
+
+test_succeed!(
+    prelude,
+    ".././test-scripts/module/prelude.lox",
+    1,
+    "empty list"
+);
+
+test_succeed!(live, ".././test-scripts/module/live.lox", 0, 1, 11);
+
+test_fail!(
+    cycle,
+    ".././test-scripts/module/cycle.lox",
+    LoxError::runtime_error("cycle_a.lox", 1)
+);
+
+test_fail!(
+    self_import,
+    ".././test-scripts/module/self_import.lox",
+    LoxError::runtime_error("self_import.lox", 1)
+);
+
+test_fail!(
+    missing,
+    ".././test-scripts/module/missing.lox",
+    LoxError::runtime_error("lib/nope.lox", 1)
+);
+
+test_fail!(
+    missing_member,
+    ".././test-scripts/module/missing_member.lox",
+    LoxError::runtime_error("cube", 1)
+);
+
+test_fail!(
+    in_block,
+    ".././test-scripts/module/in_block.lox",
+    LoxError::semantic_error("import", 2)
+);
+
+#[test]
+fn cycle_chain() {
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::new(RefCell::new(vec![])),
+    })));
+    match run_script(".././test-scripts/module/cycle.lox", &mut interpreter) {
+        Err(LoxError::RuntimeError {
+            message: msg,
+            span: Some(span),
+            ..
+        }) => {
+            assert_eq!(
+                msg,
+                "Import cycle detected: cycle_a.lox -> cycle_b.lox -> cycle_a.lox"
+            );
+            assert_eq!((span.file_id, span.line, span.column), (2, 1, 1));
+        }
+        res => panic!("unexpected result {:?}", res),
+    }
+}
+
+#[test]
+fn missing_after_print() {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    })));
+    match run_script(
+        ".././test-scripts/module/missing_after_print.lox",
+        &mut interpreter,
+    ) {
+        Err(LoxError::RuntimeError { message: msg, .. }) => assert_eq!(msg, "Module not found"),
+        res => panic!("unexpected result {:?}", res),
+    }
+    assert_eq!(
+        *print_cache.borrow(),
+        vec![Object::Str("before".to_string())]
+    );
+}
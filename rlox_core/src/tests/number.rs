@@ -18,13 +18,13 @@ use std::sync::atomic::{AtomicBool, Ordering};
 test_fail!(
     decimal_point_at_eof,
     ".././test-scripts/number/decimal_point_at_eof.lox",
-    LoxError::ParserError(String::from(""), 3, String::from(""))
+    LoxError::parser_error("", 3)
 );
 
 test_fail!(
     leading_dot,
     ".././test-scripts/number/leading_dot.lox",
-    LoxError::ParserError(String::from("."), 2, String::from(""))
+    LoxError::parser_error(".", 2)
 );
 
 test_succeed!(
@@ -41,5 +41,49 @@ test_succeed!(
 test_fail!(
     trailing_dot,
     ".././test-scripts/number/trailing_dot.lox",
-    LoxError::ParserError(String::from(";"), 2, String::from(""))
+    LoxError::parser_error(";", 2)
+);
+
+test_succeed!(
+    extended,
+    ".././test-scripts/number/extended.lox",
+    1e9,
+    0.0015,
+    200,
+    255,
+    10,
+    15,
+    1000000,
+    65535,
+    10.25
+);
+
+test_fail!(
+    empty_hex,
+    ".././test-scripts/number/empty_hex.lox",
+    LoxError::scanner_error("0x", 2)
+);
+
+test_fail!(
+    invalid_binary,
+    ".././test-scripts/number/invalid_binary.lox",
+    LoxError::scanner_error("0b102", 2)
+);
+
+test_fail!(
+    missing_exponent,
+    ".././test-scripts/number/missing_exponent.lox",
+    LoxError::scanner_error("1e", 2)
+);
+
+test_fail!(
+    misplaced_separator,
+    ".././test-scripts/number/misplaced_separator.lox",
+    LoxError::scanner_error("1__0", 2)
+);
+
+test_fail!(
+    trailing_letters,
+    ".././test-scripts/number/trailing_letters.lox",
+    LoxError::scanner_error("12abc", 2)
 );
@@ -18,5 +18,5 @@ use std::sync::atomic::{AtomicBool, Ordering};
 test_fail!(
     missing_argument,
     ".././test-scripts/print/missing_argument.lox",
-    LoxError::ParserError(String::from(";"), 2, String::from(""))
+    LoxError::parser_error(";", 2)
 );
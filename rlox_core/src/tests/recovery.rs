@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::definitions::stmt::Stmt;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::rc::Rc;
+
+fn parse(path: &str) -> (Vec<Stmt>, Vec<LoxError>) {
+    let script = read_to_string(path).unwrap();
+    Parser::new(Lexer::new().parse(&script).unwrap()).parse_all()
+}
+
+// the lexemes and lines errors were found at
+fn positions(errors: &[LoxError]) -> Vec<(String, u32)> {
+    errors
+        .iter()
+        .map(|err| match err {
+            LoxError::ScannerError {
+                lexeme, line_no, ..
+            }
+            | LoxError::ParserError {
+                lexeme, line_no, ..
+            }
+            | LoxError::SemanticError {
+                lexeme, line_no, ..
+            }
+            | LoxError::RuntimeError {
+                lexeme, line_no, ..
+            } => (lexeme.clone(), *line_no),
+            err => panic!("unexpected error {:?}", err),
+        })
+        .collect()
+}
+
+#[test]
+fn syntax_errors() {
+    let (ast, errors) = parse(".././test-scripts/recovery/syntax.lox");
+    assert_eq!(
+        positions(&errors),
+        vec![
+            (";".to_string(), 1),
+            ("print".to_string(), 4),
+            (";".to_string(), 7)
+        ]
+    );
+    assert_eq!(errors[0].error_message(), "Expect Expression");
+    // the function keeps the statements of its body which parsed
+    assert_eq!(ast.len(), 2);
+    match &ast[0] {
+        Stmt::Function(val) => assert_eq!(val.body.len(), 1),
+        _ => panic!("expected function"),
+    }
+    assert!(matches!(ast[1], Stmt::Print(_)));
+}
+
+#[test]
+fn first_syntax_error() {
+    let script = read_to_string(".././test-scripts/recovery/syntax.lox").unwrap();
+    let err = Parser::new(Lexer::new().parse(&script).unwrap())
+        .parse()
+        .unwrap_err();
+    assert_eq!(positions(&[err]), vec![(";".to_string(), 1)]);
+}
+
+#[test]
+fn error_at_closing_brace() {
+    let script = "{ print 1 }\nprint 2;".to_string();
+    let (ast, errors) = Parser::new(Lexer::new().parse(&script).unwrap()).parse_all();
+    assert_eq!(positions(&errors), vec![("}".to_string(), 1)]);
+    assert_eq!(ast.len(), 2);
+}
+
+#[test]
+fn scanner_errors() {
+    let script = read_to_string(".././test-scripts/recovery/scanner.lox").unwrap();
+    let (tokens, errors) = Lexer::new().parse_all(&script);
+    assert_eq!(
+        positions(&errors),
+        vec![
+            ("@".to_string(), 1),
+            ("#".to_string(), 2),
+            ("$$".to_string(), 4),
+            ("\"never closed;".to_string(), 5)
+        ]
+    );
+    assert_eq!(errors[0].error_message(), "Unexpected character.");
+    assert_eq!(errors[3].error_message(), "Unterminated string.");
+    // scanning went on after each error
+    assert!(tokens.iter().any(|token| token.lexeme == "\"ok\""));
+    let err = Lexer::new().parse(&script).unwrap_err();
+    assert_eq!(positions(&[err]), vec![("@".to_string(), 1)]);
+}
+
+#[test]
+fn semantic_errors() {
+    let (mut ast, errors) = parse(".././test-scripts/recovery/semantic.lox");
+    assert!(errors.is_empty());
+    let errors = Resolver::new().resolve_all(&mut ast).unwrap_err();
+    assert_eq!(
+        positions(&errors),
+        vec![
+            ("return".to_string(), 1),
+            ("a".to_string(), 4),
+            ("break".to_string(), 6),
+            ("this".to_string(), 7),
+            ("continue".to_string(), 10),
+        ]
+    );
+}
+
+#[test]
+fn resolver_reusable() {
+    let mut resolver = Resolver::new();
+    let (mut ast, _) = parse(".././test-scripts/recovery/semantic.lox");
+    assert!(resolver.resolve_all(&mut ast).is_err());
+    let (mut ast, _) = parse(".././test-scripts/recovery/after_continue.lox");
+    assert!(resolver.resolve_all(&mut ast).is_ok());
+}
+
+test_succeed!(
+    after_continue,
+    ".././test-scripts/recovery/after_continue.lox",
+    1.0
+);
@@ -0,0 +1,75 @@
+#![cfg(test)]
+
+use crate::error::Error;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::runner::{is_complete, Runner};
+use crate::runtime::system_calls::SystemInterfaceMock;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn session(lines: &[&str]) -> (Vec<Object>, Vec<Error>) {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut runner = Runner::build(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    })));
+    let mut errors = vec![];
+    for line in lines {
+        if let Err(errs) = runner.run_line(line) {
+            errors.extend(errs);
+        }
+    }
+    let printed = print_cache.borrow().clone();
+    (printed, errors)
+}
+
+#[test]
+fn keeps_globals() {
+    let (printed, errors) = session(&[
+        "var a = 1;",
+        "fun add(b) { return a + b; }",
+        "print add(2);",
+    ]);
+    assert_eq!(printed, vec![Object::Num(3.0)]);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn echoes_bare_expression() {
+    let (printed, errors) = session(&["var a = 2;", "a * 3;", "a + 1", "print a;", "nil;"]);
+    assert_eq!(
+        printed,
+        vec![Object::Num(6.0), Object::Num(3.0), Object::Num(2.0)]
+    );
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn recovers_from_errors() {
+    let (printed, errors) = session(&[
+        "var a = 1;",
+        "{ var b = 1; var b = 2; }",
+        "print nope;",
+        "var c = a + 1;",
+        "c",
+    ]);
+    assert_eq!(printed, vec![Object::Num(2.0)]);
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn multi_line_input() {
+    assert!(is_complete("var a = 1;\n"));
+    assert!(!is_complete("fun f() {\n"));
+    assert!(!is_complete("fun f() {\n  if (true) {\n  }\n"));
+    assert!(is_complete("fun f() {\n  return 1;\n}\n"));
+    assert!(is_complete("print \"{\";\n"));
+    assert!(is_complete("print 1; // {\n"));
+    assert!(!is_complete("print \"a\n"));
+    assert!(!is_complete("/* a\n"));
+    assert!(!is_complete("/* a /* b */\n"));
+    assert!(is_complete("/* a /* { */ */ print 1;\n"));
+
+    let (printed, errors) = session(&["fun f(x) {\n  return x + 1;\n}\n", "f(1)\n"]);
+    assert_eq!(printed, vec![Object::Num(2.0)]);
+    assert!(errors.is_empty());
+}
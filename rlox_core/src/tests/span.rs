@@ -0,0 +1,161 @@
+#![cfg(test)]
+
+use crate::frontend::definitions::expr::Expr;
+use crate::frontend::definitions::span::Span;
+use crate::frontend::definitions::stmt::Stmt;
+use crate::frontend::definitions::token::Token;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::parser::Parser;
+use crate::runtime::module::Modules;
+use std::path::Path;
+
+fn tokens(script: &str) -> Vec<Token> {
+    Lexer::new().parse(&script.to_string()).unwrap()
+}
+
+fn parse(script: &str) -> Vec<Stmt> {
+    Parser::new(tokens(script)).parse().unwrap()
+}
+
+fn span(start: usize, end: usize, line: u32, column: u32, end_line: u32, end_column: u32) -> Span {
+    Span {
+        file_id: 0,
+        start,
+        end,
+        line,
+        column,
+        end_line,
+        end_column,
+    }
+}
+
+// the source a span covers
+fn text(script: &str, span: Span) -> &str {
+    &script[span.start..span.end]
+}
+
+#[test]
+fn token_offsets_and_columns() {
+    let tokens = tokens("var a = 1;\n  print a;");
+    assert_eq!(tokens[0].span, span(0, 3, 1, 1, 1, 4));
+    assert_eq!(tokens[1].span, span(4, 5, 1, 5, 1, 6));
+    assert_eq!(tokens[5].span, span(13, 18, 2, 3, 2, 8));
+    let eof = tokens.last().unwrap();
+    assert_eq!(eof.span, span(21, 21, 2, 11, 2, 11));
+}
+
+#[test]
+fn columns_count_chars() {
+    let tokens = tokens("print \"héllo\" + x;");
+    assert_eq!(tokens[1].span, span(6, 14, 1, 7, 1, 14));
+    assert_eq!(tokens[3].span, span(17, 18, 1, 17, 1, 18));
+}
+
+#[test]
+fn multi_line_string() {
+    let tokens = tokens("var s = \"a\nbc\";");
+    assert_eq!(tokens[3].span, span(8, 14, 1, 9, 2, 4));
+}
+
+#[test]
+fn interpolation() {
+    let script = "print \"a${x + 1}b\";";
+    let tokens = tokens(script);
+    let x = tokens.iter().find(|token| token.lexeme == "x").unwrap();
+    assert_eq!(text(script, x.span), "x");
+    assert_eq!(x.span.column, 11);
+    // the tokens added around it cover the whole string
+    assert_eq!(text(script, tokens[1].span), "\"a${x + 1}b\"");
+}
+
+#[test]
+fn file_id() {
+    let tokens = Lexer::for_file(3).parse(&"x;".to_string()).unwrap();
+    assert!(tokens.iter().all(|token| token.span.file_id == 3));
+}
+
+#[test]
+fn expression_spans() {
+    let script = "print (1 + 2) * foo.bar(3, \"x\")[0];";
+    let ast = parse(script);
+    let expr = match &ast[0] {
+        Stmt::Print(val) => &val.expr,
+        _ => panic!("expected print"),
+    };
+    assert_eq!(text(script, expr.span()), "(1 + 2) * foo.bar(3, \"x\")[0]");
+    let binary = match expr {
+        Expr::Binary(val) => val,
+        _ => panic!("expected binary"),
+    };
+    assert_eq!(text(script, binary.left.span()), "(1 + 2)");
+    assert_eq!(text(script, binary.right.span()), "foo.bar(3, \"x\")[0]");
+    let call = match &binary.right {
+        Expr::Index(val) => &val.object,
+        _ => panic!("expected index"),
+    };
+    assert_eq!(text(script, call.span()), "foo.bar(3, \"x\")");
+    if let Expr::Call(call) = call {
+        assert_eq!(text(script, call.arguments[1].span()), "\"x\"");
+        assert_eq!(call.arguments[1].span().column, 28);
+    } else {
+        panic!("expected call");
+    }
+}
+
+#[test]
+fn assignment_spans() {
+    let script = "a.b = c = [1, 2];";
+    let ast = parse(script);
+    let expr = match &ast[0] {
+        Stmt::Expression(val) => &val.expr,
+        _ => panic!("expected expression"),
+    };
+    assert_eq!(text(script, ast[0].span()), script);
+    assert_eq!(text(script, expr.span()), "a.b = c = [1, 2]");
+    if let Expr::Set(set) = expr {
+        assert_eq!(text(script, set.value.span()), "c = [1, 2]");
+    } else {
+        panic!("expected set");
+    }
+}
+
+#[test]
+fn statement_spans() {
+    let script = "fun f(a) {\n  if (a) return 1;\n  return 2;\n}\nclass A < B {\n  m() {}\n}";
+    let ast = parse(script);
+    assert_eq!(
+        text(script, ast[0].span()),
+        "fun f(a) {\n  if (a) return 1;\n  return 2;\n}"
+    );
+    assert_eq!(ast[1].span().line, 5);
+    assert_eq!(text(script, ast[1].span()), "class A < B {\n  m() {}\n}");
+    let body = match &ast[0] {
+        Stmt::Function(val) => &val.body,
+        _ => panic!("expected function"),
+    };
+    assert_eq!(text(script, body[0].span()), "if (a) return 1;");
+    assert_eq!(body[1].span().column, 3);
+    if let Stmt::Class(class) = &ast[1] {
+        assert_eq!(text(script, class.methods[0].span), "m() {}");
+    } else {
+        panic!("expected class");
+    }
+}
+
+#[test]
+fn desugared_for() {
+    let script = "for (var i = 0; i < 2; i = i + 1) print i;";
+    let ast = parse(script);
+    assert_eq!(text(script, ast[0].span()), script);
+}
+
+#[test]
+fn module_file_ids() {
+    let mut modules: Modules<()> = Modules::new();
+    assert_eq!(modules.add_file(Path::new("a.lox"), "1;".to_string()), 1);
+    assert_eq!(modules.add_file(Path::new("b.lox"), "2;".to_string()), 2);
+    assert_eq!(modules.add_file(Path::new("a.lox"), "3;".to_string()), 1);
+    assert_eq!(modules.file(2), Some((Path::new("b.lox"), "2;")));
+    assert_eq!(modules.file(1), Some((Path::new("a.lox"), "3;")));
+    assert_eq!(modules.file(0), None);
+}
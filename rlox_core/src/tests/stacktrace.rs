@@ -0,0 +1,69 @@
+#![cfg(test)]
+
+use super::*;
+use crate::error::LoxError;
+use crate::frontend::lexer::*;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolver::Resolver;
+use crate::runtime::definitions::object::Object;
+use crate::runtime::interpreter::Interpreter;
+use crate::runtime::system_calls::SystemInterfaceMock;
+use logos::{source::Source, Logos};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+fn uncaught(path: &str) -> String {
+    let print_cache = Rc::new(RefCell::new(vec![]));
+    let mut interpreter = Interpreter::new(Rc::new(RefCell::new(SystemInterfaceMock {
+        print_cache: Rc::clone(&print_cache),
+    })));
+    run_script(path, &mut interpreter).unwrap_err().to_string()
+}
+
+test_succeed!(
+    statement,
+    ".././test-scripts/stacktrace/statement.lox",
+    "[line 2] in inner()\n[line 6] in outer()\n[line 9] in script",
+    "[line 10] in script"
+);
+
+test_fail!(
+    caught,
+    ".././test-scripts/stacktrace/caught.lox",
+    LoxError::runtime_error("1", 10)
+);
+
+test_fail!(
+    native,
+    ".././test-scripts/stacktrace/native.lox",
+    LoxError::runtime_error("1", 2)
+);
+
+#[test]
+fn uncaught_backtrace() {
+    assert_eq!(
+        uncaught(".././test-scripts/stacktrace/uncaught.lox"),
+        "[RuntimeError:L2:/] Division by zero\n[line 2] in divide()\n[line 6] in average()\n[line 9] in script"
+    );
+}
+
+#[test]
+fn method_backtrace() {
+    assert_eq!(
+        uncaught(".././test-scripts/stacktrace/method.lox"),
+        "[RuntimeError:L8:Error] Uncaught Error: negative\n[line 8] in check()\n[line 4] in init()\n[line 12] in Lambda()\n[line 13] in script"
+    );
+}
+
+#[test]
+fn caught_backtrace() {
+    assert_eq!(
+        uncaught(".././test-scripts/stacktrace/caught.lox"),
+        "[RuntimeError:L10:1] len expects a Str, List or Map\n[line 10] in script"
+    );
+}
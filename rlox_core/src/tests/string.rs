@@ -15,11 +15,11 @@ use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-// test_fail!(
-//     error_after_multiline,
-//     ".././test-scripts/string/error_after_multiline.lox",
-//     LoxError::ParserError(String::from(""), 0, String::from(""))
-// );
+test_fail!(
+    error_after_multiline,
+    ".././test-scripts/string/error_after_multiline.lox",
+    LoxError::runtime_error("err", 7)
+);
 
 test_succeed!(
     literals,
@@ -29,19 +29,48 @@ test_succeed!(
     "A~¶Þॐஃ"
 );
 
-//TODO: handle multiline string
-// test_succeed!(
-//     multiline,
-//     ".././test-scripts/string/multiline.lox",
-//     "1\n2\n3"
-// );
+test_succeed!(
+    multiline,
+    ".././test-scripts/string/multiline.lox",
+    "1\n2\n3"
+);
 
 test_fail!(
     unterminated,
     ".././test-scripts/string/unterminated.lox",
-    LoxError::ParserError(
-        String::from("\"this string has no close quote"),
-        2,
-        String::from("")
-    )
+    LoxError::scanner_error("\"this string has no close quote", 2)
+);
+
+test_succeed!(
+    escapes,
+    ".././test-scripts/string/escapes.lox",
+    "tab:\there",
+    "quote: \"hi\"",
+    "back\\slash",
+    "line\nbreak",
+    "HI",
+    "${not interpolated}"
+);
+
+test_fail!(
+    invalid_escape,
+    ".././test-scripts/string/invalid_escape.lox",
+    LoxError::scanner_error("\\q", 2)
+);
+
+test_succeed!(
+    interpolation,
+    ".././test-scripts/string/interpolation.lox",
+    "Hello world!",
+    "3 is three",
+    "nil: Nil, bool: true",
+    "[1, 2] {a: 1}",
+    "nested: inner world",
+    "world"
+);
+
+test_fail!(
+    interpolation_line,
+    ".././test-scripts/string/interpolation_line.lox",
+    LoxError::runtime_error("err", 5)
 );
@@ -47,7 +47,7 @@ test_succeed!(
 test_fail!(
     extra_arguments,
     ".././test-scripts/super/extra_arguments.lox",
-    LoxError::RuntimeError(String::from("foo"), 10, String::from(""))
+    LoxError::runtime_error("foo", 10)
 );
 
 test_succeed!(
@@ -60,31 +60,31 @@ test_succeed!(
 test_fail!(
     missing_arguments,
     ".././test-scripts/super/missing_arguments.lox",
-    LoxError::RuntimeError(String::from("foo"), 9, String::from(""))
+    LoxError::runtime_error("foo", 9)
 );
 
 test_fail!(
     no_superclass_bind,
     ".././test-scripts/super/no_superclass_bind.lox",
-    LoxError::SemanticError(String::from("super"), 3, String::from(""))
+    LoxError::semantic_error("super", 3)
 );
 
 test_fail!(
     no_superclass_call,
     ".././test-scripts/super/no_superclass_call.lox",
-    LoxError::SemanticError(String::from("super"), 3, String::from(""))
+    LoxError::semantic_error("super", 3)
 );
 
 test_fail!(
     no_superclass_method,
     ".././test-scripts/super/no_superclass_method.lox",
-    LoxError::RuntimeError(String::from("super"), 5, String::from(""))
+    LoxError::runtime_error("super", 5)
 );
 
 test_fail!(
     parenthesized,
     ".././test-scripts/super/parenthesized.lox",
-    LoxError::ParserError(String::from(")"), 8, String::from(""))
+    LoxError::parser_error(")", 8)
 );
 
 test_succeed!(
@@ -97,7 +97,7 @@ test_succeed!(
 test_fail!(
     super_at_top_level,
     ".././test-scripts/super/super_at_top_level.lox",
-    LoxError::SemanticError(String::from("super"), 1, String::from(""))
+    LoxError::semantic_error("super", 1)
 );
 
 test_succeed!(
@@ -115,19 +115,19 @@ test_succeed!(
 test_fail!(
     super_in_top_level_function,
     ".././test-scripts/super/super_in_top_level_function.lox",
-    LoxError::SemanticError(String::from("super"), 1, String::from(""))
+    LoxError::semantic_error("super", 1)
 );
 
 test_fail!(
     super_without_dot,
     ".././test-scripts/super/super_without_dot.lox",
-    LoxError::ParserError(String::from(";"), 6, String::from(""))
+    LoxError::parser_error(";", 6)
 );
 
 test_fail!(
     super_without_name,
     ".././test-scripts/super/super_without_name.lox",
-    LoxError::ParserError(String::from(";"), 5, String::from(""))
+    LoxError::parser_error(";", 5)
 );
 
 test_succeed!(
@@ -47,7 +47,7 @@ test_succeed!(
 test_fail!(
     this_at_top_level,
     ".././test-scripts/this/this_at_top_level.lox",
-    LoxError::SemanticError(String::from("this"), 1, String::from(""))
+    LoxError::semantic_error("this", 1)
 );
 
 test_succeed!(
@@ -59,5 +59,5 @@ test_succeed!(
 test_fail!(
     this_in_top_level_function,
     ".././test-scripts/this/this_in_top_level_function.lox",
-    LoxError::SemanticError(String::from("this"), 2, String::from(""))
+    LoxError::semantic_error("this", 2)
 );
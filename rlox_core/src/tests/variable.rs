@@ -18,19 +18,19 @@ use std::sync::atomic::{AtomicBool, Ordering};
 test_fail!(
     collide_with_parameter,
     ".././test-scripts/variable/collide_with_parameter.lox",
-    LoxError::RuntimeError(String::from("a"), 2, String::from(""))
+    LoxError::runtime_error("a", 2)
 );
 
 test_fail!(
     duplicate_local,
     ".././test-scripts/variable/duplicate_local.lox",
-    LoxError::RuntimeError(String::from("a"), 3, String::from(""))
+    LoxError::runtime_error("a", 3)
 );
 
 test_fail!(
     duplicate_parameter,
     ".././test-scripts/variable/duplicate_parameter.lox",
-    LoxError::RuntimeError(String::from("arg"), 2, String::from(""))
+    LoxError::runtime_error("arg", 2)
 );
 
 test_succeed!(
@@ -104,13 +104,13 @@ test_succeed!(
 test_fail!(
     undefined_global,
     ".././test-scripts/variable/undefined_global.lox",
-    LoxError::RuntimeError(String::from("notDefined"), 1, String::from(""))
+    LoxError::runtime_error("notDefined", 1)
 );
 
 test_fail!(
     undefined_local,
     ".././test-scripts/variable/undefined_local.lox",
-    LoxError::RuntimeError(String::from("notDefined"), 2, String::from(""))
+    LoxError::runtime_error("notDefined", 2)
 );
 
 test_succeed!(
@@ -128,7 +128,7 @@ test_succeed!(
 test_fail!(
     use_false_as_var,
     ".././test-scripts/variable/use_false_as_var.lox",
-    LoxError::ParserError(String::from("false"), 2, String::from(""))
+    LoxError::parser_error("false", 2)
 );
 
 test_succeed!(
@@ -140,17 +140,17 @@ test_succeed!(
 test_fail!(
     use_local_in_initializer,
     ".././test-scripts/variable/use_local_in_initializer.lox",
-    LoxError::SemanticError(String::from("a"), 3, String::from(""))
+    LoxError::semantic_error("a", 3)
 );
 
 test_fail!(
     use_nil_as_var,
     ".././test-scripts/variable/use_nil_as_var.lox",
-    LoxError::ParserError(String::from("nil"), 2, String::from(""))
+    LoxError::parser_error("nil", 2)
 );
 
 test_fail!(
     use_this_as_var,
     ".././test-scripts/variable/use_this_as_var.lox",
-    LoxError::ParserError(String::from("this"), 2, String::from(""))
+    LoxError::parser_error("this", 2)
 );
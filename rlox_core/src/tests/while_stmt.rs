@@ -18,7 +18,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 test_fail!(
     class_in_body,
     ".././test-scripts/while/class_in_body.lox",
-    LoxError::ParserError(String::from("class"), 2, String::from(""))
+    LoxError::parser_error("class", 2)
 );
 
 test_succeed!(
@@ -32,7 +32,7 @@ test_succeed!(
 test_fail!(
     fun_in_body,
     ".././test-scripts/while/fun_in_body.lox",
-    LoxError::ParserError(String::from("foo"), 2, String::from(""))
+    LoxError::parser_error("foo", 2)
 );
 
 test_succeed!(
@@ -61,5 +61,5 @@ test_succeed!(
 test_fail!(
     var_in_body,
     ".././test-scripts/while/var_in_body.lox",
-    LoxError::ParserError(String::from("var"), 2, String::from(""))
+    LoxError::parser_error("var", 2)
 );
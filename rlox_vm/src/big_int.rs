@@ -0,0 +1,16 @@
+use crate::gc::{
+    heap::Heap,
+    root::{CustomClone, Trace},
+};
+pub use rlox_core::runtime::definitions::big_int::BigInt;
+
+// BigInts hold no Objects, so there's nothing for the GC to follow.
+impl Trace for BigInt {
+    fn trace(&mut self) {}
+}
+
+impl CustomClone for BigInt {
+    fn clone(&self, _gc: &Heap) -> Self {
+        Clone::clone(self)
+    }
+}
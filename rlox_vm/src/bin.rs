@@ -1,8 +1,12 @@
+use rlox_core::diagnostics::Format;
+use rlox_core::error::Error;
 use rlox_vm::chunk::{OpCode, VM};
-// use debug::disassemble_chunk;
-use rlox_vm::compiler::run_file;
+use rlox_vm::compiler::{run_prompt, Session};
+use rlox_vm::debug::Tracer;
 use rlox_vm::system_calls::SystemInterface;
 use std::env::args;
+use std::fs::read_to_string;
+use std::io::stderr;
 fn main() {
     //     let mut vm = VM::new();
     // let mut const_pool:Vec<Value>;
@@ -11,13 +15,69 @@ fn main() {
     // vm.push_inst(OpCode::Constant(2, const_pos));
     // vm.push_inst(OpCode::Return(1));
     // vm.run(true);
-    let cli_args: Vec<String> = args().collect();
+    let mut cli_args: Vec<String> = args().collect();
+    let error_format = match Format::from_args(&mut cli_args) {
+        Some(format) => format,
+        None => return usage(),
+    };
     let ln = cli_args.len();
-    if ln == 2 {
-        run_file(&cli_args[1], SystemInterface {});
+    let mut session = Session::new(SystemInterface {});
+    if ln == 3 && cli_args[1] == "--disassemble" {
+        match session.disassemble_file(&cli_args[2]) {
+            Ok(listing) => print!("{}", listing),
+            Err(errors) => report(&session, &errors, &cli_args[2], error_format),
+        }
+    } else if ln >= 3 && cli_args[1] == "--trace" {
+        match tracer(&cli_args[2..ln - 1]) {
+            Some(tracer) => {
+                session.set_tracer(tracer);
+                if let Err(errors) = session.run_file(&cli_args[ln - 1]) {
+                    report(&session, &errors, &cli_args[ln - 1], error_format);
+                }
+            }
+            None => usage(),
+        }
+    } else if ln == 2 {
+        if let Err(errors) = session.run_file(&cli_args[1]) {
+            report(&session, &errors, &cli_args[1], error_format);
+        }
     } else if ln < 2 {
+        run_prompt(SystemInterface {}, error_format);
     } else {
-        println!("rlox [script]");
+        usage();
     }
-    // disassemble_chunk(&mut vm, "test chunk");
+}
+
+fn usage() {
+    println!(
+        "rlox [--error-format plain|color|json] [--disassemble | --trace [--fn name] [--lines from-to]] [script]"
+    );
+}
+
+fn report(session: &Session<SystemInterface>, errors: &[Error], path: &str, format: Format) {
+    let script = read_to_string(path).unwrap_or_default();
+    for err in errors {
+        let diagnostic = session.diagnostic(err, Some(path), &script);
+        eprint!("{}", diagnostic.render(format));
+    }
+}
+
+// The trace is written to stderr, out of the way of the script's own output.
+fn tracer(options: &[String]) -> Option<Tracer> {
+    let mut tracer = Tracer::new(Box::new(stderr()));
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next()?;
+        tracer = match option.as_str() {
+            "--fn" => tracer.function(value),
+            "--lines" => {
+                let mut bounds = value.splitn(2, '-').map(|bound| bound.parse::<u32>());
+                let from = bounds.next()?.ok()?;
+                let to = bounds.next().unwrap_or(Ok(from)).ok()?;
+                tracer.lines(from..=to)
+            }
+            _ => return None,
+        };
+    }
+    Some(tracer)
 }
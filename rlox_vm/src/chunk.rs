@@ -1,27 +1,37 @@
-use rlox_core::frontend::definitions::literal::Literal;
-use rlox_core::frontend::definitions::token::Token;
-use rlox_core::{error::LoxError, frontend::definitions::token_type::TokenType};
-use std::cell::RefCell;
-use std::fmt::Error;
-use std::fmt::Formatter;
-use std::time::SystemTime;
-// use crate::debug::disassemble_inst;
 use crate::{
+    big_int::BigInt,
     class::Class,
+    debug::{self, Tracer},
     gc::{
         heap::Heap,
         root::{CustomClone, CustomVecOps, Root, UniqueRoot},
     },
+    generator::{Generator, GeneratorState},
     instance::{Instance, InstanceBoundMethod},
+    list::List,
+    map::{Map, MapKey},
     system_calls::SystemCalls,
 };
+use rlox_core::frontend::definitions::literal::Literal;
+use rlox_core::frontend::definitions::span::Span;
+use rlox_core::frontend::definitions::token::Token;
+use rlox_core::runtime::definitions::lox_generator::Resume;
+use rlox_core::runtime::definitions::number::{BinaryOp, Number};
+use rlox_core::{
+    error::{format_backtrace, ErrorCode, LoxError, TraceFrame},
+    frontend::definitions::token_type::TokenType,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
 use std::ops::{Add, Div, Mul, Sub};
+use std::time::SystemTime;
 
 const MAX_STACK: usize = 1000;
 
-type NativeFn = fn(args: Vec<Object>) -> Object;
+type NativeFn = fn(args: Vec<Object>, gc: &Heap) -> Result<Object, LoxError>;
 
 /**
  * TODO:
@@ -41,6 +51,8 @@ type NativeFn = fn(args: Vec<Object>) -> Object;
 pub enum Object {
     Str(String),
     Num(f64),
+    Int(i64),
+    BigInt(Root<BigInt>),
     Bool(bool),
     Nil,
     // TODO: non closures can be made functions instead of closures
@@ -50,6 +62,14 @@ pub enum Object {
     ClassDef(Root<Class>),
     InstanceDef(Root<Instance>),
     InstanceBindDef(Root<InstanceBoundMethod>),
+    List(Root<List>),
+    Map(Root<Map>),
+    // What a for-in loop over a list, string or map iterates over: a snapshot of the items left,
+    // last first.
+    Iterator(Root<List>),
+    Generator(Root<Generator>),
+    // a generator's hasNext or next, bound to it
+    GeneratorMethod(Root<Generator>, Resume),
 }
 
 impl CustomClone for Object {
@@ -57,13 +77,20 @@ impl CustomClone for Object {
         match self {
             Object::Str(v) => Object::Str(v.clone()),
             Object::Num(v) => Object::Num(*v),
+            Object::Int(v) => Object::Int(*v),
+            Object::BigInt(v) => Object::BigInt(gc.clone_root(v)),
             Object::Bool(v) => Object::Bool(*v),
             Object::Nil => Object::Nil,
-            Object::NativeFunction(v) => Object::NativeFunction(v.clone()),
+            Object::NativeFunction(v) => Object::NativeFunction(*v),
             Object::Closure(v) => Object::Closure(v.clone(gc)),
             Object::ClassDef(v) => Object::ClassDef(v.clone(gc)),
             Object::InstanceDef(v) => Object::InstanceDef(v.clone(gc)),
             Object::InstanceBindDef(v) => Object::InstanceBindDef(v.clone(gc)),
+            Object::List(v) => Object::List(v.clone(gc)),
+            Object::Map(v) => Object::Map(v.clone(gc)),
+            Object::Iterator(v) => Object::Iterator(v.clone(gc)),
+            Object::Generator(v) => Object::Generator(v.clone(gc)),
+            Object::GeneratorMethod(v, resume) => Object::GeneratorMethod(v.clone(gc), *resume),
         }
     }
 }
@@ -108,26 +135,43 @@ impl UpValueWrap {
     }
 }
 
-impl From<Literal> for Object {
-    fn from(val: Literal) -> Self {
+impl Object {
+    // BigInts live on the heap, so literals and numbers need it to become Objects.
+    pub fn from_literal(val: Literal, gc: &Heap) -> Self {
         match val {
             Literal::NUM(v) => Object::Num(v),
+            Literal::INT(v) => Object::Int(v),
+            Literal::BIG(v) => Object::BigInt(gc.get_root(v)),
             Literal::STRING(v) => Object::Str(v),
             Literal::BOOL(v) => Object::Bool(v),
             Literal::NIL => Object::Nil,
         }
     }
+
+    pub fn from_number(val: Number, gc: &Heap) -> Self {
+        match val {
+            Number::Int(v) => Object::Int(v),
+            Number::Big(v) => Object::BigInt(gc.get_root(v)),
+            Number::Float(v) => Object::Num(v),
+        }
+    }
 }
 
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         use Object::*;
 
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a.eq(b);
+        }
         match (self, other) {
             (&Str(ref a), &Str(ref b)) => a == b,
             (&Num(ref a), &Num(ref b)) => a == b,
             (&Bool(ref a), &Bool(ref b)) => a == b,
             (&Nil, &Nil) => true,
+            (List(a), List(b)) => a.data == b.data,
+            (Map(a), Map(b)) => a.data == b.data,
+            (Generator(a), Generator(b)) => a.data == b.data,
             _ => false,
         }
     }
@@ -141,6 +185,8 @@ impl Display for Object {
         match self {
             Object::Str(val) => writer.write_str(&val.to_string()),
             Object::Num(val) => writer.write_str(&val.to_string()),
+            Object::Int(val) => writer.write_str(&val.to_string()),
+            Object::BigInt(val) => writer.write_str(&val.to_string()),
             Object::Bool(val) => writer.write_str(&val.to_string()),
             Object::Nil => writer.write_str("Nil"),
             // Object::Function(val) => writer.write_fmt(format_args!("Function<{:?}>", val.name)),
@@ -151,126 +197,165 @@ impl Display for Object {
                 writer.write_fmt(format_args!("Instance<{}>", val.class.name))
             }
             Object::InstanceBindDef(val) => writer.write_fmt(format_args!("InstanceBind<>")),
+            Object::List(val) => {
+                writer.write_str("[")?;
+                for (i, element) in val.values.borrow().iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    element.fmt(writer)?;
+                }
+                writer.write_str("]")
+            }
+            Object::Map(val) => {
+                writer.write_str("{")?;
+                for (i, (key, value)) in val.entries.borrow().iter().enumerate() {
+                    if i > 0 {
+                        writer.write_str(", ")?;
+                    }
+                    writer.write_fmt(format_args!("{}: {}", key, value))?;
+                }
+                writer.write_str("}")
+            }
+            Object::Iterator(_) => writer.write_str("Iterator<>"),
+            Object::Generator(val) => writer.write_fmt(format_args!("Generator<{}>", val.name())),
+            Object::GeneratorMethod(_, resume) => {
+                writer.write_fmt(format_args!("GeneratorMethod<{}>", resume.name()))
+            }
         }
     }
 }
 
 impl Object {
-    pub fn add(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l + r)),
-            (Str(ref l), Str(ref r)) => {
-                let mut l = l.clone();
-                l.push_str(r);
-                Ok(Object::Str(l))
-            }
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Object::Num(val) => Some(Number::Float(*val)),
+            Object::Int(val) => Some(Number::Int(*val)),
+            Object::BigInt(val) => Some(Number::Big(Clone::clone(&**val))),
+            _ => None,
         }
     }
-    pub fn sub(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
+
+    // The arithmetic and bitwise operators, following the promotion rules of Number.
+    pub fn binary(
+        &self,
+        op: BinaryOp,
+        other: &Self,
+        line_no: u32,
+        gc: &Heap,
+    ) -> Result<Self, LoxError> {
+        let error = |(code, msg): (ErrorCode, &str)| LoxError::RuntimeError {
+            lexeme: other.to_string(),
+            line_no,
+            message: msg.to_string(),
+            code,
+            span: None,
+            backtrace: Box::new([]),
+        };
         match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l - r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
+            (Object::Str(l), Object::Str(r)) if op == BinaryOp::Add => {
+                Ok(Object::Str(format!("{}{}", l, r)))
+            }
+            _ => match (self.as_number(), other.as_number()) {
+                (Some(l), Some(r)) => l
+                    .binary(op, r)
+                    .map(|val| Object::from_number(val, gc))
+                    .map_err(error),
+                _ if op == BinaryOp::Add => Err(error((
+                    ErrorCode::OperandType,
+                    "Operands not Num or String",
+                ))),
+                _ => Err(error((ErrorCode::OperandType, "Operands not Num"))),
+            },
         }
     }
-    pub fn mul(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l * r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+    pub fn add(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Add, other, line_no, gc)
     }
-    pub fn div(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Num(l / r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+    pub fn sub(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Sub, other, line_no, gc)
+    }
+    pub fn mul(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Mul, other, line_no, gc)
+    }
+    pub fn div(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Div, other, line_no, gc)
+    }
+    pub fn int_div(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::IntDiv, other, line_no, gc)
+    }
+    pub fn modulo(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Mod, other, line_no, gc)
+    }
+    pub fn pow(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Pow, other, line_no, gc)
+    }
+    pub fn bit_and(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::BitAnd, other, line_no, gc)
+    }
+    pub fn bit_or(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::BitOr, other, line_no, gc)
+    }
+    pub fn bit_xor(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::BitXor, other, line_no, gc)
+    }
+    pub fn shl(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Shl, other, line_no, gc)
+    }
+    pub fn shr(&self, other: &Self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.binary(BinaryOp::Shr, other, line_no, gc)
     }
     pub fn gt(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l > r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l > r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_gt())
     }
     pub fn gte(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l >= r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l >= r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_ge())
     }
     pub fn lt(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l < r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l < r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_lt())
     }
     pub fn lte(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self, other) {
-            (Num(ref l), Num(ref r)) => Ok(Object::Bool(l <= r)),
-            (Str(ref l), Str(ref r)) => Ok(Object::Bool(l <= r)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
-                line_no,
-                "Operands not Num or String".to_string(),
-            )),
-        }
+        self.compare(other, line_no, |ord| ord.is_le())
+    }
+
+    // NaN compares false with everything.
+    fn compare(
+        &self,
+        other: &Self,
+        line_no: u32,
+        test: fn(std::cmp::Ordering) -> bool,
+    ) -> Result<Self, LoxError> {
+        let ordering = match (self, other) {
+            (Object::Str(l), Object::Str(r)) => l.partial_cmp(r),
+            _ => match (self.as_number(), other.as_number()) {
+                (Some(l), Some(r)) => l.partial_cmp(r),
+                _ => {
+                    return Err(LoxError::RuntimeError {
+                        lexeme: other.to_string(),
+                        line_no,
+                        message: "Operands not Num or String".to_string(),
+                        code: ErrorCode::OperandType,
+                        span: None,
+                        backtrace: Box::new([]),
+                    })
+                }
+            },
+        };
+        Ok(Object::Bool(ordering.is_some_and(test)))
     }
     pub fn bool_or(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
         use Object::*;
         match (self, other) {
             (Bool(ref l), Bool(ref r)) => Ok(Object::Bool(*l || *r)),
             // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
+            _ => Err(LoxError::RuntimeError {
+                lexeme: other.to_string(),
                 line_no,
-                "Operands not Boolean".to_string(),
-            )),
+                message: "Operands not Boolean".to_string(),
+                code: ErrorCode::OperandType,
+                span: None,
+                backtrace: Box::new([]),
+            }),
         }
     }
     pub fn bool_and(&self, other: &Self, line_no: u32) -> Result<Self, LoxError> {
@@ -278,23 +363,43 @@ impl Object {
         match (self, other) {
             (Bool(ref l), Bool(ref r)) => Ok(Object::Bool(*l && *r)),
             // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                other.to_string(),
+            _ => Err(LoxError::RuntimeError {
+                lexeme: other.to_string(),
                 line_no,
-                "Operands not Boolean".to_string(),
-            )),
+                message: "Operands not Boolean".to_string(),
+                code: ErrorCode::OperandType,
+                span: None,
+                backtrace: Box::new([]),
+            }),
         }
     }
-    pub fn neg(&self, line_no: u32) -> Result<Self, LoxError> {
-        use Object::*;
-        match (self) {
-            (Num(ref l)) => Ok(Object::Num(-*l)),
-            // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                self.to_string(),
-                line_no,
-                "Operands not Num".to_string(),
-            )),
+    pub fn neg(&self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.unary(|num| Ok(-num), line_no, gc)
+    }
+
+    pub fn bit_not(&self, line_no: u32, gc: &Heap) -> Result<Self, LoxError> {
+        self.unary(Number::bit_not, line_no, gc)
+    }
+
+    fn unary(
+        &self,
+        op: fn(Number) -> Result<Number, (ErrorCode, &'static str)>,
+        line_no: u32,
+        gc: &Heap,
+    ) -> Result<Self, LoxError> {
+        let error = |(code, msg): (ErrorCode, &str)| LoxError::RuntimeError {
+            lexeme: self.to_string(),
+            line_no,
+            message: msg.to_string(),
+            code,
+            span: None,
+            backtrace: Box::new([]),
+        };
+        match self.as_number() {
+            Some(val) => op(val)
+                .map(|val| Object::from_number(val, gc))
+                .map_err(error),
+            None => Err(error((ErrorCode::OperandType, "Operands not Num"))),
         }
     }
 
@@ -303,11 +408,14 @@ impl Object {
         match (self) {
             (Bool(ref l)) => Ok(Object::Bool(!*l)),
             // TODO: import error def
-            _ => Err(LoxError::RuntimeError(
-                self.to_string(),
+            _ => Err(LoxError::RuntimeError {
+                lexeme: self.to_string(),
                 line_no,
-                "Operands not Boolean".to_string(),
-            )),
+                message: "Operands not Boolean".to_string(),
+                code: ErrorCode::OperandType,
+                span: None,
+                backtrace: Box::new([]),
+            }),
         }
     }
 }
@@ -330,6 +438,16 @@ pub struct FuncSpec {
     // index, isLocal
     pub upvalues: Vec<(usize, bool)>,
     pub upvalues_ref: RefCell<Vec<Root<UpValueWrap>>>,
+    // innermost handlers come first
+    pub handlers: Vec<Handler>,
+    // calls return a generator instead of running the body
+    pub is_generator: bool,
+    // the code which the instructions able to raise errors were compiled from, by index
+    pub spans: Vec<(usize, Span)>,
+    // the modules compiled into the function, a module coming before the ones importing it
+    pub modules: Vec<InlinedModule>,
+    // the errors of the imports which failed to compile, raised once the import is run
+    pub errors: Vec<LoxError>,
 }
 
 impl CustomClone for FuncSpec {
@@ -343,6 +461,11 @@ impl CustomClone for FuncSpec {
             scope_depth: self.scope_depth.clone(),
             upvalues: self.upvalues.clone(),
             upvalues_ref: self.upvalues_ref.clone(gc),
+            handlers: self.handlers.clone(),
+            is_generator: self.is_generator,
+            spans: self.spans.clone(),
+            modules: self.modules.clone(),
+            errors: self.errors.clone(),
         }
     }
 }
@@ -373,9 +496,23 @@ impl FuncSpec {
             scope_depth: 0,
             upvalues: vec![],
             upvalues_ref: RefCell::new(vec![]),
+            handlers: vec![],
+            is_generator: false,
+            spans: vec![],
+            modules: vec![],
+            errors: vec![],
         }
     }
 
+    // The code the instruction at ip was compiled from, if it can raise errors.
+    pub fn span_at(&self, ip: usize) -> Option<Span> {
+        let pos = self
+            .spans
+            .binary_search_by_key(&ip, |(index, _)| *index)
+            .ok()?;
+        Some(self.spans[pos].1)
+    }
+
     pub fn resolve_local(&mut self, token: &Token) -> i32 {
         for i in (0..self.locals.len()).rev() {
             if self.locals[i].name.lexeme == token.lexeme {
@@ -417,6 +554,26 @@ impl FuncSpec {
     // }
 }
 
+// An exception raised while executing chunks[start..end] resumes at `target`, with the
+// stack cut back to `depth` slots above the frame and the exception pushed on top.
+#[derive(Debug, Clone)]
+pub struct Handler {
+    pub start: usize,
+    pub end: usize,
+    pub target: usize,
+    pub depth: usize,
+}
+
+// The top-level code of a module imported at line_no, compiled into chunks[start..end] of the
+// function importing it, which backtraces show as a frame of its own.
+#[derive(Debug, Clone)]
+pub struct InlinedModule {
+    pub start: usize,
+    pub end: usize,
+    pub name: String,
+    pub line_no: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FunctionType {
     FUNCTION,
@@ -438,16 +595,26 @@ pub enum VmErr {
 pub enum OpCode {
     Return(u32),
     Exit(u32),
-    Constant(usize),
+    Constant(u32, usize),
     // Unary
     Negate(u32),
     Not(u32),
+    BitNot(u32),
+    Stringify(u32),
 
     //Binary
     Add(u32),
     Divide(u32),
     Multiply(u32),
     Subs(u32),
+    IntDivide(u32),
+    Modulo(u32),
+    Power(u32),
+    BitAnd(u32),
+    BitOr(u32),
+    BitXor(u32),
+    ShiftLeft(u32),
+    ShiftRight(u32),
     GreaterThan(u32),
     GreaterThanEq(u32),
     LesserThan(u32),
@@ -478,10 +645,39 @@ pub enum OpCode {
 
     GetSuper(u32, usize),
 
+    //Collections
+    BuildList(u32, usize),
+    BuildMap(u32, usize),
+    GetIndex(u32),
+    SetIndex(u32),
+
+    //Iteration
+    // Pops an iterable and pushes what a for-in loop over it iterates over
+    GetIterator(u32),
+    // Pops an iterator and pushes whether it has items left
+    HasNext(u32),
+    // Pops an iterator and pushes its next item
+    Next(u32),
+    // Pops a value and suspends the generator being run, handing the value to its caller
+    Yield(u32),
+    //Patterns
+    // pops a class and a value, pushing whether the value is an instance of it
+    MatchClass(u32),
+    // pops a value, pushing whether it's a list of that many elements
+    MatchList(u32, usize),
+
     //Control Flow
     JumpIfFalse(u32, usize),
     Jump(u32, usize),
 
+    //Exceptions
+    Throw(u32),
+
+    //Modules
+    Module(u32, usize),
+    // raises the error at that index of the function's errors, an import which failed to compile
+    Raise(u32, usize),
+
     //Fn
     Call(u32, usize),
     Closure(u32, usize),
@@ -491,10 +687,36 @@ pub enum OpCode {
 
     //Helpers
     StackPop,
+    // copies the top n values, in order
+    Duplicate(usize),
+    // moves the top value below the n values under it
+    Bury(usize),
     CloseUpvalue,
     NilVal,
     NoOp,
-    PrintStackTrace,
+    PrintStackTrace(u32),
+}
+
+// The number passed to the int or float native, strings being parsed.
+fn number_arg(val: &Object, native: &str) -> Result<Number, LoxError> {
+    let error = |code, msg: String| LoxError::RuntimeError {
+        lexeme: val.to_string(),
+        line_no: 0,
+        message: msg,
+        code,
+        span: None,
+        backtrace: Box::new([]),
+    };
+    match val {
+        Object::Str(text) => Number::parse(text)
+            .ok_or_else(|| error(ErrorCode::Conversion, "Cannot convert to Num".to_string())),
+        val => val.as_number().ok_or_else(|| {
+            error(
+                ErrorCode::NativeArgument,
+                format!("{} expects a Num or Str", native),
+            )
+        }),
+    }
 }
 
 macro_rules! binary_op {
@@ -506,6 +728,18 @@ macro_rules! binary_op {
     }};
 }
 
+// arithmetic may promote to a BigInt, which is allocated on the heap
+macro_rules! arith_op {
+    ($self:ident, $op:ident, $line_no:ident, $gc: ident) => {{
+        let b = $self.pop_stack($gc).unwrap();
+        let a = $self.pop_stack($gc).unwrap();
+        let x = a.$op(&b, $line_no, $gc)?;
+        $self.push_stack(x);
+    }};
+}
+
+// for dumping the stack while debugging the VM
+#[allow(dead_code)]
 struct PrintVec(Vec<Object>);
 
 impl Display for PrintVec {
@@ -530,11 +764,18 @@ pub struct CallFrame {
     pub func: UniqueRoot<FuncSpec>,
     ip: usize,
     slot: usize,
+    // the generator the frame runs, and what it was resumed for
+    pub generator: Option<(Root<Generator>, Resume)>,
 }
 
 impl CallFrame {
     pub fn new(func: UniqueRoot<FuncSpec>, ip: usize, slot: usize) -> Self {
-        CallFrame { func, ip, slot }
+        CallFrame {
+            func,
+            ip,
+            slot,
+            generator: None,
+        }
     }
 }
 
@@ -547,6 +788,12 @@ pub struct VM<T: SystemCalls> {
     pub globals: HashMap<String, Object>,
     pub open_upvalues: RefCell<Vec<Root<UpValueWrap>>>,
     sys_interface: T,
+    // the value being thrown while the stack unwinds to a handler
+    exception: Option<Object>,
+    tracer: Option<Tracer>,
+    // the prelude's classes, which built-in errors and modules are instances of whatever the
+    // script goes on to bind their names to
+    prelude: HashMap<String, Object>,
 }
 
 impl<T: SystemCalls> VM<T> {
@@ -559,16 +806,63 @@ impl<T: SystemCalls> VM<T> {
             frames: vec![CallFrame::new(gc.get_unique_root(func), 0, 0)],
             open_upvalues: RefCell::new(vec![]),
             sys_interface,
+            exception: None,
+            tracer: None,
+            prelude: HashMap::new(),
         };
 
-        vm.define_native_fn("clock", |_| {
-            let curr_time = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap();
-            Object::Num(curr_time.as_millis() as f64)
+        vm.define_native_fn("clock", |_, _| {
+            let curr_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+            Ok(Object::Num(curr_time.as_millis() as f64))
         });
 
-        vm.define_native_fn("native_add", |args| args[0].add(&args[1], 0).unwrap());
+        vm.define_native_fn("native_add", |args, gc| args[0].add(&args[1], 0, gc));
+
+        vm.define_native_fn("len", |args, _| match &args[0] {
+            Object::Str(val) => Ok(Object::Int(val.chars().count() as i64)),
+            Object::List(val) => Ok(Object::Int(val.len() as i64)),
+            Object::Map(val) => Ok(Object::Int(val.len() as i64)),
+            val => Err(LoxError::RuntimeError {
+                lexeme: val.to_string(),
+                line_no: 0,
+                message: "len expects a Str, List or Map".to_string(),
+                code: ErrorCode::NativeArgument,
+                span: None,
+                backtrace: Box::new([]),
+            }),
+        });
+
+        vm.define_native_fn("int", |args, gc| {
+            number_arg(&args[0], "int")?
+                .to_int()
+                .map(|val| Object::from_number(val, gc))
+                .map_err(|(code, msg)| LoxError::RuntimeError {
+                    code,
+                    lexeme: args[0].to_string(),
+                    line_no: 0,
+                    message: msg.to_string(),
+                    span: None,
+                    backtrace: Box::new([]),
+                })
+        });
+
+        vm.define_native_fn("float", |args, _| {
+            Ok(Object::Num(number_arg(&args[0], "float")?.as_f64()))
+        });
+
+        vm.define_native_fn("keys", |args, gc| {
+            if let Object::Map(val) = &args[0] {
+                return Ok(Object::List(gc.get_root(List::new(val.keys(gc)))));
+            }
+            Err(LoxError::RuntimeError {
+                lexeme: args[0].to_string(),
+                line_no: 0,
+                message: "keys expects a Map".to_string(),
+                code: ErrorCode::NativeArgument,
+                span: None,
+                backtrace: Box::new([]),
+            })
+        });
 
         vm
     }
@@ -578,9 +872,171 @@ impl<T: SystemCalls> VM<T> {
             .insert(name.as_ref().to_string(), Object::NativeFunction(fn_def));
     }
 
+    // Sets another script up to run, keeping the globals and constants of the ones before it.
+    pub fn load_script(&mut self, func: FuncSpec, gc: &Heap) {
+        self.truncate_stack(0, gc);
+        self.frames = vec![CallFrame::new(gc.get_unique_root(func), 0, 0)];
+    }
+
+    pub fn sys_interface(&mut self) -> &mut T {
+        &mut self.sys_interface
+    }
+
+    // Where instructions are logged when the VM is run with is_debug set.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.tracer = Some(tracer);
+    }
+
+    fn trace(&mut self) {
+        if let Some(tracer) = &mut self.tracer {
+            let frame = self.frames.last().unwrap();
+            tracer.trace(
+                self.frames.len(),
+                &frame.func,
+                frame.ip,
+                frame.slot,
+                &self.constant_pool,
+                &self.stack,
+            );
+        }
+    }
+
+    pub fn run(&mut self, is_debug: bool, gc: &Heap) -> Result<(), LoxError> {
+        loop {
+            match self.execute(is_debug, gc) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    let frames = self.backtrace();
+                    let frame = self.frames.last().unwrap();
+                    let err = match frame.func.span_at(frame.ip - 1) {
+                        Some(span) => err.at(span),
+                        None => err,
+                    };
+                    self.unwind(err, gc)
+                        .map_err(|err| err.with_backtrace(frames))?
+                }
+            }
+        }
+    }
+
+    // The frames being run innermost first, each at the line of the instruction it's running.
+    // A frame running the code of a module it imported gets a frame for each module below it.
+    fn backtrace(&self) -> Vec<TraceFrame> {
+        let mut frames = vec![];
+        for frame in self.frames.iter().rev() {
+            let name = match (&frame.func.fn_type, &frame.func.name) {
+                (FunctionType::SCRIPT, _) => "script".to_string(),
+                (FunctionType::LAMBDA, _) => "Lambda".to_string(),
+                (_, name) => name.clone().unwrap_or_default(),
+            };
+            let ip = frame.ip - 1;
+            let mut line_no = debug::current_line(&frame.func, ip).unwrap_or(0);
+            for module in &frame.func.modules {
+                if module.start <= ip && ip < module.end {
+                    frames.push(TraceFrame::new(module.name.clone(), line_no));
+                    line_no = module.line_no;
+                }
+            }
+            frames.push(TraceFrame::new(name, line_no));
+        }
+        frames
+    }
+
+    // Pops frames until one has a handler covering its current instruction and
+    // jumps there, or hands the error back once no frame is left.
+    fn unwind(&mut self, err: LoxError, gc: &Heap) -> Result<(), LoxError> {
+        let exception = match self.exception.take() {
+            Some(exception) => exception,
+            None => self.error_instance(&err, gc),
+        };
+        while let Some(frame) = self.frames.last() {
+            let ip = frame.ip - 1;
+            let handler = frame
+                .func
+                .handlers
+                .iter()
+                .find(|handler| handler.start <= ip && ip < handler.end)
+                .cloned();
+            let slot = frame.slot;
+            if let Some(handler) = handler {
+                self.truncate_stack(slot + handler.depth, gc);
+                self.frames.last_mut().unwrap().ip = handler.target;
+                self.push_stack(exception);
+                return Ok(());
+            }
+            self.truncate_stack(slot, gc);
+            if let Some((generator, _)) = self.frames.pop().unwrap().generator {
+                generator.state.set(GeneratorState::Done);
+            }
+        }
+        Err(err)
+    }
+
+    // The exported global of a module which a property of its namespace reads.
+    fn module_global(&self, inst: &Instance, prop: &str, gc: &Heap) -> Option<Object> {
+        let prefix = inst.module.as_ref()?;
+        let value = self.globals.get(&format!("{}{}", prefix, prop))?;
+        Some(value.clone(gc))
+    }
+
+    // Keeps hold of the globals the prelude defined, once it has run.
+    pub fn keep_prelude(&mut self, names: &[String], gc: &Heap) {
+        for name in names {
+            if let Some(val) = self.globals.get(name) {
+                self.prelude.insert(name.clone(), val.clone(gc));
+            }
+        }
+    }
+
+    // Runtime errors are caught as instances of the prelude's Error classes.
+    fn error_instance(&self, err: &LoxError, gc: &Heap) -> Object {
+        let message = Object::Str(err.error_message());
+        if let Some(Object::ClassDef(class)) = self.prelude.get(err.error_class()) {
+            let inst = Instance::new(class.clone(gc));
+            inst.set(String::from("message"), message);
+            if let LoxError::RuntimeError { line_no, .. } = err {
+                inst.set(String::from("line"), Object::Int(*line_no as i64));
+            }
+            return Object::InstanceDef(gc.get_root(inst));
+        }
+        message
+    }
+
+    // An uncaught thrown value is reported like any other runtime error.
+    fn uncaught(value: &Object, line_no: u32, gc: &Heap) -> LoxError {
+        if let Object::InstanceDef(inst) = value {
+            if let Some(message) = inst.get(&String::from("message"), gc) {
+                let line_no = match inst.get(&String::from("line"), gc) {
+                    Some(Object::Int(line)) => line as u32,
+                    Some(Object::Num(line)) => line as u32,
+                    _ => line_no,
+                };
+                let class = inst.get_class_name();
+                let message = format!("Uncaught {}: {}", class, message);
+                return LoxError::RuntimeError {
+                    lexeme: class,
+                    line_no,
+                    message,
+                    code: ErrorCode::Uncaught,
+                    span: None,
+                    backtrace: Box::new([]),
+                };
+            }
+        }
+        let message = format!("Uncaught exception: {}", value);
+        LoxError::RuntimeError {
+            lexeme: value.to_string(),
+            line_no,
+            message,
+            code: ErrorCode::Uncaught,
+            span: None,
+            backtrace: Box::new([]),
+        }
+    }
+
     //TODO: try prefetching
     //TODO: this whole thing barely does any error handling
-    pub fn run(&mut self, is_debug: bool, gc: &Heap) -> Result<(), LoxError> {
+    fn execute(&mut self, is_debug: bool, gc: &Heap) -> Result<(), LoxError> {
         use OpCode::*;
 
         // let frame = self.frames.last_mut().unwrap();
@@ -590,39 +1046,70 @@ impl<T: SystemCalls> VM<T> {
                 // gc.collect_free(self);
             }
             i += 1;
-            //TODO: handle debugging
-            // if is_debug {
-            //     disassemble_inst(&self, self.ip);
-            // }
+            if is_debug {
+                self.trace();
+            }
             let ip = self.frames.last_mut().unwrap().ip;
             self.frames.last_mut().unwrap().ip += 1;
 
             // println!("exec: {:?}", self.frames.last_mut().unwrap().func.chunks[ip]);
             match self.frames.last_mut().unwrap().func.chunks[ip] {
-                Constant(pos) => {
+                Constant(_, pos) => {
                     // this will create new copies everytime. think over
                     self.push_stack(self.constant_pool[pos].clone(&gc))
                 }
                 Exit(_) => return Ok(()),
                 Negate(line_no) => {
                     let a = self.pop_stack(gc).unwrap();
-                    self.push_stack(a.neg(line_no)?);
+                    self.push_stack(a.neg(line_no, gc)?);
                 }
                 Not(line_no) => {
                     let a = self.pop_stack(gc).unwrap();
                     self.push_stack(a.not(line_no)?);
                 }
+                BitNot(line_no) => {
+                    let a = self.pop_stack(gc).unwrap();
+                    self.push_stack(a.bit_not(line_no, gc)?);
+                }
+                Stringify(_) => {
+                    let a = self.pop_stack(gc).unwrap();
+                    self.push_stack(Object::Str(a.to_string()));
+                }
                 Add(line_no) => {
-                    binary_op!(self, add, line_no, gc)
+                    arith_op!(self, add, line_no, gc)
                 }
                 Divide(line_no) => {
-                    binary_op!(self, div, line_no, gc)
+                    arith_op!(self, div, line_no, gc)
                 }
                 Multiply(line_no) => {
-                    binary_op!(self, mul, line_no, gc)
+                    arith_op!(self, mul, line_no, gc)
                 }
                 Subs(line_no) => {
-                    binary_op!(self, sub, line_no, gc)
+                    arith_op!(self, sub, line_no, gc)
+                }
+                IntDivide(line_no) => {
+                    arith_op!(self, int_div, line_no, gc)
+                }
+                Modulo(line_no) => {
+                    arith_op!(self, modulo, line_no, gc)
+                }
+                Power(line_no) => {
+                    arith_op!(self, pow, line_no, gc)
+                }
+                BitAnd(line_no) => {
+                    arith_op!(self, bit_and, line_no, gc)
+                }
+                BitOr(line_no) => {
+                    arith_op!(self, bit_or, line_no, gc)
+                }
+                BitXor(line_no) => {
+                    arith_op!(self, bit_xor, line_no, gc)
+                }
+                ShiftLeft(line_no) => {
+                    arith_op!(self, shl, line_no, gc)
+                }
+                ShiftRight(line_no) => {
+                    arith_op!(self, shr, line_no, gc)
                 }
                 GreaterThan(line_no) => {
                     binary_op!(self, gt, line_no, gc)
@@ -658,16 +1145,32 @@ impl<T: SystemCalls> VM<T> {
                 StackPop => {
                     self.pop_stack(gc);
                 }
+                Duplicate(count) => {
+                    let len = self.stack.len();
+                    for pos in len - count..len {
+                        let val = self.stack[pos].clone(gc);
+                        self.push_stack(val);
+                    }
+                }
+                Bury(count) => {
+                    let val = self.pop_stack(gc).unwrap();
+                    let pos = self.stack.len() - count;
+                    self.stack.insert(pos, val);
+                    self.sp += 1;
+                }
                 DefineGlobal(line_no, pos) => {
                     let name = self.constant_pool[pos].to_string();
                     if let Some(val) = self.stack.last() {
                         self.globals.insert(name, val.clone(&gc));
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "dg".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "dg".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 GetGlobal(line_no, pos) => {
@@ -676,30 +1179,40 @@ impl<T: SystemCalls> VM<T> {
                     if let Some(val) = self.globals.get(&name) {
                         self.push_stack(val.clone(&gc));
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "gg".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: name,
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "Undefined variable".to_string(),
+                            code: ErrorCode::Undefined,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 SetGlobal(line_no, pos) => {
                     //TODO: actually check if it's a string
                     let name = self.constant_pool[pos].to_string();
                     if let Some(val) = self.stack.last() {
-                        if self.globals.insert(name, val.clone(&gc)).is_none() {
-                            return Err(LoxError::RuntimeError(
-                                "unknown".to_string(),
+                        if !self.globals.contains_key(&name) {
+                            return Err(LoxError::RuntimeError {
+                                lexeme: name,
                                 line_no,
-                                "".to_string(),
-                            ));
+                                message: "Undefined variable".to_string(),
+                                code: ErrorCode::Undefined,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
                         }
+                        self.globals.insert(name, val.clone(gc));
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "sg".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "sg".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 GetLocal(line_no, pos) => {
@@ -707,45 +1220,78 @@ impl<T: SystemCalls> VM<T> {
                     if let Some(val) = self.stack.get(self.frames.last_mut().unwrap().slot + pos) {
                         self.push_stack(val.clone(&gc));
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "gl".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "gl".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 SetLocal(line_no, pos) => {
                     if let Some(val) = self.stack.last() {
                         self.stack[self.frames.last_mut().unwrap().slot + pos] = val.clone(&gc);
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "sl".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "sl".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 GetProperty(line_no, pos) => {
                     // println!("stacktrace: {}", PrintVec(self.stack.clone(&gc)));
-                    if let Some(Object::InstanceDef(inst)) = self.pop_stack(gc) {
+                    if let Some(Object::Generator(generator)) = self.stack.last() {
+                        let prop = self.constant_pool[pos].to_string();
+                        let generator = generator.clone(gc);
+                        self.pop_stack(gc);
+                        match Resume::from_name(&prop) {
+                            Some(resume) => {
+                                self.push_stack(Object::GeneratorMethod(generator, resume))
+                            }
+                            None => {
+                                return Err(LoxError::RuntimeError {
+                                    lexeme: prop,
+                                    line_no,
+                                    message: "Undefined property".to_string(),
+                                    code: ErrorCode::Undefined,
+                                    span: None,
+                                    backtrace: Box::new([]),
+                                })
+                            }
+                        }
+                    } else if let Some(Object::InstanceDef(inst)) = self.pop_stack(gc) {
                         // TODO: String/identifier check
                         let prop = self.constant_pool[pos].to_string();
-                        if let Some(field) = inst.get(&prop, gc) {
+                        if let Some(field) = self.module_global(&inst, &prop, gc) {
+                            self.push_stack(field);
+                        } else if let Some(field) = inst.get(&prop, gc) {
                             self.push_stack(field);
                         } else if self.bind_method(&inst, &inst.class, &prop, gc).is_ok() {
                         } else {
-                            return Err(LoxError::RuntimeError(
-                                "gp".to_string(),
+                            return Err(LoxError::RuntimeError {
+                                lexeme: prop,
                                 line_no,
-                                "".to_string(),
-                            ));
+                                message: "Undefined property".to_string(),
+                                code: ErrorCode::Undefined,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
                         }
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "gp".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: self.constant_pool[pos].to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "Only Instance have properties".to_string(),
+                            code: ErrorCode::NotInstance,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 SetProperty(line_no, pos) => {
@@ -753,15 +1299,29 @@ impl<T: SystemCalls> VM<T> {
                     if let Some(Object::InstanceDef(inst)) = &self.stack.last() {
                         // TODO: String/identifier check
                         let prop = self.constant_pool[pos].to_string();
-                        inst.set(prop, val.clone(gc));
+                        // the exported globals of a module are assigned through its namespace
+                        let global = inst
+                            .module
+                            .as_ref()
+                            .map(|prefix| format!("{}{}", prefix, prop))
+                            .filter(|global| self.globals.contains_key(global));
+                        match global {
+                            Some(global) => {
+                                self.globals.insert(global, val.clone(gc));
+                            }
+                            None => inst.set(prop, val.clone(gc)),
+                        }
                         self.pop_stack(gc);
                         self.push_stack(val);
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "sp".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: self.constant_pool[pos].to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "Only Instances have fields".to_string(),
+                            code: ErrorCode::NotInstance,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 SetUpvalue(line_no, pos) => {
@@ -788,11 +1348,14 @@ impl<T: SystemCalls> VM<T> {
                             _ => {}
                         }
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "su".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "su".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 GetUpvalue(line_no, pos) => {
@@ -828,98 +1391,20 @@ impl<T: SystemCalls> VM<T> {
                         self.frames.last_mut().unwrap().ip = offset;
                     } else if let Some(Object::Bool(true)) = self.stack.last() {
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "jif".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "jif".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 Jump(line_no, offset) => {
                     self.frames.last_mut().unwrap().ip = offset;
                 }
-                Call(line_no, args_count) => {
-                    // println!("stacktrace: {}", PrintVec(self.stack.clone(&gc)));
-                    let stack_len = self.stack.len() - args_count - 1;
-                    // let frame = self.frames.last().unwrap();
-                    if let Object::Closure(func) = &self.stack[stack_len] {
-                        if func.arity != args_count as u32 {
-                            return Err(LoxError::RuntimeError(
-                                "arg cnt fn".to_string(),
-                                0,
-                                "".to_string(),
-                            ));
-                        }
-                        self.frames
-                            .push(CallFrame::new(gc.clone_unique_root(func), 0, stack_len));
-                        // println!("upvals {:?}: {:?}", func.name, func.upvalues);
-                        // println!("open upvals {:?}: {:?}", func.name, self.open_upvalues);
-                    } else if let Object::NativeFunction(func) = self.stack[stack_len].clone(&gc) {
-                        // TODO: args count check
-                        let ret_val =
-                            func(self.to_vec(&self.stack[stack_len..(stack_len + args_count)], gc));
-
-                        for _ in 0..(args_count) {
-                            self.stack.pop();
-                        }
-
-                        self.stack.push(ret_val);
-                    } else if let Object::ClassDef(val) = &self.stack[stack_len] {
-                        let mut init = None;
-                        // println!("arg {} {}", val.name, args_count);
-                        if let Some(Object::Closure(initializer)) =
-                            val.get_method(&String::from("init"), gc)
-                        {
-                            if initializer.arity != args_count as u32 {
-                                return Err(LoxError::RuntimeError(
-                                    "arg cnt cl".to_string(),
-                                    0,
-                                    "".to_string(),
-                                ));
-                            }
-                            init = Some(initializer);
-                        } else if args_count != 0 {
-                            return Err(LoxError::RuntimeError(
-                                "arg cnt cli".to_string(),
-                                0,
-                                "".to_string(),
-                            ));
-                        }
-                        self.replace_top_stack(
-                            Object::InstanceDef(gc.get_root(Instance::new(val.clone(gc)))),
-                            args_count,
-                        );
-                        if let Some(func) = &init {
-                            // TODO: Args are not parsed
-                            self.frames.push(CallFrame::new(
-                                gc.clone_unique_root(func),
-                                0,
-                                stack_len,
-                            ));
-                        }
-                        // TODO: arg count should be zero here
-                    } else if let Object::InstanceBindDef(val) = &self.stack[stack_len] {
-                        if val.method.arity != args_count as u32 {
-                            return Err(LoxError::RuntimeError(
-                                "arg cnt idef".to_string(),
-                                0,
-                                "".to_string(),
-                            ));
-                        }
-                        self.frames.push(CallFrame::new(
-                            gc.clone_unique_root(&val.method),
-                            0,
-                            stack_len,
-                        ));
-                        self.replace_top_stack(val.receiver.clone(gc), args_count);
-                    } else {
-                        return Err(LoxError::RuntimeError(
-                            "call".to_string(),
-                            0,
-                            "".to_string(),
-                        ));
-                    }
-                }
+                Call(line_no, args_count) => self.call(line_no, args_count, gc)?,
                 Closure(line_no, pos) => {
                     if let Object::Closure(func) = self.constant_pool[pos].clone(&gc) {
                         for up_val in func.upvalues.iter() {
@@ -950,7 +1435,14 @@ impl<T: SystemCalls> VM<T> {
                         }
                         self.push_stack(Object::Closure(func));
                     } else {
-                        return Err(LoxError::RuntimeError("cls".to_string(), 0, "".to_string()));
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "cls".to_string(),
+                            line_no: 0,
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 Return(line_no) => {
@@ -972,10 +1464,47 @@ impl<T: SystemCalls> VM<T> {
 
                     //removing the function object
                     // self.pop_stack(gc);
-                    self.frames.pop();
+                    let val = match self.frames.pop().unwrap().generator {
+                        // a return ends the generator, and what it returns is dropped
+                        Some((generator, resume)) => {
+                            generator.state.set(GeneratorState::Done);
+                            match resume {
+                                Resume::HasNext => Object::Bool(false),
+                                Resume::Next => Object::Nil,
+                            }
+                        }
+                        None => val,
+                    };
                     self.push_stack(val);
                 }
                 NoOp => {}
+                Throw(line_no) => {
+                    let val = self.pop_stack(gc).unwrap();
+                    let err = Self::uncaught(&val, line_no, gc);
+                    self.exception = Some(val);
+                    return Err(err);
+                }
+                Module(line_no, pos) => {
+                    // a module's globals are the ones named with its prefix
+                    let prefix = self.constant_pool[pos].to_string();
+                    let module = if let Some(Object::ClassDef(class)) = self.prelude.get("Module") {
+                        let inst = Instance::module(class.clone(gc), prefix);
+                        Object::InstanceDef(gc.get_root(inst))
+                    } else {
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "Module".to_string(),
+                            line_no,
+                            message: "Undefined variable".to_string(),
+                            code: ErrorCode::Undefined,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
+                    };
+                    self.push_stack(module);
+                }
+                Raise(_, pos) => {
+                    return Err(self.frames.last().unwrap().func.errors[pos].clone());
+                }
                 CloseUpvalue => {
                     // let ln = self.frames.last().unwrap().func.upvalues_ref.borrow().len();
                     // for i in (0..ln).rev() {
@@ -993,8 +1522,9 @@ impl<T: SystemCalls> VM<T> {
                     self.close_value(i, gc);
                     self.pop_stack(gc);
                 }
-                PrintStackTrace => {
-                    println!("stacktrace: {}", PrintVec(self.stack.clone(&gc)));
+                PrintStackTrace(_) => {
+                    let trace = Object::Str(format_backtrace(&self.backtrace()));
+                    self.sys_interface.print(&trace, gc);
                 }
                 ClassDef(line_no, pos) => {
                     let name = self.constant_pool[pos].to_string();
@@ -1007,11 +1537,14 @@ impl<T: SystemCalls> VM<T> {
                         let prop = self.constant_pool[pos].to_string();
                         class.set_method(prop, method);
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "mdef".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "mdef".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
                 Inherit(line_no) => {
@@ -1019,18 +1552,190 @@ impl<T: SystemCalls> VM<T> {
                         if let Some(Object::ClassDef(super_class)) = self.stack.last() {
                             child_class.add_super_class(&super_class, gc);
                         } else {
-                            return Err(LoxError::RuntimeError(
-                                "inh".to_string(),
+                            return Err(LoxError::RuntimeError {
+                                lexeme: "inh".to_string(),
                                 line_no,
-                                "".to_string(),
-                            ));
+                                message: "".to_string(),
+                                code: ErrorCode::SuperclassNotClass,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
                         }
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "inh".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "inh".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::SuperclassNotClass,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
+                    }
+                }
+                BuildList(_, count) => {
+                    let start = self.stack.len() - count;
+                    let values = self.to_vec(&self.stack[start..], gc);
+                    for _ in 0..count {
+                        self.pop_stack(gc);
+                    }
+                    self.push_stack(Object::List(gc.get_root(List::new(values))));
+                }
+                BuildMap(line_no, count) => {
+                    let start = self.stack.len() - count * 2;
+                    let values = self.to_vec(&self.stack[start..], gc);
+                    for _ in 0..count * 2 {
+                        self.pop_stack(gc);
+                    }
+                    let map = Map::new();
+                    let mut values = values.into_iter();
+                    while let (Some(key), Some(val)) = (values.next(), values.next()) {
+                        map.set(Self::map_key(&key, line_no)?, key, val);
+                    }
+                    self.push_stack(Object::Map(gc.get_root(map)));
+                }
+                GetIndex(line_no) => {
+                    let index = self.pop_stack(gc).unwrap();
+                    match self.pop_stack(gc) {
+                        Some(Object::List(list)) => {
+                            let pos = Self::list_index(&index, list.len(), line_no)?;
+                            self.push_stack(list.get(pos, gc).unwrap());
+                        }
+                        Some(Object::Map(map)) => {
+                            let key = Self::map_key(&index, line_no)?;
+                            self.push_stack(map.get(&key, gc).unwrap_or(Object::Nil));
+                        }
+                        _ => {
+                            return Err(LoxError::RuntimeError {
+                                lexeme: "[".to_string(),
+                                line_no,
+                                message: "Only lists and maps can be indexed".to_string(),
+                                code: ErrorCode::NotIndexable,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
+                        }
+                    }
+                }
+                SetIndex(line_no) => {
+                    let val = self.pop_stack(gc).unwrap();
+                    let index = self.pop_stack(gc).unwrap();
+                    match self.pop_stack(gc) {
+                        Some(Object::List(list)) => {
+                            let pos = Self::list_index(&index, list.len(), line_no)?;
+                            list.set(pos, val.clone(gc));
+                        }
+                        Some(Object::Map(map)) => {
+                            let key = Self::map_key(&index, line_no)?;
+                            map.set(key, index, val.clone(gc));
+                        }
+                        _ => {
+                            return Err(LoxError::RuntimeError {
+                                lexeme: "[".to_string(),
+                                line_no,
+                                message: "Only lists and maps can be indexed".to_string(),
+                                code: ErrorCode::NotIndexable,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
+                        }
+                    }
+                    self.push_stack(val);
+                }
+                MatchClass(line_no) => {
+                    let class = self.pop_stack(gc).unwrap();
+                    let val = self.pop_stack(gc).unwrap();
+                    let class = match class {
+                        Object::ClassDef(class) => class,
+                        class => {
+                            return Err(LoxError::RuntimeError {
+                                lexeme: class.to_string(),
+                                line_no,
+                                message: "Only classes can be matched against".to_string(),
+                                code: ErrorCode::NotMatchable,
+                                span: None,
+                                backtrace: Box::new([]),
+                            });
+                        }
+                    };
+                    let is_instance = match val {
+                        Object::InstanceDef(inst) => inst.class.is_subclass_of(&class),
+                        _ => false,
+                    };
+                    self.push_stack(Object::Bool(is_instance));
+                }
+                MatchList(_, count) => {
+                    let val = self.pop_stack(gc).unwrap();
+                    let is_list = match val {
+                        Object::List(list) => list.len() == count,
+                        _ => false,
+                    };
+                    self.push_stack(Object::Bool(is_list));
+                }
+                GetIterator(line_no) => {
+                    if let Some(Object::InstanceDef(_)) = self.stack.last() {
+                        self.invoke(line_no, "iterator", gc)?;
+                    } else if let Some(Object::Generator(_)) = self.stack.last() {
+                        // generators are their own iterators
+                    } else {
+                        let mut items: Vec<Object> = match self.pop_stack(gc).unwrap() {
+                            Object::List(list) => {
+                                list.values.borrow().iter().map(|v| v.clone(gc)).collect()
+                            }
+                            Object::Str(string) => string
+                                .chars()
+                                .map(|ch| Object::Str(ch.to_string()))
+                                .collect(),
+                            Object::Map(map) => map.keys(gc),
+                            _ => return Err(LoxError::RuntimeError {
+                                lexeme: "for".to_string(),
+                                line_no,
+                                message:
+                                    "Only lists, maps, strings and instances can be iterated over"
+                                        .to_string(),
+                                code: ErrorCode::NotIterable,
+                                span: None,
+                                backtrace: Box::new([]),
+                            }),
+                        };
+                        items.reverse();
+                        self.push_stack(Object::Iterator(gc.get_root(List::new(items))));
+                    }
+                }
+                HasNext(line_no) => match self.pop_stack(gc).unwrap() {
+                    Object::Iterator(iter) => self.push_stack(Object::Bool(!iter.is_empty())),
+                    Object::Generator(generator) => {
+                        self.resume(generator, Resume::HasNext, line_no, gc)?
+                    }
+                    iter => {
+                        self.push_stack(iter);
+                        self.invoke(line_no, "hasNext", gc)?;
+                    }
+                },
+                Next(line_no) => match self.pop_stack(gc).unwrap() {
+                    Object::Iterator(iter) => {
+                        let item = iter.values.borrow_mut().pop().unwrap();
+                        self.push_stack(item);
+                    }
+                    Object::Generator(generator) => {
+                        self.resume(generator, Resume::Next, line_no, gc)?
+                    }
+                    iter => {
+                        self.push_stack(iter);
+                        self.invoke(line_no, "next", gc)?;
+                    }
+                },
+                Yield(_) => {
+                    let val = self.pop_stack(gc).unwrap();
+                    let frame = self.frames.pop().unwrap();
+                    let (generator, resume) = frame.generator.unwrap();
+                    self.suspend(&generator, frame.slot, gc);
+                    generator.ip.set(frame.ip);
+                    match resume {
+                        Resume::HasNext => {
+                            generator.next.replace(Some(val));
+                            self.push_stack(Object::Bool(true));
+                        }
+                        Resume::Next => self.push_stack(val),
                     }
                 }
                 GetSuper(line_no, pos) => {
@@ -1045,17 +1750,55 @@ impl<T: SystemCalls> VM<T> {
                             // break;
                         }
                     } else {
-                        return Err(LoxError::RuntimeError(
-                            "gsup".to_string(),
+                        return Err(LoxError::RuntimeError {
+                            lexeme: "gsup".to_string(),
                             line_no,
-                            "".to_string(),
-                        ));
+                            message: "".to_string(),
+                            code: ErrorCode::Run,
+                            span: None,
+                            backtrace: Box::new([]),
+                        });
                     }
                 }
             };
         }
     }
 
+    fn list_index(index: &Object, len: usize, line_no: u32) -> Result<usize, LoxError> {
+        if let Some(num) = index.as_number() {
+            if let Some(index) = num.as_int().filter(|index| (0..len as i64).contains(index)) {
+                return Ok(index as usize);
+            }
+            return Err(LoxError::RuntimeError {
+                lexeme: index.to_string(),
+                line_no,
+                message: "Index out of bounds".to_string(),
+                code: ErrorCode::IndexOutOfBounds,
+                span: None,
+                backtrace: Box::new([]),
+            });
+        }
+        Err(LoxError::RuntimeError {
+            lexeme: index.to_string(),
+            line_no,
+            message: "Index not a Num".to_string(),
+            code: ErrorCode::IndexType,
+            span: None,
+            backtrace: Box::new([]),
+        })
+    }
+
+    fn map_key(key: &Object, line_no: u32) -> Result<MapKey, LoxError> {
+        MapKey::from_object(key).ok_or_else(|| LoxError::RuntimeError {
+            lexeme: key.to_string(),
+            line_no,
+            message: "Map keys must be Str, Num, Bool or Instance".to_string(),
+            code: ErrorCode::MapKey,
+            span: None,
+            backtrace: Box::new([]),
+        })
+    }
+
     fn to_vec(&self, slice: &[Object], gc: &Heap) -> Vec<Object> {
         let mut res = vec![];
         for it in slice {
@@ -1096,6 +1839,14 @@ impl<T: SystemCalls> VM<T> {
         }
     }
 
+    // pops everything above `len`, closing the upvalues that point into it
+    fn truncate_stack(&mut self, len: usize, gc: &Heap) {
+        while self.stack.len() > len {
+            self.close_value(self.stack.len() - 1, gc);
+            self.pop_stack(gc);
+        }
+    }
+
     pub fn push_stack(&mut self, val: Object) {
         self.sp += 1;
         self.stack.push(val);
@@ -1131,6 +1882,270 @@ impl<T: SystemCalls> VM<T> {
         x
     }
 
+    // Calls the value below the top args_count values on the stack with them as its arguments.
+    fn call(&mut self, line_no: u32, args_count: usize, gc: &Heap) -> Result<(), LoxError> {
+        // println!("stacktrace: {}", PrintVec(self.stack.clone(&gc)));
+        let stack_len = self.stack.len() - args_count - 1;
+        // let frame = self.frames.last().unwrap();
+        if let Object::Closure(func) = &self.stack[stack_len] {
+            if func.arity != args_count as u32 {
+                return Err(LoxError::RuntimeError {
+                    lexeme: "arg cnt fn".to_string(),
+                    line_no,
+                    message: "No. of args don\'t match".to_string(),
+                    code: ErrorCode::ArgumentCount,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
+            }
+            let func = gc.clone_unique_root(func);
+            if func.is_generator {
+                return self.generator(func, stack_len, gc);
+            }
+            self.frames.push(CallFrame::new(func, 0, stack_len));
+            // println!("upvals {:?}: {:?}", func.name, func.upvalues);
+            // println!("open upvals {:?}: {:?}", func.name, self.open_upvalues);
+        } else if let Object::NativeFunction(func) = self.stack[stack_len].clone(&gc) {
+            // TODO: args count check
+            let args = self.to_vec(&self.stack[(stack_len + 1)..], gc);
+            let ret_val = match func(args, gc) {
+                // natives don't know where they were called from
+                Err(LoxError::RuntimeError {
+                    lexeme: literal,
+                    line_no: 0,
+                    message: msg,
+                    code,
+                    ..
+                }) => {
+                    return Err(LoxError::RuntimeError {
+                        lexeme: literal,
+                        line_no,
+                        message: msg,
+                        code,
+                        span: None,
+                        backtrace: Box::new([]),
+                    })
+                }
+                res => res?,
+            };
+
+            // pops the args along with the native function itself
+            for _ in 0..(args_count + 1) {
+                self.pop_stack(gc);
+            }
+
+            self.push_stack(ret_val);
+        } else if let Object::ClassDef(val) = &self.stack[stack_len] {
+            let mut init = None;
+            // println!("arg {} {}", val.name, args_count);
+            if let Some(Object::Closure(initializer)) = val.get_method(&String::from("init"), gc) {
+                if initializer.arity != args_count as u32 {
+                    return Err(LoxError::RuntimeError {
+                        lexeme: "arg cnt cl".to_string(),
+                        line_no,
+                        message: "No. of args don\'t match".to_string(),
+                        code: ErrorCode::ArgumentCount,
+                        span: None,
+                        backtrace: Box::new([]),
+                    });
+                }
+                init = Some(initializer);
+            } else if args_count != 0 {
+                return Err(LoxError::RuntimeError {
+                    lexeme: "arg cnt cli".to_string(),
+                    line_no,
+                    message: "No. of args don\'t match".to_string(),
+                    code: ErrorCode::ArgumentCount,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
+            }
+            self.replace_top_stack(
+                Object::InstanceDef(gc.get_root(Instance::new(val.clone(gc)))),
+                args_count,
+            );
+            if let Some(func) = &init {
+                // TODO: Args are not parsed
+                self.frames
+                    .push(CallFrame::new(gc.clone_unique_root(func), 0, stack_len));
+            }
+            // TODO: arg count should be zero here
+        } else if let Object::InstanceBindDef(val) = &self.stack[stack_len] {
+            if val.method.arity != args_count as u32 {
+                return Err(LoxError::RuntimeError {
+                    lexeme: "arg cnt idef".to_string(),
+                    line_no,
+                    message: "No. of args don\'t match".to_string(),
+                    code: ErrorCode::ArgumentCount,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
+            }
+            let func = gc.clone_unique_root(&val.method);
+            self.replace_top_stack(val.receiver.clone(gc), args_count);
+            if func.is_generator {
+                return self.generator(func, stack_len, gc);
+            }
+            self.frames.push(CallFrame::new(func, 0, stack_len));
+        } else if let Object::GeneratorMethod(generator, resume) = &self.stack[stack_len] {
+            if args_count != 0 {
+                return Err(LoxError::RuntimeError {
+                    lexeme: resume.name().to_string(),
+                    line_no,
+                    message: "No. of args don\'t match".to_string(),
+                    code: ErrorCode::ArgumentCount,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
+            }
+            let (generator, resume) = (generator.clone(gc), *resume);
+            self.pop_stack(gc);
+            self.resume(generator, resume, line_no, gc)?;
+        } else {
+            return Err(LoxError::RuntimeError {
+                lexeme: self.stack[stack_len].to_string(),
+                line_no,
+                message: "Not a function".to_string(),
+                code: ErrorCode::NotCallable,
+                span: None,
+                backtrace: Box::new([]),
+            });
+        }
+        Ok(())
+    }
+    // Moves the function being called and its arguments, from slot on, into a new generator.
+    fn generator(
+        &mut self,
+        func: UniqueRoot<FuncSpec>,
+        slot: usize,
+        gc: &Heap,
+    ) -> Result<(), LoxError> {
+        let mut stack = vec![];
+        while self.stack.len() > slot {
+            stack.push(self.pop_stack(gc).unwrap());
+        }
+        stack.reverse();
+        let generator = Generator::new(func, stack);
+        self.push_stack(Object::Generator(gc.get_root(generator)));
+        Ok(())
+    }
+
+    // Runs the generator up to its next yield, unless a hasNext already has, leaving the value
+    // that yield hands over on the stack once it gets there.
+    fn resume(
+        &mut self,
+        generator: Root<Generator>,
+        resume: Resume,
+        line_no: u32,
+        gc: &Heap,
+    ) -> Result<(), LoxError> {
+        let next = generator.next.borrow_mut().take();
+        if let Some(val) = next {
+            match resume {
+                Resume::HasNext => {
+                    generator.next.replace(Some(val));
+                    self.push_stack(Object::Bool(true));
+                }
+                Resume::Next => self.push_stack(val),
+            }
+            return Ok(());
+        }
+        match generator.state.get() {
+            GeneratorState::Done => {
+                self.push_stack(match resume {
+                    Resume::HasNext => Object::Bool(false),
+                    Resume::Next => Object::Nil,
+                });
+                return Ok(());
+            }
+            GeneratorState::Running => {
+                return Err(LoxError::RuntimeError {
+                    lexeme: generator.name(),
+                    line_no,
+                    message: "Generator is already running".to_string(),
+                    code: ErrorCode::GeneratorRunning,
+                    span: None,
+                    backtrace: Box::new([]),
+                })
+            }
+            GeneratorState::Suspended => {}
+        }
+        // the slots go back on top of the stack, and the upvalues pointing into them reopen
+        let slot = self.stack.len();
+        for val in generator.stack.take() {
+            self.push_stack(val);
+        }
+        for (offset, upvalue) in generator.upvalues.take() {
+            if let UpValue::Closed(val) = upvalue.get(gc) {
+                self.stack[slot + offset] = val;
+            }
+            upvalue.update(UpValue::Open(slot + offset));
+            self.open_upvalues.borrow_mut().push(upvalue);
+        }
+        generator.state.set(GeneratorState::Running);
+        let mut frame = CallFrame::new(
+            gc.clone_unique_root(&generator.func),
+            generator.ip.get(),
+            slot,
+        );
+        frame.generator = Some((generator, resume));
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    // Moves the slots of a generator's frame, from slot on, off the stack and into it. The
+    // upvalues pointing into them are closed until it's resumed.
+    fn suspend(&mut self, generator: &Root<Generator>, slot: usize, gc: &Heap) {
+        let mut upvalues = vec![];
+        self.open_upvalues
+            .borrow_mut()
+            .retain(|upvalue| match upvalue.get(gc) {
+                UpValue::Open(pos) if pos >= slot => {
+                    upvalue.update(UpValue::Closed(self.stack[pos].clone(gc)));
+                    upvalues.push((pos - slot, upvalue.clone(gc)));
+                    false
+                }
+                _ => true,
+            });
+        let mut stack = vec![];
+        while self.stack.len() > slot {
+            stack.push(self.pop_stack(gc).unwrap());
+        }
+        stack.reverse();
+        generator.stack.replace(stack);
+        generator.upvalues.replace(upvalues);
+        generator.state.set(GeneratorState::Suspended);
+    }
+
+    // Calls the method of the instance on top of the stack with no arguments, as the iterator
+    // protocol does.
+    fn invoke(&mut self, line_no: u32, method: &str, gc: &Heap) -> Result<(), LoxError> {
+        let method = method.to_string();
+        if let Some(Object::InstanceDef(inst)) = self.pop_stack(gc) {
+            if let Some(field) = inst.get(&method, gc) {
+                self.push_stack(field);
+            } else if self.bind_method(&inst, &inst.class, &method, gc).is_err() {
+                return Err(LoxError::RuntimeError {
+                    lexeme: method,
+                    line_no,
+                    message: "Undefined property".to_string(),
+                    code: ErrorCode::Undefined,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
+            }
+            self.call(line_no, 0, gc)
+        } else {
+            Err(LoxError::RuntimeError {
+                lexeme: method,
+                line_no,
+                message: "Only Instance have properties".to_string(),
+                code: ErrorCode::NotInstance,
+                span: None,
+                backtrace: Box::new([]),
+            })
+        }
+    }
     fn bind_method(
         &mut self,
         inst: &Root<Instance>,
@@ -1143,7 +2158,14 @@ impl<T: SystemCalls> VM<T> {
             self.push_stack(Object::InstanceBindDef(gc.get_root(bound)));
             Ok(())
         } else {
-            Err(LoxError::RuntimeError("bm".to_string(), 0, "".to_string()))
+            Err(LoxError::RuntimeError {
+                lexeme: "bm".to_string(),
+                line_no: 0,
+                message: "".to_string(),
+                code: ErrorCode::Run,
+                span: None,
+                backtrace: Box::new([]),
+            })
         }
     }
 }
@@ -2,7 +2,7 @@ use crate::{
     chunk::Object,
     gc::{
         heap::Heap,
-        root::{CustomClone, Trace},
+        root::{CustomClone, Root, Trace},
     },
 };
 use std::{cell::RefCell, collections::HashMap, fmt::Debug};
@@ -10,6 +10,8 @@ use std::{cell::RefCell, collections::HashMap, fmt::Debug};
 pub struct Class {
     pub name: String,
     pub methods: RefCell<HashMap<String, Object>>,
+    // only used to tell subclasses apart, as the methods are copied from it
+    super_class: RefCell<Option<Root<Class>>>,
 }
 
 impl Class {
@@ -17,6 +19,7 @@ impl Class {
         Class {
             name,
             methods: RefCell::new(HashMap::new()),
+            super_class: RefCell::new(None),
         }
     }
     pub fn set_method(&self, k: String, v: Object) {
@@ -25,7 +28,7 @@ impl Class {
     pub fn get_method(&self, k: &String, gc: &Heap) -> Option<Object> {
         self.methods.borrow().get(k).map(|v| v.clone(gc))
     }
-    pub fn add_super_class(&self, super_class: &Class, gc: &Heap) {
+    pub fn add_super_class(&self, super_class: &Root<Class>, gc: &Heap) {
         // self.methods.borrow_mut().extend();
         for (name, method) in super_class.methods.borrow().iter() {
             if !self.methods.borrow().contains_key(name) {
@@ -34,6 +37,16 @@ impl Class {
                     .insert(name.clone(), method.clone(gc));
             }
         }
+        self.super_class.replace(Some(gc.clone_root(super_class)));
+    }
+    // Whether the class is other or inherits from it.
+    pub fn is_subclass_of(&self, other: &Class) -> bool {
+        std::ptr::eq(self, other)
+            || self
+                .super_class
+                .borrow()
+                .as_ref()
+                .is_some_and(|super_class| super_class.is_subclass_of(other))
     }
 }
 
@@ -48,6 +61,7 @@ impl CustomClone for Class {
         Class {
             name: self.name.clone(),
             methods: self.methods.clone(gc),
+            super_class: self.super_class.clone(gc),
         }
     }
 }
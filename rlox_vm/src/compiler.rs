@@ -1,53 +1,210 @@
 use crate::chunk::FunctionType;
+use crate::chunk::Handler;
+use crate::chunk::InlinedModule;
 use crate::chunk::Local;
 use crate::chunk::Object;
 use crate::chunk::OpCode;
 use crate::chunk::VM;
+use crate::debug::{self, Tracer};
 use crate::resolver::Resolver;
-use crate::system_calls::SystemCalls;
+use crate::system_calls::{SystemCalls, SystemInterface};
 use crate::{
     chunk::FuncSpec,
     gc::{heap::Heap, root::CustomClone, root::Trace},
 };
 use core::cell::RefCell;
-use rlox_core::error::LoxError;
+use rlox_core::diagnostics::{Diagnostic, Format};
+use rlox_core::error::{Error, ErrorCode, LoxError};
 use rlox_core::frontend::definitions::expr::*;
 use rlox_core::frontend::definitions::literal::Literal;
+use rlox_core::frontend::definitions::pattern::{MatchArm, Pattern};
+use rlox_core::frontend::definitions::span::Span;
 use rlox_core::frontend::definitions::stmt::*;
 use rlox_core::frontend::definitions::token::Token;
 use rlox_core::frontend::definitions::token_type::TokenType;
 use rlox_core::frontend::lexer::Lexer;
 use rlox_core::frontend::parser::Parser;
+use rlox_core::runtime::module::{self, Modules};
+use rlox_core::runtime::prelude::PRELUDE;
+use rlox_core::runtime::runner::{parse_line, parse_script, read_input};
 use rlox_core::runtime::visitor::VisAcceptor;
 use rlox_core::runtime::visitor::Visitor;
 use std::fs::read_to_string;
-use std::path::Path;
-
-pub fn run_file<T: SystemCalls, S: AsRef<str>>(path: S, sys_interface: T) -> Result<(), LoxError> {
-    let path = Path::new(path.as_ref());
-    let script = read_to_string(path).unwrap();
-    run(&script, sys_interface)?;
-    Ok(())
-    // if let Err(err) = run(&script, sys_interface) {
-    //     println!("{}", err);
-    // }
+use std::path::{Path, PathBuf};
+
+// Every syntax or semantic error of the script, or else the runtime error which stopped it.
+pub fn run_file<T: SystemCalls, S: AsRef<str>>(
+    path: S,
+    sys_interface: T,
+) -> Result<(), Vec<Error>> {
+    Session::new(sys_interface).run_file(path.as_ref())
 }
 
-fn run<T: SystemCalls, S: AsRef<str>>(script: S, sys_interface: T) -> Result<(), LoxError> {
-    let x = script.as_ref().to_string();
-    let mut ast = Parser::new(Lexer::new().parse(&x)?).parse()?;
-    Resolver::new().resolve(&mut ast)?;
-    // you have the ast here.
-    let mut gc = Heap::new();
-    let mut comp = Compiler::new(&gc);
-    ast.accept(&mut comp)?;
-    comp.curr_fn_mut().chunks.push(OpCode::Exit(0));
-    // println!("{:?}", comp.curr_fn().chunks);
-    let curr_fn = comp.curr_fn().clone(&gc);
-    let cons_pl = comp.constant_pool;
-    let mut vm = VM::new(sys_interface, cons_pl, curr_fn, &gc);
-    vm.run(false, &gc)?;
-    Ok(())
+// Runs a script, logging each instruction it executes to the tracer.
+pub fn trace_file<T: SystemCalls, S: AsRef<str>>(
+    path: S,
+    sys_interface: T,
+    tracer: Tracer,
+) -> Result<(), Vec<Error>> {
+    let mut session = Session::new(sys_interface);
+    session.set_tracer(tracer);
+    session.run_file(path.as_ref())
+}
+
+pub fn disassemble_file<S: AsRef<str>>(path: S) -> Result<String, Vec<Error>> {
+    Session::new(SystemInterface {}).disassemble_file(path.as_ref())
+}
+
+pub fn run_prompt<T: SystemCalls>(sys_interface: T, error_format: Format) {
+    let mut session = Session::new(sys_interface);
+    let mut buff = String::new();
+    while read_input(&mut buff) {
+        if let Err(errors) = session.run_line(&buff) {
+            for err in errors {
+                let report = session.diagnostic(&err, None, &buff).render(error_format);
+                session.vm.sys_interface().print_error(&report);
+            }
+        }
+    }
+}
+
+// Compiles scripts one after the other and runs them on the same VM, so that globals,
+// constants and heap objects outlive the script which made them, as a REPL needs.
+pub struct Session<T: SystemCalls> {
+    gc: Heap,
+    vm: VM<T>,
+    resolver: Resolver,
+    path: Option<PathBuf>,
+    modules: Modules<ModuleScope>,
+    prelude: Vec<String>,
+    is_debug: bool,
+}
+
+impl<T: SystemCalls> Session<T> {
+    pub fn new(sys_interface: T) -> Self {
+        let gc = Heap::new();
+        let script = FuncSpec::new(0, None, FunctionType::SCRIPT);
+        let vm = VM::new(sys_interface, vec![], script, &gc);
+        let mut session = Session {
+            gc,
+            vm,
+            resolver: Resolver::new(),
+            path: None,
+            modules: Modules::new(),
+            prelude: vec![],
+            is_debug: false,
+        };
+        let tokens = Lexer::new().parse(&PRELUDE.to_string()).unwrap();
+        let mut prelude = Parser::new(tokens).parse().unwrap();
+        session.prelude = module::exports(&prelude);
+        session.run(&mut prelude, false).unwrap();
+        session.vm.keep_prelude(&session.prelude, &session.gc);
+        // scripts see the prelude's names without them counting as declared by the script
+        session.resolver = Resolver::new();
+        for name in &session.prelude {
+            session.resolver.define_global(name);
+        }
+        session
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.modules.enter(path.clone());
+        self.path = Some(path);
+    }
+
+    // Traces the scripts run from now on, the prelude having already run.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.vm.set_tracer(tracer);
+        self.is_debug = true;
+    }
+
+    // Compiles a script into a function, adding its constants to the VM's.
+    fn compile(
+        &mut self,
+        ast: &mut Vec<Stmt>,
+        echo: bool,
+    ) -> Result<(FuncSpec, bool), Vec<LoxError>> {
+        self.resolver.resolve_all(ast)?;
+        let mut comp = Compiler::new(&self.gc);
+        comp.constant_pool = std::mem::take(&mut self.vm.constant_pool);
+        comp.modules = std::mem::take(&mut self.modules);
+        comp.path = self.path.clone();
+        comp.prelude = self.prelude.clone();
+        let echo = comp.compile_script(ast, echo);
+        self.vm.constant_pool = std::mem::take(&mut comp.constant_pool);
+        self.modules = std::mem::take(&mut comp.modules);
+        Ok((
+            comp.curr_fn().clone(&self.gc),
+            echo.map_err(|err| vec![err])?,
+        ))
+    }
+
+    // The bytecode a script compiles to, without running it.
+    pub fn disassemble(&mut self, script: &str) -> Result<String, Vec<LoxError>> {
+        let mut ast = parse_script(script)?;
+        let (script, _) = self.compile(&mut ast, false)?;
+        Ok(debug::disassemble(&script, &self.vm.constant_pool))
+    }
+
+    pub fn run_file(&mut self, path: &str) -> Result<(), Vec<Error>> {
+        let path = Path::new(path);
+        let script = read_to_string(path).unwrap();
+        self.set_path(path);
+        parse_script(&script)
+            .and_then(|mut ast| self.run(&mut ast, false))
+            .map_err(|errors| errors.into_iter().map(Error::from).collect())
+    }
+
+    pub fn disassemble_file(&mut self, path: &str) -> Result<String, Vec<Error>> {
+        let path = Path::new(path);
+        let script = read_to_string(path).map_err(|_| {
+            vec![Error::from(LoxError::RuntimeError {
+                lexeme: path.display().to_string(),
+                line_no: 0,
+                message: "File not readable".to_string(),
+                code: ErrorCode::Run,
+                span: None,
+                backtrace: Box::new([]),
+            })]
+        })?;
+        self.set_path(path);
+        self.disassemble(&script)
+            .map_err(|errors| errors.into_iter().map(Error::from).collect())
+    }
+
+    // How an error of the scripts run is reported, against the file of the module it was
+    // raised in, if any.
+    pub fn diagnostic(&self, err: &Error, file: Option<&str>, source: &str) -> Diagnostic {
+        Diagnostic::from_program(err, &self.modules, file, source)
+    }
+
+    // Runs one REPL input, and prints the value of a bare expression statement ending it.
+    pub fn run_line(&mut self, script: &str) -> Result<(), Vec<Error>> {
+        self.eval_line(script)
+            .map_err(|errors| errors.into_iter().map(Error::from).collect())
+    }
+
+    fn eval_line(&mut self, script: &str) -> Result<(), Vec<LoxError>> {
+        let file_id = self.modules.add_input(script);
+        let mut ast = parse_line(script, file_id)?;
+        self.run(&mut ast, true)
+    }
+
+    fn run(&mut self, ast: &mut Vec<Stmt>, echo: bool) -> Result<(), Vec<LoxError>> {
+        let (script, echo) = self.compile(ast, echo)?;
+        self.vm.load_script(script, &self.gc);
+        self.vm
+            .run(self.is_debug, &self.gc)
+            .map_err(|err| vec![err])?;
+        if echo {
+            match self.vm.pop_stack(&self.gc) {
+                Some(Object::Nil) | None => {}
+                Some(value) => self.vm.sys_interface().print(&value, &self.gc),
+            }
+        }
+        Ok(())
+    }
 }
 
 struct Compiler<'a> {
@@ -56,6 +213,36 @@ struct Compiler<'a> {
     pub scoped_fns: Vec<FuncSpec>,
     pub gc: &'a Heap,
     loop_start: Option<(usize, usize)>,
+    finally_blocks: Vec<Finally>,
+    // finally blocks entered before the innermost loop, which break/continue don't leave
+    loop_finally: usize,
+    // locals declared before the innermost loop's body, which break/continue don't pop
+    loop_locals: usize,
+    // the file being compiled, which imports are relative to
+    path: Option<PathBuf>,
+    // the module being compiled, None for the script run
+    module: Option<ModuleScope>,
+    modules: Modules<ModuleScope>,
+    // globals defined by the prelude, which every module sees
+    prelude: Vec<String>,
+    // jumps out of the optional chains being compiled, taken when a `?.` finds nil
+    optional_jumps: Vec<Vec<usize>>,
+}
+
+// Modules are compiled inline where they are first imported, and share the VM's globals. The
+// globals a module declares are prefixed with its path to keep them apart from other modules'.
+#[derive(Clone)]
+struct ModuleScope {
+    prefix: String,
+    exports: Vec<String>,
+}
+
+// The body of a finally clause, inlined wherever return/break/continue leave its try.
+struct Finally {
+    body: Vec<Stmt>,
+    fn_depth: usize,
+    // ranges of inlined finally code which the try's own handlers must not cover
+    holes: Vec<(usize, usize)>,
 }
 
 impl<'a> Compiler<'a> {
@@ -65,6 +252,14 @@ impl<'a> Compiler<'a> {
             scoped_fns: vec![FuncSpec::new(0, None, FunctionType::SCRIPT)],
             gc,
             loop_start: None,
+            finally_blocks: vec![],
+            loop_finally: 0,
+            loop_locals: 0,
+            path: None,
+            module: None,
+            modules: Modules::new(),
+            prelude: vec![],
+            optional_jumps: vec![],
         }
     }
     pub fn add_const(&mut self, val: Object) -> usize {
@@ -81,6 +276,85 @@ impl<'a> Compiler<'a> {
             _ => true,
         };
     }
+    // Compiles a whole script. With echo, a trailing expression statement leaves its value on
+    // the stack, and true is returned.
+    fn compile_script(&mut self, ast: &[Stmt], echo: bool) -> Result<bool, LoxError> {
+        let mut echoed = false;
+        for (i, stmt) in ast.iter().enumerate() {
+            match stmt {
+                Stmt::Expression(val) if echo && i == ast.len() - 1 => {
+                    val.expr.accept(self)?;
+                    echoed = true;
+                }
+                _ => stmt.accept(self)?,
+            }
+        }
+        self.curr_fn_mut().chunks.push(OpCode::Exit(0));
+        Ok(echoed)
+    }
+
+    fn global_const(&mut self, name: &Token) -> usize {
+        let name = match &self.module {
+            Some(module) if module.exports.contains(&name.lexeme) => {
+                format!("{}{}", module.prefix, name.lexeme)
+            }
+            _ => name.lexeme.clone(),
+        };
+        self.add_const(Object::Str(name))
+    }
+
+    fn import_module(&mut self, path: &Token, import: Span) -> Result<ModuleScope, LoxError> {
+        let file = module::resolve(self.path.as_deref(), path)?;
+        if let Some(module) = self.modules.get(&file) {
+            return Ok(module.clone());
+        }
+        if !self.modules.enter(file.clone()) {
+            return Err(module::cycle_error(&self.modules, &file, path, import));
+        }
+        let module = self.compile_module(&file, path);
+        self.modules.exit();
+        let module = module?;
+        self.modules.insert(file, module.clone());
+        Ok(module)
+    }
+
+    // An import failing to compile fails when it is run, as it does in the tree-walker.
+    fn raise(&mut self, line_no: u32, err: LoxError) {
+        let pos = self.curr_fn().errors.len();
+        self.curr_fn_mut().errors.push(err);
+        self.curr_fn_mut().chunks.push(OpCode::Raise(line_no, pos));
+    }
+
+    fn compile_module(&mut self, file: &Path, path: &Token) -> Result<ModuleScope, LoxError> {
+        let mut ast = module::load(&mut self.modules, file, path)?;
+        let mut resolver = Resolver::new();
+        for name in &self.prelude {
+            resolver.define_global(name);
+        }
+        resolver
+            .resolve_all(&mut ast)
+            .map_err(|mut errors| errors.remove(0))?;
+        let module = ModuleScope {
+            prefix: format!("{}::", file.display()),
+            exports: module::exports(&ast),
+        };
+        let enclosing = self.module.replace(module.clone());
+        let importer = self.path.replace(file.to_path_buf());
+        let start = self.curr_fn().chunks.len();
+        let res = ast.accept(self);
+        self.module = enclosing;
+        self.path = importer;
+        res?;
+        let end = self.curr_fn().chunks.len();
+        self.curr_fn_mut().modules.push(InlinedModule {
+            start,
+            end,
+            name: module::frame_name(path),
+            line_no: path.line_no,
+        });
+        Ok(module)
+    }
+
     fn resolve_local(&mut self, token: &Token) -> i32 {
         for i in (0..self.curr_fn().locals.len()).rev() {
             if self.curr_fn().locals[i].name.lexeme == token.lexeme {
@@ -99,10 +373,11 @@ impl<'a> Compiler<'a> {
                     .chunks
                     .push(OpCode::GetUpvalue(token.line_no, x as usize));
             } else {
-                x = self.add_const(Object::Str(token.lexeme.clone())) as i32;
+                x = self.global_const(token) as i32;
                 self.curr_fn_mut()
                     .chunks
                     .push(OpCode::GetGlobal(token.line_no, x as usize));
+                self.mark(token);
             }
         } else {
             self.curr_fn_mut()
@@ -110,6 +385,298 @@ impl<'a> Compiler<'a> {
                 .push(OpCode::GetLocal(token.line_no, x as usize));
         }
     }
+    // Stores the top of the stack in a variable, leaving it on the stack.
+    fn set_variable(&mut self, name: &Token) {
+        let mut x = self.resolve_local(name);
+        if x == -1 {
+            x = self.resolve_upvalue(name);
+            if x != -1 {
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::SetUpvalue(name.line_no, x as usize));
+            } else {
+                // TODO: think of better approach
+                x = self.global_const(name) as i32;
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::SetGlobal(name.line_no, x as usize));
+                self.mark(name);
+                // x = self.add_const(Object::Str(name.lexeme.clone())) as i32;
+                // self.curr_fn_mut().chunks.push(OpCode::GetGlobal(name.line_no, x as usize));
+            }
+        } else {
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::SetLocal(name.line_no, x as usize));
+        }
+    }
+    // Jumps past the nil case when the top of the stack isn't nil, leaving whether it is above it.
+    fn jump_if_not_nil(&mut self, line_no: u32) -> usize {
+        self.curr_fn_mut().chunks.push(OpCode::Duplicate(1));
+        self.curr_fn_mut().chunks.push(OpCode::NilVal);
+        self.curr_fn_mut().chunks.push(OpCode::EqualTo(line_no));
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::JumpIfFalse(line_no, 9999));
+        self.curr_fn_mut().chunks.len() - 1
+    }
+    // Pops the locals of the loop body being left by a break or continue, leaving them declared
+    // for the code after it.
+    fn pop_loop_locals(&mut self) {
+        for i in (self.loop_locals..self.curr_fn().locals.len()).rev() {
+            let op = if self.curr_fn().locals[i].is_closed {
+                OpCode::CloseUpvalue
+            } else {
+                OpCode::StackPop
+            };
+            self.curr_fn_mut().chunks.push(op);
+        }
+    }
+    // Points the jump at pos to the next instruction.
+    fn patch_jump(&mut self, pos: usize) {
+        let target = self.curr_fn().chunks.len();
+        self.curr_fn_mut().chunks[pos] = match self.curr_fn().chunks[pos] {
+            OpCode::JumpIfFalse(line_no, _) => OpCode::JumpIfFalse(line_no, target),
+            OpCode::Jump(line_no, _) => OpCode::Jump(line_no, target),
+            op => op,
+        };
+    }
+    // Declares the value on top of the stack as a local no name in the script can refer to,
+    // returning its slot.
+    fn hidden_local(&mut self, keyword: &Token, name: &str) -> Result<usize, LoxError> {
+        let mut token = keyword.clone();
+        token.lexeme = format!(" {} {}", keyword.lexeme, name);
+        self.declare_variable(&token)?;
+        Ok(self.curr_fn().locals.len() - 1)
+    }
+    // Compiles the arms of a match whose subject is in the local slot subject. The first arm that
+    // matches runs its body in a scope holding its bindings, then jumps past the others.
+    fn match_arms<T>(
+        &mut self,
+        arms: &[MatchArm<T>],
+        subject: usize,
+        mut body: impl FnMut(&mut Self, &T) -> Result<(), LoxError>,
+    ) -> Result<(), LoxError> {
+        let mut end_jumps = vec![];
+        for arm in arms {
+            let line_no = arm.keyword.line_no;
+            self.begin_scope();
+            let mut fails = vec![];
+            let mut bindings = vec![];
+            self.match_pattern(
+                &arm.pattern,
+                subject,
+                &mut vec![],
+                &mut fails,
+                &mut bindings,
+            )?;
+            let first_binding = self.curr_fn().locals.len();
+            for (name, path) in &bindings {
+                self.push_path(subject, path, line_no);
+                self.declare_variable(name)?;
+            }
+
+            let mut guard_jump = None;
+            if let Some(guard) = &arm.guard {
+                guard.accept(self)?;
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::JumpIfFalse(line_no, 9999));
+                guard_jump = Some(self.curr_fn().chunks.len() - 1);
+                self.curr_fn_mut().chunks.push(OpCode::StackPop);
+            }
+            body(self, &arm.body)?;
+            let closed: Vec<bool> = self.curr_fn().locals[first_binding..][..bindings.len()]
+                .iter()
+                .map(|local| local.is_closed)
+                .collect();
+            self.end_scope();
+            self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+            end_jumps.push(self.curr_fn().chunks.len() - 1);
+
+            // a failed guard pops the bindings pushed for it before trying the next arm
+            let mut next_jump = None;
+            if let Some(guard_jump) = guard_jump {
+                self.patch_jump(guard_jump);
+                self.curr_fn_mut().chunks.push(OpCode::StackPop);
+                for is_closed in closed.into_iter().rev() {
+                    let op = if is_closed {
+                        OpCode::CloseUpvalue
+                    } else {
+                        OpCode::StackPop
+                    };
+                    self.curr_fn_mut().chunks.push(op);
+                }
+                self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+                next_jump = Some(self.curr_fn().chunks.len() - 1);
+            }
+            for fail in fails {
+                self.patch_jump(fail);
+            }
+            self.curr_fn_mut().chunks.push(OpCode::StackPop);
+            if let Some(next_jump) = next_jump {
+                self.patch_jump(next_jump);
+            }
+        }
+        for end_jump in end_jumps {
+            self.patch_jump(end_jump);
+        }
+        Ok(())
+    }
+    // Emits the tests of pattern against the value at path in the subject, each jumping to the
+    // arm's failure with its result left on the stack when it doesn't pass. The names the
+    // pattern binds are collected with their paths, to be pushed once every test has passed.
+    fn match_pattern<'p>(
+        &mut self,
+        pattern: &'p Pattern,
+        subject: usize,
+        path: &mut Vec<usize>,
+        fails: &mut Vec<usize>,
+        bindings: &mut Vec<(&'p Token, Vec<usize>)>,
+    ) -> Result<(), LoxError> {
+        let line_no = pattern.token().line_no;
+        match pattern {
+            Pattern::Wildcard(_) => return Ok(()),
+            Pattern::Binding(name) => {
+                bindings.push((name, path.clone()));
+                return Ok(());
+            }
+            Pattern::Literal(literal, _) => {
+                self.push_path(subject, path, line_no);
+                let x = self.add_const(Object::from_literal(literal.clone(), self.gc));
+                self.curr_fn_mut().chunks.push(OpCode::Constant(line_no, x));
+                self.curr_fn_mut().chunks.push(OpCode::EqualTo(line_no));
+            }
+            Pattern::Class(class, name) => {
+                self.push_path(subject, path, line_no);
+                self.named_variable(&class.name);
+                self.curr_fn_mut().chunks.push(OpCode::MatchClass(line_no));
+                self.mark(&class.name);
+                if let Some(name) = name {
+                    bindings.push((name, path.clone()));
+                }
+            }
+            Pattern::List(_, elements) => {
+                self.push_path(subject, path, line_no);
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::MatchList(line_no, elements.len()));
+            }
+        }
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::JumpIfFalse(line_no, 9999));
+        fails.push(self.curr_fn().chunks.len() - 1);
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+
+        if let Pattern::List(_, elements) = pattern {
+            for (i, element) in elements.iter().enumerate() {
+                path.push(i);
+                self.match_pattern(element, subject, path, fails, bindings)?;
+                path.pop();
+            }
+        }
+        Ok(())
+    }
+    // Pushes the value at path in the subject, each step indexing into a list.
+    fn push_path(&mut self, subject: usize, path: &[usize], line_no: u32) {
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::GetLocal(line_no, subject));
+        for &i in path {
+            let x = self.add_const(Object::Int(i as i64));
+            self.curr_fn_mut().chunks.push(OpCode::Constant(line_no, x));
+            self.curr_fn_mut().chunks.push(OpCode::GetIndex(line_no));
+        }
+    }
+    fn binary_op(&mut self, operator: &Token) -> Result<(), LoxError> {
+        match operator.token_type {
+            TokenType::MINUS => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Subs(operator.line_no)),
+            TokenType::PLUS => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Add(operator.line_no)),
+            TokenType::STAR => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Multiply(operator.line_no)),
+            TokenType::SLASH => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Divide(operator.line_no)),
+            TokenType::TildeSlash => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::IntDivide(operator.line_no)),
+            TokenType::PERCENT => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Modulo(operator.line_no)),
+            TokenType::StarStar => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::Power(operator.line_no)),
+            TokenType::AMPERSAND => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::BitAnd(operator.line_no)),
+            TokenType::PIPE => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::BitOr(operator.line_no)),
+            TokenType::CARET => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::BitXor(operator.line_no)),
+            TokenType::LessLess => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::ShiftLeft(operator.line_no)),
+            TokenType::GreaterGreater => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::ShiftRight(operator.line_no)),
+            TokenType::EqualEqual => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::EqualTo(operator.line_no)),
+            TokenType::BangEqual => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::NotEqualTo(operator.line_no)),
+            TokenType::GREATER => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::GreaterThan(operator.line_no)),
+            TokenType::GreaterEqual => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::GreaterThanEq(operator.line_no)),
+            TokenType::LESS => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::LesserThan(operator.line_no)),
+            TokenType::LessEqual => self
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::LesserThanEq(operator.line_no)),
+            _ => {
+                return Err(LoxError::SemanticError {
+                    lexeme: String::from(""),
+                    line_no: operator.line_no,
+                    message: String::from(""),
+                    code: ErrorCode::Resolve,
+                    span: Some(operator.span),
+                })
+            }
+        }
+        self.mark(operator);
+        Ok(())
+    }
+
     fn begin_scope(&mut self) {
         self.curr_fn_mut().scope_depth += 1;
     }
@@ -141,11 +708,14 @@ impl<'a> Compiler<'a> {
                 break;
             }
             if local.name.lexeme == lc.name.lexeme {
-                return Err(LoxError::RuntimeError(
-                    String::from(""),
-                    local.name.line_no,
-                    String::from(""),
-                ));
+                return Err(LoxError::RuntimeError {
+                    lexeme: String::from(""),
+                    line_no: local.name.line_no,
+                    message: String::from(""),
+                    code: ErrorCode::AlreadyExists(Some(Box::new(lc.name.span))),
+                    span: None,
+                    backtrace: Box::new([]),
+                });
             }
         }
         self.curr_fn_mut().locals.push(local);
@@ -160,6 +730,12 @@ impl<'a> Compiler<'a> {
         self.scoped_fns.last_mut().unwrap()
     }
 
+    // Records the code the instruction just emitted comes from, for the errors it raises.
+    fn mark(&mut self, token: &Token) {
+        let index = self.curr_fn().chunks.len() - 1;
+        self.curr_fn_mut().spans.push((index, token.span));
+    }
+
     fn resolve_upvalue(&mut self, token: &Token) -> i32 {
         let n = self.scoped_fns.len();
         if n < 2 {
@@ -187,6 +763,74 @@ impl<'a> Compiler<'a> {
         return -1;
     }
 
+    // compiles a try/catch/finally body in its own scope; the catch variable takes the
+    // slot the VM pushes the exception into
+    fn compile_clause(&mut self, body: &Vec<Stmt>, name: Option<&Token>) -> Result<(), LoxError> {
+        self.begin_scope();
+        if let Some(name) = name {
+            self.declare_variable(name)?;
+        }
+        body.accept(self)?;
+        self.end_scope();
+        Ok(())
+    }
+
+    // Inlines the finally blocks from `from` onwards, innermost first, before control
+    // jumps out of them. Each body is compiled without itself on the finally stack.
+    fn exit_finally_blocks(&mut self, from: usize) -> Result<(), LoxError> {
+        let mut starts = vec![];
+        for i in (from..self.finally_blocks.len()).rev() {
+            let rest = self.finally_blocks.split_off(i);
+            starts.push((i, self.curr_fn().chunks.len()));
+            let res = self.compile_clause(&rest[0].body, None);
+            self.finally_blocks.extend(rest);
+            res?;
+        }
+        let end = self.curr_fn().chunks.len();
+        for (i, start) in starts {
+            self.finally_blocks[i].holes.push((start, end));
+        }
+        Ok(())
+    }
+
+    fn fn_finally_start(&self) -> usize {
+        let fn_depth = self.scoped_fns.len();
+        self.finally_blocks
+            .iter()
+            .position(|finally| finally.fn_depth == fn_depth)
+            .unwrap_or(self.finally_blocks.len())
+    }
+
+    // Adds a handler for each part of start..end that isn't a hole.
+    fn add_handlers(&mut self, start: usize, end: usize, target: usize, holes: &[(usize, usize)]) {
+        let depth = self.curr_fn().locals.len();
+        let mut start = start;
+        let mut holes = holes.to_vec();
+        holes.sort();
+        for (hole_start, hole_end) in holes {
+            if hole_start >= end {
+                break;
+            }
+            if start < hole_start {
+                self.curr_fn_mut().handlers.push(Handler {
+                    start,
+                    end: hole_start,
+                    target,
+                    depth,
+                });
+            }
+            start = start.max(hole_end);
+        }
+        if start < end {
+            self.curr_fn_mut().handlers.push(Handler {
+                start,
+                end,
+                target,
+                depth,
+            });
+        }
+    }
+
     fn parse_function(&mut self, val: &Function, fn_type: FunctionType) -> Result<(), LoxError> {
         self.scoped_fns.push(FuncSpec::new(
             0,
@@ -197,6 +841,7 @@ impl<'a> Compiler<'a> {
         self.begin_scope();
 
         self.curr_fn_mut().arity = val.params.len() as u32;
+        self.curr_fn_mut().is_generator = val.is_generator;
         for param in &val.params {
             self.declare_variable(&param)?;
         }
@@ -229,56 +874,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
     fn visit_binary_expr(&mut self, val: &Binary) -> Result<(), LoxError> {
         val.left.accept(self)?;
         val.right.accept(self)?;
-        match val.operator.token_type {
-            TokenType::MINUS => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::Subs(val.operator.line_no)),
-            TokenType::PLUS => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::Add(val.operator.line_no)),
-            TokenType::STAR => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::Multiply(val.operator.line_no)),
-            TokenType::SLASH => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::Divide(val.operator.line_no)),
-            TokenType::EqualEqual => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::EqualTo(val.operator.line_no)),
-            TokenType::BangEqual => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::NotEqualTo(val.operator.line_no)),
-            TokenType::GREATER => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::GreaterThan(val.operator.line_no)),
-            TokenType::GreaterEqual => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::GreaterThanEq(val.operator.line_no)),
-            TokenType::LESS => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::LesserThan(val.operator.line_no)),
-            TokenType::LessEqual => self
-                .curr_fn_mut()
-                .chunks
-                .push(OpCode::LesserThanEq(val.operator.line_no)),
-            _ => {
-                return Err(LoxError::SemanticError(
-                    String::from(""),
-                    val.operator.line_no,
-                    String::from(""),
-                ))
-            }
-        }
-        Ok(())
+        self.binary_op(&val.operator)
     }
 
     fn visit_call_expr(&mut self, val: &Call) -> Result<(), LoxError> {
@@ -289,6 +885,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.curr_fn_mut()
             .chunks
             .push(OpCode::Call(val.paren.line_no, val.arguments.len()));
+        self.mark(&val.paren);
         Ok(())
     }
 
@@ -302,25 +899,45 @@ impl<'a> Visitor<()> for Compiler<'a> {
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::Negate(val.operator.line_no));
+            self.mark(&val.operator);
             Ok(())
         } else if val.operator.token_type == TokenType::BANG {
             val.right.accept(self)?;
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::Not(val.operator.line_no));
+            self.mark(&val.operator);
+            Ok(())
+        } else if val.operator.token_type == TokenType::TILDE {
+            val.right.accept(self)?;
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::BitNot(val.operator.line_no));
+            self.mark(&val.operator);
+            Ok(())
+        } else if val.operator.token_type == TokenType::STRINGIFY {
+            val.right.accept(self)?;
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::Stringify(val.operator.line_no));
             Ok(())
         } else {
-            Err(LoxError::RuntimeError(
-                String::from(""),
-                val.operator.line_no,
-                String::from(""),
-            ))
+            Err(LoxError::RuntimeError {
+                lexeme: String::from(""),
+                line_no: val.operator.line_no,
+                message: String::from(""),
+                code: ErrorCode::Run,
+                span: None,
+                backtrace: Box::new([]),
+            })
         }
     }
 
-    fn visit_literal_expr(&mut self, val: &Literal) -> Result<(), LoxError> {
-        let x = self.add_const(val.clone().into());
-        self.curr_fn_mut().chunks.push(OpCode::Constant(x));
+    fn visit_literal_expr(&mut self, val: &Literal, span: Span) -> Result<(), LoxError> {
+        let x = self.add_const(Object::from_literal(val.clone(), self.gc));
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::Constant(span.line, x));
         Ok(())
     }
 
@@ -337,11 +954,14 @@ impl<'a> Visitor<()> for Compiler<'a> {
                 .chunks
                 .push(OpCode::BoolOr(val.operator.line_no)),
             _ => {
-                return Err(LoxError::RuntimeError(
-                    String::from(""),
-                    val.operator.line_no,
-                    String::from(""),
-                ))
+                return Err(LoxError::RuntimeError {
+                    lexeme: String::from(""),
+                    line_no: val.operator.line_no,
+                    message: String::from(""),
+                    code: ErrorCode::Run,
+                    span: None,
+                    backtrace: Box::new([]),
+                })
             }
         };
         Ok(())
@@ -354,6 +974,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.curr_fn_mut()
             .chunks
             .push(OpCode::GetProperty(val.name.line_no, x as usize));
+        self.mark(&val.name);
         Ok(())
     }
 
@@ -365,6 +986,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.curr_fn_mut()
             .chunks
             .push(OpCode::SetProperty(val.name.line_no, x as usize));
+        self.mark(&val.name);
         Ok(())
     }
 
@@ -375,6 +997,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.begin_scope();
 
         self.curr_fn_mut().arity = val.params.len() as u32;
+        self.curr_fn_mut().is_generator = val.is_generator;
         for param in &val.params {
             self.declare_variable(&param)?;
         }
@@ -401,9 +1024,6 @@ impl<'a> Visitor<()> for Compiler<'a> {
 
     fn visit_super_expr(&mut self, val: &Super) -> Result<(), LoxError> {
         let x = self.add_const(Object::Str(val.method.lexeme.clone())) as i32;
-        for lc in &self.scoped_fns {
-            println!("{:?}", lc.locals);
-        }
         let mut this_keyword = Token::new(TokenType::THIS, 0, None, String::from("this"));
         if let Some(x) = val.keyword.scope {
             if x != 0 {
@@ -415,6 +1035,210 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.curr_fn_mut()
             .chunks
             .push(OpCode::GetSuper(val.keyword.line_no, x as usize));
+        self.mark(&val.keyword);
+        Ok(())
+    }
+
+    fn visit_list_expr(&mut self, val: &List) -> Result<(), LoxError> {
+        for element in &val.elements {
+            element.accept(self)?;
+        }
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::BuildList(val.bracket.line_no, val.elements.len()));
+        Ok(())
+    }
+
+    fn visit_map_expr(&mut self, val: &Map) -> Result<(), LoxError> {
+        for (key, value) in &val.entries {
+            key.accept(self)?;
+            value.accept(self)?;
+        }
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::BuildMap(val.brace.line_no, val.entries.len()));
+        self.mark(&val.brace);
+        Ok(())
+    }
+
+    fn visit_index_expr(&mut self, val: &Index) -> Result<(), LoxError> {
+        val.object.accept(self)?;
+        val.index.accept(self)?;
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::GetIndex(val.bracket.line_no));
+        self.mark(&val.bracket);
+        Ok(())
+    }
+
+    fn visit_set_index_expr(&mut self, val: &SetIndex) -> Result<(), LoxError> {
+        val.object.accept(self)?;
+        val.index.accept(self)?;
+        val.value.accept(self)?;
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::SetIndex(val.bracket.line_no));
+        self.mark(&val.bracket);
+        Ok(())
+    }
+
+    fn visit_compound_expr(&mut self, val: &Compound) -> Result<(), LoxError> {
+        // the old value is buried under the target's operands so that it outlives the store
+        let line_no = val.operator.line_no;
+        match &val.target {
+            Expr::Variable(var) => {
+                self.named_variable(&var.name);
+                if val.postfix {
+                    self.curr_fn_mut().chunks.push(OpCode::Duplicate(1));
+                }
+                val.value.accept(self)?;
+                self.binary_op(&val.operator)?;
+                self.set_variable(&var.name);
+            }
+            Expr::Get(get) => {
+                get.object.accept(self)?;
+                let x = self.add_const(Object::Str(get.name.lexeme.clone()));
+                self.curr_fn_mut().chunks.push(OpCode::Duplicate(1));
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::GetProperty(get.name.line_no, x));
+                self.mark(&get.name);
+                if val.postfix {
+                    self.curr_fn_mut().chunks.push(OpCode::Duplicate(1));
+                    self.curr_fn_mut().chunks.push(OpCode::Bury(2));
+                }
+                val.value.accept(self)?;
+                self.binary_op(&val.operator)?;
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::SetProperty(get.name.line_no, x));
+                self.mark(&get.name);
+            }
+            Expr::Index(index) => {
+                index.object.accept(self)?;
+                index.index.accept(self)?;
+                self.curr_fn_mut().chunks.push(OpCode::Duplicate(2));
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::GetIndex(index.bracket.line_no));
+                self.mark(&index.bracket);
+                if val.postfix {
+                    self.curr_fn_mut().chunks.push(OpCode::Duplicate(1));
+                    self.curr_fn_mut().chunks.push(OpCode::Bury(3));
+                }
+                val.value.accept(self)?;
+                self.binary_op(&val.operator)?;
+                self.curr_fn_mut()
+                    .chunks
+                    .push(OpCode::SetIndex(index.bracket.line_no));
+                self.mark(&index.bracket);
+            }
+            _ => {
+                return Err(LoxError::RuntimeError {
+                    lexeme: val.operator.lexeme.clone(),
+                    line_no,
+                    message: "Invalid assignment".to_string(),
+                    code: ErrorCode::InvalidAssignment,
+                    span: None,
+                    backtrace: Box::new([]),
+                })
+            }
+        }
+        if val.postfix {
+            self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        }
+        Ok(())
+    }
+
+    fn visit_conditional_expr(&mut self, val: &Conditional) -> Result<(), LoxError> {
+        let line_no = val.question.line_no;
+        val.condition.accept(self)?;
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::JumpIfFalse(line_no, 9999));
+        let then_jump = self.curr_fn().chunks.len() - 1;
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        val.then_branch.accept(self)?;
+
+        self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+        let else_jump = self.curr_fn().chunks.len() - 1;
+        self.curr_fn_mut().chunks[then_jump] =
+            OpCode::JumpIfFalse(line_no, self.curr_fn().chunks.len());
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        val.else_branch.accept(self)?;
+
+        self.curr_fn_mut().chunks[else_jump] = OpCode::Jump(line_no, self.curr_fn().chunks.len());
+        Ok(())
+    }
+
+    fn visit_coalesce_expr(&mut self, val: &Coalesce) -> Result<(), LoxError> {
+        let line_no = val.operator.line_no;
+        val.left.accept(self)?;
+        let not_nil = self.jump_if_not_nil(line_no);
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        val.right.accept(self)?;
+
+        self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+        let end_jump = self.curr_fn().chunks.len() - 1;
+        self.curr_fn_mut().chunks[not_nil] =
+            OpCode::JumpIfFalse(line_no, self.curr_fn().chunks.len());
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+
+        self.curr_fn_mut().chunks[end_jump] = OpCode::Jump(line_no, self.curr_fn().chunks.len());
+        Ok(())
+    }
+
+    fn visit_safe_get_expr(&mut self, val: &SafeGet) -> Result<(), LoxError> {
+        let line_no = val.name.line_no;
+        val.object.accept(self)?;
+        // the nil left on the stack is what the chain evaluates to
+        let not_nil = self.jump_if_not_nil(line_no);
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+        let end_jump = self.curr_fn().chunks.len() - 1;
+        self.optional_jumps.last_mut().unwrap().push(end_jump);
+        self.curr_fn_mut().chunks[not_nil] =
+            OpCode::JumpIfFalse(line_no, self.curr_fn().chunks.len());
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+
+        let x = self.add_const(Object::Str(val.name.lexeme.clone()));
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::GetProperty(line_no, x));
+        self.mark(&val.name);
+        Ok(())
+    }
+
+    fn visit_optional_chain_expr(&mut self, val: &OptionalChain) -> Result<(), LoxError> {
+        self.optional_jumps.push(vec![]);
+        val.expr.accept(self)?;
+        for pos in self.optional_jumps.pop().unwrap() {
+            self.patch_jump(pos);
+        }
+        Ok(())
+    }
+
+    fn visit_match_expr(&mut self, val: &MatchExpr) -> Result<(), LoxError> {
+        let line_no = val.keyword.line_no;
+        self.begin_scope();
+        self.curr_fn_mut().chunks.push(OpCode::NilVal);
+        let result = self.hidden_local(&val.keyword, "result")?;
+        val.subject.accept(self)?;
+        let subject = self.hidden_local(&val.keyword, "subject")?;
+        self.match_arms(&val.arms, subject, |compiler, body| {
+            body.accept(compiler)?;
+            compiler
+                .curr_fn_mut()
+                .chunks
+                .push(OpCode::SetLocal(line_no, result));
+            compiler.curr_fn_mut().chunks.push(OpCode::StackPop);
+            Ok(())
+        })?;
+        // popping the subject leaves the result slot as the expression's value
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        self.curr_fn_mut().locals.truncate(result);
+        self.curr_fn_mut().scope_depth -= 1;
         Ok(())
     }
 
@@ -450,7 +1274,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
             return Ok(());
         }
 
-        let x = self.add_const(Object::Str(val.name.lexeme.clone()));
+        let x = self.global_const(&val.name);
         self.curr_fn_mut()
             .chunks
             .push(OpCode::DefineGlobal(val.name.line_no, x));
@@ -461,29 +1285,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
 
     fn visit_assign_stmt(&mut self, val: &Assign) -> Result<(), LoxError> {
         val.value.accept(self)?;
-
-        let mut x = self.resolve_local(&val.name);
-        if x == -1 {
-            x = self.resolve_upvalue(&val.name);
-            if x != -1 {
-                self.curr_fn_mut()
-                    .chunks
-                    .push(OpCode::SetUpvalue(val.name.line_no, x as usize));
-            } else {
-                // TODO: think of better approach
-                x = self.add_const(Object::Str(val.name.lexeme.clone())) as i32;
-                self.curr_fn_mut()
-                    .chunks
-                    .push(OpCode::SetGlobal(val.name.line_no, x as usize));
-                // x = self.add_const(Object::Str(val.name.lexeme.clone())) as i32;
-                // self.curr_fn_mut().chunks.push(OpCode::GetGlobal(val.name.line_no, x as usize));
-            }
-        } else {
-            self.curr_fn_mut()
-                .chunks
-                .push(OpCode::SetLocal(val.name.line_no, x as usize));
-        }
-
+        self.set_variable(&val.name);
         Ok(())
     }
 
@@ -525,6 +1327,8 @@ impl<'a> Visitor<()> for Compiler<'a> {
     fn visit_while_stmt(&mut self, val: &While) -> Result<(), LoxError> {
         let loop_start = self.curr_fn().chunks.len() + 1;
         let old_loop_start = self.loop_start;
+        let old_loop_finally = self.loop_finally;
+        self.loop_finally = self.finally_blocks.len();
         self.curr_fn_mut().chunks.push(OpCode::NoOp);
         val.condition.accept(self)?;
 
@@ -535,6 +1339,8 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.curr_fn_mut().chunks.push(OpCode::StackPop);
 
         self.loop_start = Some((loop_start, then_jump));
+        let locals = self.curr_fn().locals.len();
+        let old_loop_locals = std::mem::replace(&mut self.loop_locals, locals);
         val.body.accept(self)?;
 
         self.curr_fn_mut()
@@ -544,38 +1350,100 @@ impl<'a> Visitor<()> for Compiler<'a> {
             OpCode::JumpIfFalse(val.token.line_no, self.curr_fn().chunks.len());
         self.curr_fn_mut().chunks.push(OpCode::StackPop);
         self.loop_start = old_loop_start;
+        self.loop_finally = old_loop_finally;
+        self.loop_locals = old_loop_locals;
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(&mut self, val: &ForIn) -> Result<(), LoxError> {
+        let line_no = val.keyword.line_no;
+        self.begin_scope();
+        val.iterable.accept(self)?;
+        self.curr_fn_mut().chunks.push(OpCode::GetIterator(line_no));
+        self.mark(&val.keyword);
+        let iterator = self.hidden_local(&val.keyword, "iterator")?;
+
+        let old_loop_start = self.loop_start;
+        let old_loop_finally = std::mem::replace(&mut self.loop_finally, self.finally_blocks.len());
+        let locals = self.curr_fn().locals.len();
+        let old_loop_locals = std::mem::replace(&mut self.loop_locals, locals);
+        let loop_start = self.curr_fn().chunks.len();
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::GetLocal(line_no, iterator));
+        self.curr_fn_mut().chunks.push(OpCode::HasNext(line_no));
+        self.mark(&val.keyword);
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::JumpIfFalse(line_no, 9999));
+        let exit_jump = self.curr_fn().chunks.len() - 1;
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        self.loop_start = Some((loop_start, exit_jump));
+
+        // the item is bound in a scope of its own, so closures capture each one separately
+        self.begin_scope();
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::GetLocal(line_no, iterator));
+        self.curr_fn_mut().chunks.push(OpCode::Next(line_no));
+        self.mark(&val.keyword);
+        self.declare_variable(&val.name)?;
+        val.body.accept(self)?;
+        self.end_scope();
+
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::Jump(line_no, loop_start));
+        self.patch_jump(exit_jump);
+        self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        self.loop_start = old_loop_start;
+        self.loop_finally = old_loop_finally;
+        self.loop_locals = old_loop_locals;
+        self.end_scope();
         Ok(())
     }
 
     fn visit_break_stmt(&mut self, val: &Break) -> Result<(), LoxError> {
         if let Some((_, loop_start)) = self.loop_start {
+            self.exit_finally_blocks(self.loop_finally.max(self.fn_finally_start()))?;
+            self.pop_loop_locals();
             let x = self.add_const(Object::Bool(false));
-            self.curr_fn_mut().chunks.push(OpCode::Constant(x));
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::Constant(val.keyword.line_no, x));
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::Jump(val.keyword.line_no, loop_start));
             Ok(())
         } else {
-            return Err(LoxError::RuntimeError(
-                String::from("cannot continue"),
-                0,
-                String::from(""),
-            ));
+            return Err(LoxError::RuntimeError {
+                lexeme: String::from("cannot continue"),
+                line_no: 0,
+                message: String::from(""),
+                code: ErrorCode::ContinueOutsideLoop,
+                span: None,
+                backtrace: Box::new([]),
+            });
         }
     }
 
     fn visit_continue_stmt(&mut self, val: &Continue) -> Result<(), LoxError> {
         if let Some((cond_start, _)) = self.loop_start {
+            self.exit_finally_blocks(self.loop_finally.max(self.fn_finally_start()))?;
+            self.pop_loop_locals();
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::Jump(val.keyword.line_no, cond_start));
             Ok(())
         } else {
-            return Err(LoxError::RuntimeError(
-                String::from("cannot continue"),
-                0,
-                String::from(""),
-            ));
+            return Err(LoxError::RuntimeError {
+                lexeme: String::from("cannot continue"),
+                line_no: 0,
+                message: String::from(""),
+                code: ErrorCode::ContinueOutsideLoop,
+                span: None,
+                backtrace: Box::new([]),
+            });
         }
     }
 
@@ -589,7 +1457,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
             return Ok(());
         }
 
-        let x = self.add_const(Object::Str(val.name.lexeme.clone()));
+        let x = self.global_const(&val.name);
         self.curr_fn_mut()
             .chunks
             .push(OpCode::DefineGlobal(val.name.line_no, x));
@@ -601,11 +1469,14 @@ impl<'a> Visitor<()> for Compiler<'a> {
     fn visit_return_stmt(&mut self, val: &Return) -> Result<(), LoxError> {
         if let FunctionType::INIT = self.curr_fn().fn_type {
             if val.value.is_some() {
-                return Err(LoxError::RuntimeError(
-                    String::from("cannot return from init"),
-                    0,
-                    String::from(""),
-                ));
+                return Err(LoxError::RuntimeError {
+                    lexeme: String::from("cannot return from init"),
+                    line_no: 0,
+                    message: String::from(""),
+                    code: ErrorCode::ReturnFromInitializer,
+                    span: None,
+                    backtrace: Box::new([]),
+                });
             }
             self.curr_fn_mut().chunks.push(OpCode::GetLocal(0, 0));
         } else if let Some(vl) = &val.value {
@@ -613,12 +1484,42 @@ impl<'a> Visitor<()> for Compiler<'a> {
         } else {
             self.curr_fn_mut().chunks.push(OpCode::NilVal);
         }
+        let from = self.fn_finally_start();
+        if from < self.finally_blocks.len() {
+            // park the return value in a hidden local while the finally blocks run
+            let ret_val = Token::new(TokenType::IDENTIFIER, 0, None, String::from(""));
+            self.begin_scope();
+            self.declare_variable(&ret_val)?;
+            self.exit_finally_blocks(from)?;
+            let slot = self.curr_fn().locals.len() - 1;
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::GetLocal(val.keyword.line_no, slot));
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::Return(val.keyword.line_no));
+            self.end_scope();
+            return Ok(());
+        }
         self.curr_fn_mut()
             .chunks
             .push(OpCode::Return(val.keyword.line_no));
         Ok(())
     }
 
+    fn visit_yield_stmt(&mut self, val: &Yield) -> Result<(), LoxError> {
+        if let Some(value) = &val.value {
+            value.accept(self)?;
+        } else {
+            self.curr_fn_mut().chunks.push(OpCode::NilVal);
+        }
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::Yield(val.keyword.line_no));
+        self.mark(&val.keyword);
+        Ok(())
+    }
+
     fn visit_class_stmt(&mut self, val: &Class) -> Result<(), LoxError> {
         // TODO: Handle global declarations also inside the declare_variable() thing
         let x = self.add_const(Object::Str(val.name.lexeme.clone()));
@@ -630,6 +1531,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
         self.declare_variable(&val.name)?;
 
         if self.curr_fn().scope_depth == 0 {
+            let x = self.global_const(&val.name);
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::DefineGlobal(val.name.line_no, x));
@@ -651,6 +1553,7 @@ impl<'a> Visitor<()> for Compiler<'a> {
             self.curr_fn_mut()
                 .chunks
                 .push(OpCode::Inherit(super_class.name.line_no));
+            self.mark(&super_class.name);
         }
 
         self.named_variable(&val.name);
@@ -679,8 +1582,130 @@ impl<'a> Visitor<()> for Compiler<'a> {
         Ok(())
     }
 
-    fn visit_stack_trace_stmt(&mut self) -> Result<(), LoxError> {
-        self.curr_fn_mut().chunks.push(OpCode::PrintStackTrace);
+    fn visit_try_stmt(&mut self, val: &Try) -> Result<(), LoxError> {
+        let line_no = val.keyword.line_no;
+        if let Some(finally) = &val.finally {
+            self.finally_blocks.push(Finally {
+                body: finally.clone(),
+                fn_depth: self.scoped_fns.len(),
+                holes: vec![],
+            });
+        }
+
+        let start = self.curr_fn().chunks.len();
+        self.compile_clause(&val.body, None)?;
+        let body_end = self.curr_fn().chunks.len();
+        let mut exits = vec![body_end];
+        self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+
+        let catch_target = self.curr_fn().chunks.len();
+        if let Some(catch) = &val.catch {
+            self.compile_clause(&catch.body, Some(&catch.name))?;
+            exits.push(self.curr_fn().chunks.len());
+            self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+        }
+        let catch_end = self.curr_fn().chunks.len();
+        for exit in exits {
+            self.curr_fn_mut().chunks[exit] = OpCode::Jump(line_no, catch_end);
+        }
+
+        let mut holes = vec![];
+        let mut finally_target = None;
+        if let Some(finally) = &val.finally {
+            holes = self.finally_blocks.pop().unwrap().holes;
+            self.compile_clause(finally, None)?;
+            let end_jump = self.curr_fn().chunks.len();
+            self.curr_fn_mut().chunks.push(OpCode::Jump(line_no, 9999));
+
+            // exceptional path: run the finally body, then rethrow
+            finally_target = Some(self.curr_fn().chunks.len());
+            let exception = Token::new(TokenType::IDENTIFIER, 0, None, String::from(""));
+            self.begin_scope();
+            self.declare_variable(&exception)?;
+            let slot = self.curr_fn().locals.len() - 1;
+            finally.accept(self)?;
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::GetLocal(line_no, slot));
+            self.curr_fn_mut().chunks.push(OpCode::Throw(line_no));
+            self.end_scope();
+
+            let end = self.curr_fn().chunks.len();
+            self.curr_fn_mut().chunks[end_jump] = OpCode::Jump(line_no, end);
+        }
+
+        if val.catch.is_some() {
+            self.add_handlers(start, body_end, catch_target, &holes);
+        }
+        if let Some(target) = finally_target {
+            self.add_handlers(start, catch_end, target, &holes);
+        }
+        Ok(())
+    }
+
+    fn visit_match_stmt(&mut self, val: &Match) -> Result<(), LoxError> {
+        self.begin_scope();
+        val.subject.accept(self)?;
+        let subject = self.hidden_local(&val.keyword, "subject")?;
+        self.match_arms(&val.arms, subject, |compiler, body| body.accept(compiler))?;
+        self.end_scope();
+        Ok(())
+    }
+
+    fn visit_throw_stmt(&mut self, val: &Throw) -> Result<(), LoxError> {
+        val.value.accept(self)?;
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::Throw(val.keyword.line_no));
+        self.mark(&val.keyword);
+        Ok(())
+    }
+
+    fn visit_import_stmt(&mut self, val: &Import) -> Result<(), LoxError> {
+        let line_no = val.keyword.line_no;
+        let module = match self.import_module(&val.path, val.span) {
+            Ok(module) => module,
+            Err(err) => {
+                self.raise(line_no, err);
+                return Ok(());
+            }
+        };
+        if let Some(name) = &val.name {
+            let prefix = self.add_const(Object::Str(module.prefix.clone()));
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::Module(line_no, prefix));
+            self.mark(&val.path);
+            let x = self.global_const(name);
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::DefineGlobal(line_no, x));
+            self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        }
+        for name in &val.names {
+            if !module.exports.contains(&name.lexeme) {
+                let err = LoxError::runtime(name, ErrorCode::Undefined, "Undefined module member");
+                self.raise(name.line_no, err);
+                return Ok(());
+            }
+            let from = self.add_const(Object::Str(format!("{}{}", module.prefix, name.lexeme)));
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::GetGlobal(name.line_no, from));
+            self.mark(name);
+            let x = self.global_const(name);
+            self.curr_fn_mut()
+                .chunks
+                .push(OpCode::DefineGlobal(name.line_no, x));
+            self.curr_fn_mut().chunks.push(OpCode::StackPop);
+        }
+        Ok(())
+    }
+
+    fn visit_stack_trace_stmt(&mut self, val: &StackTrace) -> Result<(), LoxError> {
+        self.curr_fn_mut()
+            .chunks
+            .push(OpCode::PrintStackTrace(val.keyword.line_no));
         Ok(())
     }
 }
@@ -1,32 +1,257 @@
-// use crate::VM;
-// use crate::OpCode;
-
-// pub fn disassemble_chunk(vm: &VM, chunk_name: &str) {
-//     println!("===== {} =====", chunk_name);
-//     let mut pc = 0;
-//     // while pc < vm.len_inst() {
-//     //     pc = disassemble_inst(vm, pc);
-//     // }
-// }
-
-// pub fn disassemble_inst(vm: &VM, offset: usize) -> usize {
-//     use OpCode::*;
-
-//     // println!("{} ", offset);
-//     // let inst = vm.get_inst(offset).unwrap();
-//     // return match inst {
-//     //     Return(line) => simple_instruction("OP_RETURN",*line, offset),
-//     //     Constant(line, pos) => const_instruction("OP_CONST",*line, vm, *pos, offset),
-//     // }
-//     return 0;
-// }
-
-// pub fn simple_instruction(name: &str,line: u32, offset: usize) -> usize {
-//     println!("L{}: {} ",line , name);
-//     return offset + 1;
-// }
-
-// pub fn const_instruction(name: &str,line: u32, vm: &VM, pos: usize, offset: usize) -> usize {
-//     // println!("L{}: {} {:?} ",line , name, vm.get_const(pos));
-//     return offset + 1;
-// }
+use crate::chunk::{FuncSpec, Object, OpCode};
+use std::fmt::Write;
+use std::io;
+use std::ops::RangeInclusive;
+
+// Renders a function's chunks, followed by those of every closure it creates, methods included.
+pub fn disassemble(func: &FuncSpec, constant_pool: &[Object]) -> String {
+    let mut out = String::new();
+    disassemble_into(&mut out, func, constant_pool);
+    out
+}
+
+fn disassemble_into(out: &mut String, func: &FuncSpec, constant_pool: &[Object]) {
+    writeln!(out, "== {} ==", fn_name(func)).unwrap();
+    let mut prev_line = None;
+    for offset in 0..func.chunks.len() {
+        // the first instruction of a function always shows the line it's on
+        let line = match prev_line {
+            None => current_line(func, offset),
+            Some(_) => line_no(&func.chunks[offset]),
+        };
+        let same_line = line.is_none() || line == prev_line;
+        prev_line = line.or(prev_line);
+        let line = match (same_line, line) {
+            (false, Some(line)) => format!("{:4}", line),
+            _ => "   |".to_string(),
+        };
+        writeln!(
+            out,
+            "{:04} {} {}",
+            offset,
+            line,
+            disassemble_instruction(func, constant_pool, offset)
+        )
+        .unwrap();
+    }
+    for handler in &func.handlers {
+        writeln!(
+            out,
+            "handler [{:04}, {:04}) -> {:04} depth {}",
+            handler.start, handler.end, handler.target, handler.depth
+        )
+        .unwrap();
+    }
+    for op in &func.chunks {
+        if let OpCode::Closure(_, pos) = op {
+            if let Some(Object::Closure(inner)) = constant_pool.get(*pos) {
+                out.push('\n');
+                disassemble_into(out, inner, constant_pool);
+            }
+        }
+    }
+}
+
+// The opcode at offset, with its operands decoded.
+pub fn disassemble_instruction(func: &FuncSpec, constant_pool: &[Object], offset: usize) -> String {
+    use OpCode::*;
+
+    let op = &func.chunks[offset];
+    let debug = format!("{:?}", op);
+    let name = debug.split('(').next().unwrap_or(&debug);
+    let operand = match *op {
+        Constant(_, pos)
+        | DefineGlobal(_, pos)
+        | GetGlobal(_, pos)
+        | SetGlobal(_, pos)
+        | GetProperty(_, pos)
+        | SetProperty(_, pos)
+        | GetSuper(_, pos)
+        | ClassDef(_, pos)
+        | MethodDef(_, pos)
+        | Module(_, pos) => constant(constant_pool, pos),
+        GetLocal(_, slot) | SetLocal(_, slot) => format!("{:4} slot", slot),
+        GetUpvalue(_, index) | SetUpvalue(_, index) => format!("{:4} upvalue", index),
+        BuildList(_, count) | BuildMap(_, count) | MatchList(_, count) => {
+            format!("{:4} elements", count)
+        }
+        Call(_, args) => format!("{:4} args", args),
+        Duplicate(count) | Bury(count) => format!("{:4} values", count),
+        Jump(_, target) | JumpIfFalse(_, target) => format!("  -> {:04}", target),
+        Closure(_, pos) => {
+            let mut operand = constant(constant_pool, pos);
+            if let Some(Object::Closure(inner)) = constant_pool.get(pos) {
+                for (index, is_local) in &inner.upvalues {
+                    let kind = if *is_local { "local" } else { "upvalue" };
+                    write!(operand, " [{} {}]", kind, index).unwrap();
+                }
+            }
+            operand
+        }
+        _ => String::new(),
+    };
+    format!("{:<16} {}", name, operand).trim_end().to_string()
+}
+
+fn constant(constant_pool: &[Object], pos: usize) -> String {
+    match constant_pool.get(pos) {
+        Some(Object::Str(val)) => format!("{:4} '{}'", pos, val),
+        Some(Object::Closure(func)) => format!("{:4} <fn {}>", pos, fn_name(func)),
+        Some(val) => format!("{:4} {}", pos, val),
+        None => format!("{:4} ???", pos),
+    }
+}
+
+fn fn_name(func: &FuncSpec) -> String {
+    match &func.name {
+        Some(name) => name.clone(),
+        None => "<script>".to_string(),
+    }
+}
+
+pub fn line_no(op: &OpCode) -> Option<u32> {
+    use OpCode::*;
+
+    match *op {
+        Return(line)
+        | Exit(line)
+        | Negate(line)
+        | Not(line)
+        | BitNot(line)
+        | Stringify(line)
+        | Add(line)
+        | Divide(line)
+        | Multiply(line)
+        | Subs(line)
+        | IntDivide(line)
+        | Modulo(line)
+        | Power(line)
+        | BitAnd(line)
+        | BitOr(line)
+        | BitXor(line)
+        | ShiftLeft(line)
+        | ShiftRight(line)
+        | GreaterThan(line)
+        | GreaterThanEq(line)
+        | LesserThan(line)
+        | LesserThanEq(line)
+        | NotEqualTo(line)
+        | EqualTo(line)
+        | BoolAnd(line)
+        | BoolOr(line)
+        | Print(line)
+        | GetIndex(line)
+        | SetIndex(line)
+        | MatchClass(line)
+        | GetIterator(line)
+        | HasNext(line)
+        | Next(line)
+        | Yield(line)
+        | Throw(line)
+        | Inherit(line)
+        | PrintStackTrace(line) => Some(line),
+        Constant(line, _)
+        | DefineGlobal(line, _)
+        | GetGlobal(line, _)
+        | SetGlobal(line, _)
+        | GetLocal(line, _)
+        | SetLocal(line, _)
+        | GetUpvalue(line, _)
+        | SetUpvalue(line, _)
+        | GetProperty(line, _)
+        | SetProperty(line, _)
+        | GetSuper(line, _)
+        | BuildList(line, _)
+        | BuildMap(line, _)
+        | MatchList(line, _)
+        | JumpIfFalse(line, _)
+        | Jump(line, _)
+        | Module(line, _)
+        | Raise(line, _)
+        | Call(line, _)
+        | Closure(line, _)
+        | ClassDef(line, _)
+        | MethodDef(line, _) => Some(line),
+        StackPop | Duplicate(_) | Bury(_) | CloseUpvalue | NilVal | NoOp => None,
+    }
+}
+
+// The line an instruction belongs to: its own, else that of the closest one before it,
+// or failing that the closest one after it.
+pub fn current_line(func: &FuncSpec, offset: usize) -> Option<u32> {
+    let (before, after) = func.chunks.split_at(offset + 1);
+    before.iter().rev().chain(after).find_map(line_no)
+}
+
+// Logs every instruction the VM runs, as long as the VM is run with is_debug set. Instructions
+// can be narrowed down to those of one function, or of a range of lines.
+pub struct Tracer {
+    sink: Box<dyn io::Write>,
+    function: Option<String>,
+    lines: Option<RangeInclusive<u32>>,
+}
+
+impl Tracer {
+    pub fn new(sink: Box<dyn io::Write>) -> Self {
+        Tracer {
+            sink,
+            function: None,
+            lines: None,
+        }
+    }
+
+    pub fn function<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.function = Some(name.as_ref().to_string());
+        self
+    }
+
+    pub fn lines(mut self, lines: RangeInclusive<u32>) -> Self {
+        self.lines = Some(lines);
+        self
+    }
+
+    // One line per instruction: the frame depth and function, the instruction and the stack
+    // before it runs, with `|` marking where the frame's slots begin.
+    pub fn trace(
+        &mut self,
+        depth: usize,
+        func: &FuncSpec,
+        ip: usize,
+        slot: usize,
+        constant_pool: &[Object],
+        stack: &[Object],
+    ) {
+        let name = fn_name(func);
+        if matches!(&self.function, Some(function) if *function != name) {
+            return;
+        }
+        let line = current_line(func, ip);
+        if let Some(lines) = &self.lines {
+            if !matches!(line, Some(line) if lines.contains(&line)) {
+                return;
+            }
+        }
+        let mut snapshot = String::new();
+        for (i, value) in stack.iter().enumerate() {
+            if i == slot {
+                snapshot.push_str("| ");
+            }
+            write!(snapshot, "{} ", value).unwrap();
+        }
+        if slot >= stack.len() {
+            snapshot.push_str("| ");
+        }
+        let line = line.map_or("   ?".to_string(), |line| format!("{:4}", line));
+        // a broken sink shouldn't stop the script it's tracing
+        let _ = writeln!(
+            self.sink,
+            "[{} {}] {:04} {} {:<40} [ {}]",
+            depth,
+            name,
+            ip,
+            line,
+            disassemble_instruction(func, constant_pool, ip),
+            snapshot
+        );
+    }
+}
@@ -114,6 +114,8 @@ impl Trace for Object {
         match self {
             Object::Str(_) => {}
             Object::Num(_) => {}
+            Object::Int(_) => {}
+            Object::BigInt(val) => val.trace(),
             Object::Bool(_) => {}
             Object::Nil => {}
             // Object::Function(_) => {}
@@ -128,6 +130,11 @@ impl Trace for Object {
             Object::InstanceBindDef(val) => {
                 todo!()
             }
+            Object::List(val) => val.trace(),
+            Object::Map(val) => val.trace(),
+            Object::Iterator(val) => val.trace(),
+            Object::Generator(val) => val.trace(),
+            Object::GeneratorMethod(val, _) => val.trace(),
         }
     }
 }
@@ -161,6 +168,9 @@ impl Trace for FuncSpec {
 impl Trace for CallFrame {
     fn trace(&mut self) {
         self.func.trace();
+        if let Some((generator, _)) = &mut self.generator {
+            generator.trace();
+        }
     }
 }
 
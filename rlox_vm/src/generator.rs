@@ -0,0 +1,90 @@
+use crate::{
+    chunk::{FuncSpec, Object, UpValueWrap},
+    gc::{
+        heap::Heap,
+        root::{CustomClone, Root, Trace, UniqueRoot},
+    },
+};
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorState {
+    // not started yet, or stopped at a yield
+    Suspended,
+    Running,
+    Done,
+}
+
+// What calling a function with a yield in it returns. While suspended it holds the stack slice
+// of its frame, which is moved back onto the stack each time it's resumed.
+pub struct Generator {
+    pub func: UniqueRoot<FuncSpec>,
+    pub ip: Cell<usize>,
+    // the frame's slots, the function itself first
+    pub stack: RefCell<Vec<Object>>,
+    // the upvalues that were open on those slots, with the offsets of the slots they point to
+    pub upvalues: RefCell<Vec<(usize, Root<UpValueWrap>)>>,
+    // the value a hasNext ran the generator up to, for the next to return
+    pub next: RefCell<Option<Object>>,
+    pub state: Cell<GeneratorState>,
+}
+
+impl Generator {
+    pub fn new(func: UniqueRoot<FuncSpec>, stack: Vec<Object>) -> Self {
+        Generator {
+            func,
+            ip: Cell::new(0),
+            stack: RefCell::new(stack),
+            upvalues: RefCell::new(vec![]),
+            next: RefCell::new(None),
+            state: Cell::new(GeneratorState::Suspended),
+        }
+    }
+    pub fn name(&self) -> String {
+        self.func
+            .name
+            .clone()
+            .unwrap_or_else(|| "Lambda".to_string())
+    }
+}
+
+impl Trace for Generator {
+    fn trace(&mut self) {
+        self.func.trace();
+        self.stack.trace();
+        for (_, upvalue) in self.upvalues.borrow_mut().iter_mut() {
+            upvalue.trace();
+        }
+        if let Some(next) = self.next.borrow_mut().as_mut() {
+            next.trace();
+        }
+    }
+}
+
+impl CustomClone for Generator {
+    fn clone(&self, gc: &Heap) -> Self {
+        let upvalues = self
+            .upvalues
+            .borrow()
+            .iter()
+            .map(|(offset, upvalue)| (*offset, upvalue.clone(gc)))
+            .collect();
+        Generator {
+            func: self.func.clone(gc),
+            ip: self.ip.clone(),
+            stack: self.stack.clone(gc),
+            upvalues: RefCell::new(upvalues),
+            next: self.next.clone(gc),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Generator<{}>", self.name()))
+    }
+}
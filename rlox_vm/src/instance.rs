@@ -18,6 +18,9 @@ use std::fmt::Debug;
 pub struct Instance {
     pub class: Root<Class>,
     fields: RefCell<HashMap<String, Object>>,
+    // for the namespace of a module, the prefix of the globals it exports, which its
+    // properties read and assign
+    pub module: Option<String>,
 }
 
 impl Instance {
@@ -25,6 +28,14 @@ impl Instance {
         Instance {
             class,
             fields: RefCell::new(HashMap::new()),
+            module: None,
+        }
+    }
+
+    pub fn module(class: Root<Class>, prefix: String) -> Self {
+        Instance {
+            module: Some(prefix),
+            ..Instance::new(class)
         }
     }
     pub fn set(&self, k: String, v: Object) {
@@ -63,6 +74,7 @@ impl CustomClone for Instance {
         Instance {
             class: self.class.clone(gc),
             fields: self.fields.clone(gc),
+            module: self.module.clone(),
         }
     }
 }
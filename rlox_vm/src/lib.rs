@@ -1,9 +1,13 @@
+pub mod big_int;
 pub mod chunk;
 pub mod class;
 pub mod commons;
 pub mod compiler;
 pub mod debug;
 pub mod gc;
+pub mod generator;
 pub mod instance;
+pub mod list;
+pub mod map;
 pub mod resolver;
 pub mod system_calls;
@@ -0,0 +1,56 @@
+use crate::{
+    chunk::Object,
+    gc::{
+        heap::Heap,
+        root::{CustomClone, Trace},
+    },
+};
+use std::{cell::RefCell, fmt::Debug};
+
+pub struct List {
+    pub values: RefCell<Vec<Object>>,
+}
+
+impl List {
+    pub fn new(values: Vec<Object>) -> List {
+        List {
+            values: RefCell::new(values),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.borrow().is_empty()
+    }
+    pub fn get(&self, index: usize, gc: &Heap) -> Option<Object> {
+        self.values.borrow().get(index).map(|v| v.clone(gc))
+    }
+    pub fn set(&self, index: usize, val: Object) -> bool {
+        if let Some(slot) = self.values.borrow_mut().get_mut(index) {
+            *slot = val;
+            return true;
+        }
+        false
+    }
+}
+
+impl Trace for List {
+    fn trace(&mut self) {
+        self.values.trace();
+    }
+}
+
+impl CustomClone for List {
+    fn clone(&self, gc: &Heap) -> Self {
+        List {
+            values: self.values.clone(gc),
+        }
+    }
+}
+
+impl Debug for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("List<{}>", self.len()))
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    chunk::Object,
+    gc::{
+        heap::Heap,
+        root::{CustomClone, Trace},
+    },
+};
+use rlox_core::runtime::definitions::{big_int::BigInt, number::Number};
+use std::{cell::RefCell, collections::HashMap, fmt::Debug};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Str(String),
+    Num(u64),
+    Int(i64),
+    Big(BigInt),
+    Bool(bool),
+    Instance(usize),
+}
+
+impl MapKey {
+    pub fn from_object(key: &Object) -> Option<Self> {
+        match key {
+            Object::Str(val) => Some(MapKey::Str(val.clone())),
+            // keys comparing equal hash the same: 1 and 1.0 are one key, as are -0.0 and 0
+            Object::Int(_) | Object::BigInt(_) | Object::Num(_) => {
+                let num = key.as_number()?;
+                Some(match num.clone().as_integer() {
+                    Some(Number::Int(val)) => MapKey::Int(val),
+                    Some(Number::Big(val)) => MapKey::Big(val),
+                    _ => MapKey::Num(num.as_f64().to_bits()),
+                })
+            }
+            Object::Bool(val) => Some(MapKey::Bool(*val)),
+            Object::InstanceDef(val) => Some(MapKey::Instance(val.data.as_ptr() as usize)),
+            _ => None,
+        }
+    }
+}
+
+// entries are kept in insertion order so that key iteration is deterministic
+pub struct Map {
+    pub entries: RefCell<Vec<(Object, Object)>>,
+    index: RefCell<HashMap<MapKey, usize>>,
+}
+
+impl Map {
+    pub fn new() -> Map {
+        Map {
+            entries: RefCell::new(vec![]),
+            index: RefCell::new(HashMap::new()),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+    pub fn get(&self, key: &MapKey, gc: &Heap) -> Option<Object> {
+        self.index
+            .borrow()
+            .get(key)
+            .map(|pos| self.entries.borrow()[*pos].1.clone(gc))
+    }
+    pub fn set(&self, key: MapKey, key_obj: Object, val: Object) {
+        let pos = self.index.borrow().get(&key).cloned();
+        if let Some(pos) = pos {
+            self.entries.borrow_mut()[pos].1 = val;
+        } else {
+            self.index.borrow_mut().insert(key, self.len());
+            self.entries.borrow_mut().push((key_obj, val));
+        }
+    }
+    pub fn keys(&self, gc: &Heap) -> Vec<Object> {
+        self.entries
+            .borrow()
+            .iter()
+            .map(|(key, _)| key.clone(gc))
+            .collect()
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Map::new()
+    }
+}
+
+impl Trace for Map {
+    fn trace(&mut self) {
+        for (key, val) in self.entries.borrow_mut().iter_mut() {
+            key.trace();
+            val.trace();
+        }
+    }
+}
+
+impl CustomClone for Map {
+    fn clone(&self, gc: &Heap) -> Self {
+        let entries = self
+            .entries
+            .borrow()
+            .iter()
+            .map(|(key, val)| (key.clone(gc), val.clone(gc)))
+            .collect();
+        Map {
+            entries: RefCell::new(entries),
+            index: RefCell::new(self.index.borrow().clone()),
+        }
+    }
+}
+
+impl Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Map<{}>", self.len()))
+    }
+}
@@ -4,10 +4,12 @@ use rlox_core::frontend::lexer::*;
 
 use rlox_core::runtime::visitor::{VisitorMut, VisitorMutAcceptor};
 
-use rlox_core::error::LoxError;
+use rlox_core::error::{ErrorCode, LoxError};
 use rlox_core::frontend::definitions::class_type::ClassType;
 use rlox_core::frontend::definitions::function_type::FunctionType;
 use rlox_core::frontend::definitions::literal::Literal;
+use rlox_core::frontend::definitions::pattern::{MatchArm, Pattern};
+use rlox_core::frontend::definitions::span::Span;
 use rlox_core::frontend::definitions::token::Token;
 use std::collections::HashMap;
 
@@ -18,28 +20,64 @@ use std::collections::HashMap;
 // make this into a chain of resolvers, with different purpose and static checks etc.
 //////
 
+// bool corresponds to if the value has been initialized, the span to where it was declared
+type Scope = HashMap<String, (bool, Option<Span>)>;
+
 pub struct Resolver {
-    // bool corresponds to if the value has been initialized
-    pub fn_scopes: Vec<Vec<HashMap<String, bool>>>,
+    pub fn_scopes: Vec<Vec<Scope>>,
     curr_class: ClassType,
     curr_function: FunctionType,
     ignore_def_check: bool,
+    // how many loops the code being resolved is in, within the current function
+    loop_depth: usize,
+    errors: Vec<LoxError>,
 }
 
 impl Resolver {
     pub fn new() -> Self {
         Resolver {
-            fn_scopes: vec![vec![HashMap::new()]],
+            // the top level has the globals defined outside of the resolved code, which its own
+            // may shadow, under its own
+            fn_scopes: vec![vec![HashMap::new(), HashMap::new()]],
             curr_class: ClassType::NONE,
             curr_function: FunctionType::NONE,
             ignore_def_check: false,
+            loop_depth: 0,
+            errors: vec![],
         }
     }
 
-    pub fn resolve<T: VisitorMutAcceptor<()> + Sized>(
-        &mut self,
-        expr: &mut T,
-    ) -> Result<(), LoxError> {
+    // Resolves a whole script, going on after errors so that all of them are reported, in the
+    // order they appear in.
+    pub fn resolve_all(&mut self, ast: &mut Vec<Stmt>) -> Result<(), Vec<LoxError>> {
+        let _ = self.resolve(ast);
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        self.reset();
+        Err(std::mem::take(&mut self.errors))
+    }
+
+    // Drops the scopes a failed resolve left open, keeping the globals seen so far.
+    fn reset(&mut self) {
+        self.fn_scopes.truncate(1);
+        self.fn_scopes[0].truncate(2);
+        self.curr_class = ClassType::NONE;
+        self.curr_function = FunctionType::NONE;
+        self.ignore_def_check = false;
+        self.loop_depth = 0;
+    }
+
+    fn error(&mut self, err: LoxError) {
+        self.errors.push(err);
+    }
+
+    // Makes a global defined outside of the resolved code, like the prelude's, visible to it.
+    pub fn define_global(&mut self, name: &str) {
+        self.fn_scopes[0][0].insert(name.to_string(), (true, None));
+    }
+
+    fn resolve<T: VisitorMutAcceptor<()> + Sized>(&mut self, expr: &mut T) -> Result<(), LoxError> {
         expr.accept(self)
     }
 
@@ -54,11 +92,7 @@ impl Resolver {
             }
         }
         if !self.ignore_def_check {
-            return Err(LoxError::SemanticError(
-                name.lexeme.clone(),
-                name.line_no,
-                String::from("Undefined"),
-            ));
+            self.error(LoxError::semantic(name, ErrorCode::Undefined, "Undefined"));
         }
         return Ok(());
     }
@@ -85,11 +119,12 @@ impl Resolver {
     fn declare(&mut self, token: &Token) -> Result<(), LoxError> {
         if let Some(scopes) = self.fn_scopes.last_mut() {
             if let Some(scope) = scopes.last_mut() {
-                if scope.insert(token.lexeme.clone(), false).is_some() {
-                    return Err(LoxError::SemanticError(
-                        token.lexeme.clone(),
-                        token.line_no,
-                        String::from("Already exists"),
+                let declared = (false, Some(token.span));
+                if let Some((_, first)) = scope.insert(token.lexeme.clone(), declared) {
+                    self.error(LoxError::semantic(
+                        token,
+                        ErrorCode::AlreadyExists(first.map(Box::new)),
+                        "Already exists",
                     ));
                 }
             }
@@ -101,13 +136,16 @@ impl Resolver {
     fn define(&mut self, token: &Token) -> Result<(), LoxError> {
         if let Some(scopes) = self.fn_scopes.last_mut() {
             if let Some(scope) = scopes.last_mut() {
-                scope
-                    .insert(token.lexeme.clone(), true)
-                    .ok_or(LoxError::SemanticError(
-                        token.lexeme.clone(),
-                        token.line_no,
-                        "Declaration without definition".to_string(),
-                    ))?;
+                if scope
+                    .insert(token.lexeme.clone(), (true, Some(token.span)))
+                    .is_none()
+                {
+                    self.error(LoxError::semantic(
+                        token,
+                        ErrorCode::DeclarationWithoutDefinition,
+                        "Declaration without definition",
+                    ));
+                }
             }
         }
         Ok(())
@@ -122,11 +160,12 @@ impl Resolver {
                 .unwrap()
                 .last_mut()
                 .unwrap()
-                .insert("this".to_string(), true);
+                .insert("this".to_string(), (true, None));
         }
 
         let currfn = self.curr_function;
         self.curr_function = dec;
+        let loop_depth = std::mem::take(&mut self.loop_depth);
 
         for param in &func.params {
             self.declare(&param)?;
@@ -135,6 +174,74 @@ impl Resolver {
         self.resolve(&mut func.body)?;
         self.end_fn_scope();
         self.curr_function = currfn;
+        self.loop_depth = loop_depth;
+        Ok(())
+    }
+
+    // resolves a try/catch/finally body in its own scope, with the catch variable declared in it
+    fn resolve_clause(
+        &mut self,
+        body: &mut Vec<Stmt>,
+        name: Option<&Token>,
+    ) -> Result<(), LoxError> {
+        self.begin_scope();
+        if let Some(name) = name {
+            self.declare(name)?;
+            self.define(name)?;
+        }
+        let res = self.resolve(body);
+        self.end_scope();
+        res
+    }
+
+    // Resolves each arm in its own scope, holding the names its pattern binds. An arm after one
+    // that matches anything could never run.
+    fn resolve_arms<T: VisitorMutAcceptor<()>>(
+        &mut self,
+        arms: &mut [MatchArm<T>],
+    ) -> Result<(), LoxError> {
+        let count = arms.len();
+        for (i, arm) in arms.iter_mut().enumerate() {
+            if arm.pattern.is_irrefutable() && arm.guard.is_none() && i + 1 < count {
+                let token = arm.pattern.token();
+                self.error(LoxError::semantic(
+                    token,
+                    ErrorCode::Resolve,
+                    "Wildcard arm must come last",
+                ));
+            }
+            self.begin_scope();
+            self.resolve_pattern(&mut arm.pattern)?;
+            if let Some(guard) = &mut arm.guard {
+                self.resolve(guard)?;
+            }
+            let res = self.resolve(&mut arm.body);
+            self.end_scope();
+            res?;
+        }
+        Ok(())
+    }
+
+    fn resolve_pattern(&mut self, pattern: &mut Pattern) -> Result<(), LoxError> {
+        match pattern {
+            Pattern::Binding(name) => {
+                self.declare(name)?;
+                self.define(name)?;
+            }
+            Pattern::Class(class, name) => {
+                self.visit_variable_stmt(class)?;
+                if let Some(name) = name {
+                    self.declare(name)?;
+                    self.define(name)?;
+                }
+            }
+            Pattern::List(_, elements) => {
+                for element in elements {
+                    self.resolve_pattern(element)?;
+                }
+            }
+            Pattern::Wildcard(_) | Pattern::Literal(..) => {}
+        }
         Ok(())
     }
 
@@ -142,6 +249,7 @@ impl Resolver {
         self.begin_fn_scope();
         let currfn = self.curr_function;
         self.curr_function = FunctionType::LAMBDA;
+        let loop_depth = std::mem::take(&mut self.loop_depth);
 
         for param in &func.params {
             self.declare(&param)?;
@@ -150,6 +258,7 @@ impl Resolver {
         self.resolve(&mut func.body)?;
         self.end_fn_scope();
         self.curr_function = currfn;
+        self.loop_depth = loop_depth;
         Ok(())
     }
 }
@@ -183,7 +292,7 @@ impl VisitorMut<()> for Resolver {
         Ok(())
     }
 
-    fn visit_literal_expr(&mut self, _val: &mut Literal) -> Result<(), LoxError> {
+    fn visit_literal_expr(&mut self, _val: &mut Literal, _span: Span) -> Result<(), LoxError> {
         Ok(())
     }
 
@@ -211,11 +320,12 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_this_expr(&mut self, val: &mut This) -> Result<(), LoxError> {
         if self.curr_class == ClassType::NONE {
-            return Err(LoxError::SemanticError(
-                val.keyword.lexeme.clone(),
-                val.keyword.line_no,
-                "Cannot use this outside class.".to_string(),
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ThisOutsideClass,
+                "Cannot use this outside class.",
             ));
+            return Ok(());
         }
         self.resolve_local(&mut val.keyword)?;
         Ok(())
@@ -223,17 +333,80 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_super_expr(&mut self, val: &mut Super) -> Result<(), LoxError> {
         if self.curr_class != ClassType::SUBCLASS {
-            return Err(LoxError::SemanticError(
-                val.keyword.lexeme.clone(),
-                val.keyword.line_no,
-                "No super class as such.".to_string(),
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::SuperOutsideSubclass,
+                "No super class as such.",
             ));
+            return Ok(());
         }
         // self.resolve_local(&mut Token::new(TokenType::THIS, 0, None, String::from("this"))?;
         self.resolve_local(&mut val.keyword)?;
         Ok(())
     }
 
+    fn visit_list_expr(&mut self, val: &mut List) -> Result<(), LoxError> {
+        for element in &mut val.elements {
+            self.resolve(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_map_expr(&mut self, val: &mut Map) -> Result<(), LoxError> {
+        for (key, value) in &mut val.entries {
+            self.resolve(key)?;
+            self.resolve(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_index_expr(&mut self, val: &mut Index) -> Result<(), LoxError> {
+        self.resolve(&mut val.object)?;
+        self.resolve(&mut val.index)?;
+        Ok(())
+    }
+
+    fn visit_set_index_expr(&mut self, val: &mut SetIndex) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        self.resolve(&mut val.object)?;
+        self.resolve(&mut val.index)?;
+        Ok(())
+    }
+
+    fn visit_compound_expr(&mut self, val: &mut Compound) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        self.resolve(&mut val.target)?;
+        Ok(())
+    }
+
+    fn visit_conditional_expr(&mut self, val: &mut Conditional) -> Result<(), LoxError> {
+        self.resolve(&mut val.condition)?;
+        self.resolve(&mut val.then_branch)?;
+        self.resolve(&mut val.else_branch)?;
+        Ok(())
+    }
+
+    fn visit_coalesce_expr(&mut self, val: &mut Coalesce) -> Result<(), LoxError> {
+        self.resolve(&mut val.left)?;
+        self.resolve(&mut val.right)?;
+        Ok(())
+    }
+
+    fn visit_safe_get_expr(&mut self, val: &mut SafeGet) -> Result<(), LoxError> {
+        self.resolve(&mut val.object)?;
+        Ok(())
+    }
+
+    fn visit_optional_chain_expr(&mut self, val: &mut OptionalChain) -> Result<(), LoxError> {
+        self.resolve(&mut val.expr)?;
+        Ok(())
+    }
+
+    fn visit_match_expr(&mut self, val: &mut MatchExpr) -> Result<(), LoxError> {
+        self.resolve(&mut val.subject)?;
+        self.resolve_arms(&mut val.arms)
+    }
+
     fn visit_expression_stmt(&mut self, val: &mut Expression) -> Result<(), LoxError> {
         self.resolve(&mut val.expr)?;
         Ok(())
@@ -247,12 +420,13 @@ impl VisitorMut<()> for Resolver {
     fn visit_variable_stmt(&mut self, val: &mut Variable) -> Result<(), LoxError> {
         if let Some(scopes) = self.fn_scopes.last_mut() {
             if let Some(scope) = scopes.last_mut() {
-                if let Some(false) = scope.get(&val.name.lexeme) {
-                    return Err(LoxError::SemanticError(
-                        val.name.lexeme.clone(),
-                        val.name.line_no,
-                        "Cannot read local variable in its own initializer.".to_string(),
+                if let Some((false, _)) = scope.get(&val.name.lexeme) {
+                    self.error(LoxError::semantic(
+                        &val.name,
+                        ErrorCode::OwnInitializer,
+                        "Cannot read local variable in its own initializer.",
                     ));
+                    return Ok(());
                 }
             }
         }
@@ -294,18 +468,44 @@ impl VisitorMut<()> for Resolver {
 
     fn visit_while_stmt(&mut self, val: &mut While) -> Result<(), LoxError> {
         self.resolve(&mut val.condition)?;
-        match self.resolve(&mut val.body) {
-            Err(LoxError::Break(_)) | Err(LoxError::Continue(_)) | Ok(_) => Ok(()),
-            Err(err) => Err(err),
-        }
+        self.loop_depth += 1;
+        self.resolve(&mut val.body)?;
+        self.loop_depth -= 1;
+        Ok(())
+    }
+
+    fn visit_for_in_stmt(&mut self, val: &mut ForIn) -> Result<(), LoxError> {
+        self.resolve(&mut val.iterable)?;
+        self.begin_scope();
+        self.declare(&val.name)?;
+        self.define(&val.name)?;
+        self.loop_depth += 1;
+        let res = self.resolve(&mut val.body);
+        self.loop_depth -= 1;
+        self.end_scope();
+        res
     }
 
     fn visit_break_stmt(&mut self, val: &mut Break) -> Result<(), LoxError> {
-        Err(LoxError::Break(val.keyword.line_no))
+        if self.loop_depth == 0 {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::BreakOutsideLoop,
+                "break statements allowed only inside loops.",
+            ));
+        }
+        Ok(())
     }
 
     fn visit_continue_stmt(&mut self, val: &mut Continue) -> Result<(), LoxError> {
-        Err(LoxError::Continue(val.keyword.line_no))
+        if self.loop_depth == 0 {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ContinueOutsideLoop,
+                "continue statements allowed only inside loops.",
+            ));
+        }
+        Ok(())
     }
 
     fn visit_function_stmt(&mut self, val: &mut Function) -> Result<(), LoxError> {
@@ -318,17 +518,17 @@ impl VisitorMut<()> for Resolver {
     fn visit_return_stmt(&mut self, val: &mut Return) -> Result<(), LoxError> {
         if let Some(value) = &mut val.value {
             if self.curr_function == FunctionType::NONE {
-                return Err(LoxError::SemanticError(
-                    val.keyword.lexeme.clone(),
-                    val.keyword.line_no,
-                    "Return only from function".to_string(),
+                self.error(LoxError::semantic(
+                    &val.keyword,
+                    ErrorCode::ReturnOutsideFunction,
+                    "Return only from function",
                 ));
             }
             if self.curr_function == FunctionType::INITIALIZER {
-                return Err(LoxError::SemanticError(
-                    val.keyword.lexeme.clone(),
-                    val.keyword.line_no,
-                    "Cannot return from initializer".to_string(),
+                self.error(LoxError::semantic(
+                    &val.keyword,
+                    ErrorCode::ReturnFromInitializer,
+                    "Cannot return from initializer",
                 ));
             }
             self.resolve(value)?;
@@ -336,6 +536,27 @@ impl VisitorMut<()> for Resolver {
         Ok(())
     }
 
+    fn visit_yield_stmt(&mut self, val: &mut Yield) -> Result<(), LoxError> {
+        if self.curr_function == FunctionType::NONE {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::YieldOutsideFunction,
+                "Yield only from function",
+            ));
+        }
+        if self.curr_function == FunctionType::INITIALIZER {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ReturnFromInitializer,
+                "Cannot yield from initializer",
+            ));
+        }
+        if let Some(value) = &mut val.value {
+            self.resolve(value)?;
+        }
+        Ok(())
+    }
+
     fn visit_class_stmt(&mut self, val: &mut Class) -> Result<(), LoxError> {
         let curr_class = self.curr_class;
         self.curr_class = ClassType::CLASS;
@@ -344,10 +565,10 @@ impl VisitorMut<()> for Resolver {
         self.resolve_local(&mut val.name)?;
         if let Some(sp_class) = &mut val.superclass {
             if sp_class.name.lexeme == val.name.lexeme {
-                return Err(LoxError::SemanticError(
-                    val.name.lexeme.clone(),
-                    val.name.line_no,
-                    "Class can't inherit itself".to_string(),
+                self.error(LoxError::semantic(
+                    &val.name,
+                    ErrorCode::InheritsItself,
+                    "Class can't inherit itself",
                 ));
             }
             self.curr_class = ClassType::SUBCLASS;
@@ -359,7 +580,7 @@ impl VisitorMut<()> for Resolver {
                 .unwrap()
                 .last_mut()
                 .unwrap()
-                .insert("super".to_string(), true);
+                .insert("super".to_string(), (true, None));
         }
 
         self.begin_scope();
@@ -380,8 +601,46 @@ impl VisitorMut<()> for Resolver {
         Ok(())
     }
 
-    fn visit_stack_trace_stmt(&mut self) -> Result<(), LoxError> {
-        // todo!()
+    fn visit_try_stmt(&mut self, val: &mut Try) -> Result<(), LoxError> {
+        self.resolve_clause(&mut val.body, None)?;
+        if let Some(catch) = &mut val.catch {
+            self.resolve_clause(&mut catch.body, Some(&catch.name))?;
+        }
+        if let Some(finally) = &mut val.finally {
+            self.resolve_clause(finally, None)?;
+        }
+        Ok(())
+    }
+
+    fn visit_match_stmt(&mut self, val: &mut Match) -> Result<(), LoxError> {
+        self.resolve(&mut val.subject)?;
+        self.resolve_arms(&mut val.arms)
+    }
+
+    fn visit_throw_stmt(&mut self, val: &mut Throw) -> Result<(), LoxError> {
+        self.resolve(&mut val.value)?;
+        Ok(())
+    }
+
+    fn visit_import_stmt(&mut self, val: &mut Import) -> Result<(), LoxError> {
+        // modules are globals, and are run in the order their imports appear
+        if self.fn_scopes.len() > 1 || self.fn_scopes[0].len() > 2 {
+            self.error(LoxError::semantic(
+                &val.keyword,
+                ErrorCode::ImportOutsideTopLevel,
+                "Can't import outside top-level code.",
+            ));
+            return Ok(());
+        }
+        for name in val.name.iter_mut().chain(val.names.iter_mut()) {
+            self.declare(name)?;
+            self.define(name)?;
+            self.resolve_local(name)?;
+        }
+        Ok(())
+    }
+
+    fn visit_stack_trace_stmt(&mut self, _val: &mut StackTrace) -> Result<(), LoxError> {
         Ok(())
     }
 }
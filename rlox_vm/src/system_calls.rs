@@ -12,7 +12,7 @@ use std::time::SystemTime;
 pub trait SystemCalls {
     fn print(&mut self, arg: &Object, gc: &Heap);
     fn time(&mut self) -> Result<Object, LoxError>;
-    fn print_error(&mut self, err: LoxError);
+    fn print_error(&mut self, report: &str);
 }
 
 pub struct SystemInterface();
@@ -30,8 +30,8 @@ impl SystemCalls for SystemInterface {
         Ok(Object::Num(curr_time.as_millis() as f64))
     }
 
-    fn print_error(&mut self, err: LoxError) {
-        println!("[error] {}", err);
+    fn print_error(&mut self, report: &str) {
+        eprint!("{}", report);
     }
 }
 
@@ -54,5 +54,5 @@ impl SystemCalls for SystemInterfaceMock {
         Ok(Object::Num(0.0))
     }
 
-    fn print_error(&mut self, err: LoxError) {}
+    fn print_error(&mut self, _report: &str) {}
 }